@@ -31,7 +31,7 @@ fn benchmark_render_to_pixel_buffer_large_grid() {
         cells_json
     );
 
-    assert!(editor.load_document(doc_json));
+    assert!(editor.try_load_document(&doc_json).is_ok());
 
     // Force full redraw first
     editor.request_redraw();
@@ -12,7 +12,7 @@ declare global {
             exportAscii(): string;
             exportForCopy?: () => string;
             serializeDocument(): string;
-            loadDocument(json: string): boolean;
+            loadDocument(json: string): { code: string; message: string }[];
             clear(): void;
         } | null;
     }
@@ -118,7 +118,7 @@ test.describe('Copy / export fidelity', () => {
             const json = ed.serializeDocument();
             ed.clear();
             const afterClear = ed.exportAscii();
-            const loaded = ed.loadDocument(json);
+            const loaded = ed.loadDocument(json).length === 0;
             const after = ed.exportAscii();
             return { loaded, before, afterClear, after, same: before === after };
         });
@@ -79,6 +79,20 @@ check_forbidden_imports \
   "src/ui must not import wasm/render" \
   "UI config stays free of renderer and WASM bindings."
 
+# headless → no wasm (it must run natively)
+check_forbidden_imports \
+  "src/headless" \
+  'use crate::wasm(::|;)' \
+  "src/headless must not import wasm" \
+  "Headless rendering runs under cargo test; share drawing code through render."
+
+# wasm → no headless (headless is native-only and not built for wasm32)
+check_forbidden_imports \
+  "src/wasm" \
+  'crate::headless' \
+  "src/wasm must not use headless" \
+  "Share drawing code and palettes through render; headless is not compiled for wasm32."
+
 # web-sys / wasm-bindgen must not appear in core
 if [[ -d src/core ]]; then
   matches="$(rg -n 'wasm_bindgen|web_sys|js_sys' src/core --type rust 2>/dev/null || true)"
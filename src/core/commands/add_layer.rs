@@ -0,0 +1,52 @@
+//! Add layer command - creates a layer and its first content as one undo step.
+
+use super::{Command, DrawCommand, LayerTarget};
+use crate::core::grid::Grid;
+use crate::core::tools::DrawOp;
+
+/// Bottom entry of a new layer's history: undoing it clears the content and marks
+/// the layer for removal ([`LayerTarget::exists`]).
+pub struct AddLayerCommand {
+    content: DrawCommand,
+}
+
+impl AddLayerCommand {
+    /// Create a command adding a layer holding `ops`.
+    pub fn new(ops: Vec<DrawOp>, description: impl Into<String>) -> Self {
+        Self {
+            content: DrawCommand::with_description(ops, description),
+        }
+    }
+}
+
+impl Command for AddLayerCommand {
+    fn apply(&mut self, grid: &mut Grid) {
+        self.content.apply(grid);
+    }
+
+    fn undo(&mut self, grid: &mut Grid) {
+        self.content.undo(grid);
+    }
+
+    fn apply_layer(&mut self, layer: &mut LayerTarget) {
+        self.content.apply(layer.grid);
+        layer.exists = true;
+    }
+
+    fn undo_layer(&mut self, layer: &mut LayerTarget) {
+        self.content.undo(layer.grid);
+        layer.exists = false;
+    }
+
+    fn description(&self) -> &str {
+        self.content.description()
+    }
+
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn std::any::Any {
+        self
+    }
+}
@@ -1,6 +1,6 @@
 //! Composite command - groups multiple commands into one atomic operation.
 
-use super::Command;
+use super::{Command, LayerTarget};
 use crate::core::grid::Grid;
 
 /// A composite command that groups multiple commands together.
@@ -65,6 +65,24 @@ impl Command for CompositeCommand {
         }
     }
 
+    fn apply_layer(&mut self, layer: &mut LayerTarget) {
+        if !self.applied {
+            for cmd in &mut self.commands {
+                cmd.apply_layer(layer);
+            }
+            self.applied = true;
+        }
+    }
+
+    fn undo_layer(&mut self, layer: &mut LayerTarget) {
+        if self.applied {
+            for cmd in self.commands.iter_mut().rev() {
+                cmd.undo_layer(layer);
+            }
+            self.applied = false;
+        }
+    }
+
     fn description(&self) -> &str {
         &self.description
     }
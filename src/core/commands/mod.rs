@@ -1,14 +1,40 @@
 //! Commands module - Command pattern for undo/redo operations.
 
+mod add_layer;
 mod composite;
 mod draw;
+mod offset;
 
+pub use add_layer::AddLayerCommand;
 pub use composite::CompositeCommand;
 pub use draw::DrawCommand;
+pub use offset::{shift_offset, LayerOffsetCommand};
 
 use crate::core::cell::Cell;
 use crate::core::grid::Grid;
 
+/// A layer as commands see it: its cells and the properties stored beside them.
+pub struct LayerTarget<'a> {
+    /// The layer's cells
+    pub grid: &'a mut Grid,
+    /// Position of the grid origin on the canvas, in cells
+    pub offset: (i32, i32),
+    /// Whether the layer is on its page; cleared when the command that created
+    /// it is undone, and the owner then removes the layer.
+    pub exists: bool,
+}
+
+impl<'a> LayerTarget<'a> {
+    /// An existing layer with `grid` as its cells, placed at `offset`.
+    pub fn new(grid: &'a mut Grid, offset: (i32, i32)) -> Self {
+        Self {
+            grid,
+            offset,
+            exists: true,
+        }
+    }
+}
+
 /// Trait for undoable commands.
 pub trait Command {
     /// Apply the command to the grid.
@@ -17,6 +43,18 @@ pub trait Command {
     /// Undo the command on the grid.
     fn undo(&mut self, grid: &mut Grid);
 
+    /// Apply the command to a layer. Commands that change layer properties
+    /// override this; the default applies to the cells.
+    fn apply_layer(&mut self, layer: &mut LayerTarget) {
+        self.apply(layer.grid);
+    }
+
+    /// Undo the command on a layer. Commands that change layer properties
+    /// override this; the default undoes on the cells.
+    fn undo_layer(&mut self, layer: &mut LayerTarget) {
+        self.undo(layer.grid);
+    }
+
     /// Get a description of the command.
     fn description(&self) -> &str;
 
@@ -0,0 +1,110 @@
+//! Layer offset command - moves a whole layer without touching its cells.
+
+use super::{Command, LayerTarget};
+use crate::core::document::MAX_LAYER_OFFSET;
+use crate::core::grid::Grid;
+
+/// Moves a layer by `(dx, dy)` cells.
+///
+/// A layer's offset lives outside its grid, so only [`Command::apply_layer`] and
+/// [`Command::undo_layer`] move it; on a bare grid the command does nothing. Offsets
+/// stay within [`MAX_LAYER_OFFSET`].
+pub struct LayerOffsetCommand {
+    dx: i32,
+    dy: i32,
+}
+
+impl LayerOffsetCommand {
+    /// Create a command moving a layer by `(dx, dy)`, limited to the widest move
+    /// between two offsets in range.
+    pub fn new(dx: i32, dy: i32) -> Self {
+        let (max_x, max_y) = MAX_LAYER_OFFSET;
+        Self {
+            dx: dx.clamp(-2 * max_x, 2 * max_x),
+            dy: dy.clamp(-2 * max_y, 2 * max_y),
+        }
+    }
+
+    /// Offset change applied by this command.
+    pub fn delta(&self) -> (i32, i32) {
+        (self.dx, self.dy)
+    }
+}
+
+/// `offset` moved by `(dx, dy)`, kept within [`MAX_LAYER_OFFSET`].
+pub fn shift_offset((x, y): (i32, i32), dx: i32, dy: i32) -> (i32, i32) {
+    let (max_x, max_y) = MAX_LAYER_OFFSET;
+    (
+        x.saturating_add(dx).clamp(-max_x, max_x),
+        y.saturating_add(dy).clamp(-max_y, max_y),
+    )
+}
+
+impl Command for LayerOffsetCommand {
+    fn apply(&mut self, _grid: &mut Grid) {}
+
+    fn undo(&mut self, _grid: &mut Grid) {}
+
+    fn apply_layer(&mut self, layer: &mut LayerTarget) {
+        layer.offset = shift_offset(layer.offset, self.dx, self.dy);
+    }
+
+    fn undo_layer(&mut self, layer: &mut LayerTarget) {
+        layer.offset = shift_offset(layer.offset, -self.dx, -self.dy);
+    }
+
+    fn description(&self) -> &str {
+        "Move layer"
+    }
+
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn std::any::Any {
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::commands::{CompositeCommand, DrawCommand};
+    use crate::core::history::History;
+    use crate::core::tools::DrawOp;
+
+    #[test]
+    fn test_history_moves_the_layer_back_and_forth() {
+        let mut grid = Grid::new(4, 1);
+        let mut history = History::default();
+        let mut batch = CompositeCommand::new("Draw and move");
+        batch.add(Box::new(DrawCommand::new(vec![DrawOp::new(0, 0, 'x')])));
+        batch.add(Box::new(LayerOffsetCommand::new(2, 1)));
+        let mut layer = LayerTarget::new(&mut grid, (0, 0));
+        batch.apply_layer(&mut layer);
+        history.push(Box::new(batch));
+        assert_eq!(layer.offset, (2, 1));
+
+        assert!(history.undo_layer(&mut layer));
+        assert_eq!(layer.offset, (0, 0));
+        assert!(!layer.grid.get(0, 0).unwrap().is_visible());
+        assert!(history.redo_layer(&mut layer));
+        assert_eq!(layer.offset, (2, 1));
+        assert_eq!(layer.grid.get(0, 0).unwrap().ch, 'x');
+    }
+
+    #[test]
+    fn test_offsets_stay_in_range() {
+        let (max_x, max_y) = MAX_LAYER_OFFSET;
+        assert_eq!(shift_offset((max_x, 0), 1, i32::MIN), (max_x, -max_y));
+
+        let mut grid = Grid::new(1, 1);
+        let mut layer = LayerTarget::new(&mut grid, (0, 0));
+        let mut cmd = LayerOffsetCommand::new(i32::MIN, i32::MAX);
+        assert_eq!(cmd.delta(), (-2 * max_x, 2 * max_y));
+        cmd.apply_layer(&mut layer);
+        assert_eq!(layer.offset, (-max_x, max_y));
+        cmd.undo_layer(&mut layer);
+        assert_eq!(layer.offset, (max_x, -max_y));
+    }
+}
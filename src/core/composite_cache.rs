@@ -0,0 +1,194 @@
+//! Composite cache - the flattened layer stack, rebuilt only where something changed.
+//!
+//! The canvas is split into [`TILE_SIZE`]-square tiles. Each tile keeps a signature
+//! of what it was built from: every layer's compositing properties and the revisions
+//! of the layer-grid tiles it overlaps. [`CompositeCache::sync`] recomposites only
+//! the tiles whose signature changed, so an edit on one layer of a large, deep
+//! document costs one tile rather than the whole canvas.
+//!
+//! The cache holds both views of the stack: the cells to draw on screen (guides
+//! included, with whatever shows through translucent layers) and the export grid
+//! (guides left out).
+
+use crate::core::cell::Cell;
+use crate::core::compositing::{composite_cell, composite_stack, LayerView};
+use crate::core::grid::{Grid, TILE_SIZE};
+use smallvec::SmallVec;
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+
+/// Cells drawn at one position, bottom to top, with the index of their layer.
+pub type CellStack = SmallVec<[(Cell, usize); 2]>;
+
+/// Flattened layers of a canvas, kept per tile.
+#[derive(Clone, Debug)]
+pub struct CompositeCache {
+    /// Shown non-guide cells, as exported
+    export: Grid,
+    /// On-screen cell stacks, row-major
+    stacks: Vec<CellStack>,
+    /// Signature each tile was built from, row-major; `None` until built
+    signatures: Vec<Option<u64>>,
+}
+
+impl Default for CompositeCache {
+    fn default() -> Self {
+        Self {
+            export: Grid::new(0, 0),
+            stacks: Vec::new(),
+            signatures: Vec::new(),
+        }
+    }
+}
+
+impl CompositeCache {
+    /// An empty cache; the first [`sync`](Self::sync) builds every tile.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Bring the cache up to date with `layers` (bottom to top) on a
+    /// `width`×`height` canvas. Returns the number of tiles rebuilt.
+    pub fn sync(&mut self, layers: &[LayerView], width: usize, height: usize) -> usize {
+        if (self.export.width(), self.export.height()) != (width, height) {
+            self.export = Grid::new(width, height);
+            self.stacks = vec![CellStack::new(); width * height];
+            let (across, down) = self.export.tile_count();
+            self.signatures = vec![None; across * down];
+        }
+        let exported: Vec<LayerView> = layers.iter().filter(|v| !v.guide).copied().collect();
+        let across = self.export.tile_count().0;
+        let mut rebuilt = 0;
+        for (i, slot) in self.signatures.iter_mut().enumerate() {
+            let (tx, ty) = (i % across, i / across);
+            let signature = tile_signature(layers, tx, ty);
+            if *slot == Some(signature) {
+                continue;
+            }
+            *slot = Some(signature);
+            rebuilt += 1;
+            let (x0, y0) = (tx * TILE_SIZE, ty * TILE_SIZE);
+            for y in y0..(y0 + TILE_SIZE).min(height) {
+                for x in x0..(x0 + TILE_SIZE).min(width) {
+                    let (gx, gy) = (x as i32, y as i32);
+                    self.stacks[y * width + x] = composite_stack(layers, gx, gy);
+                    let cell = composite_cell(&exported, gx, gy).map(|(cell, _)| cell);
+                    self.export.set(gx, gy, cell.unwrap_or_default());
+                }
+            }
+        }
+        rebuilt
+    }
+
+    /// Cells to draw at `(x, y)`, bottom to top, with indices into the layers last
+    /// passed to [`sync`](Self::sync). Empty outside the canvas.
+    pub fn stack(&self, x: i32, y: i32) -> &[(Cell, usize)] {
+        if !self.export.in_bounds(x, y) {
+            return &[];
+        }
+        &self.stacks[self.export.index_of(x as usize, y as usize)]
+    }
+
+    /// The flattened non-guide layers, as every export sees them.
+    pub fn export_grid(&self) -> &Grid {
+        &self.export
+    }
+
+    /// Signature tile (`tx`, `ty`) was last built from; `None` outside the canvas.
+    /// Equal signatures mean the tile's cells are unchanged.
+    pub fn tile_signature(&self, tx: usize, ty: usize) -> Option<u64> {
+        let (across, down) = self.export.tile_count();
+        if tx >= across || ty >= down {
+            return None;
+        }
+        self.signatures[ty * across + tx]
+    }
+}
+
+/// Hash of everything the composite of canvas tile (`tx`, `ty`) depends on.
+fn tile_signature(layers: &[LayerView], tx: usize, ty: usize) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    let (x0, y0) = ((tx * TILE_SIZE) as i32, (ty * TILE_SIZE) as i32);
+    for layer in layers {
+        (layer.offset, layer.blend as u8, layer.transparent).hash(&mut hasher);
+        (layer.opacity.to_bits(), layer.guide).hash(&mut hasher);
+        let grid = layer.grid;
+        (grid.width(), grid.height()).hash(&mut hasher);
+        // Layer-grid tiles under this canvas tile
+        let (across, down) = grid.tile_count();
+        let tiles = |start: i32, offset: i32, count: usize| {
+            let first = (start - offset).div_euclid(TILE_SIZE as i32);
+            let last = (start - offset + TILE_SIZE as i32 - 1).div_euclid(TILE_SIZE as i32);
+            first.max(0)..=last.min(count as i32 - 1)
+        };
+        for gy in tiles(y0, layer.offset.1, down) {
+            for gx in tiles(x0, layer.offset.0, across) {
+                grid.tile_revision(gx as usize, gy as usize)
+                    .hash(&mut hasher);
+            }
+        }
+    }
+    hasher.finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::compositing::composite_grid;
+
+    #[test]
+    fn test_sync_rebuilds_only_changed_tiles() {
+        let (mut bottom, mut top) = (Grid::new(40, 20), Grid::new(40, 20));
+        bottom.set_char(1, 1, 'a');
+        top.set_char(1, 1, 'X');
+        let mut cache = CompositeCache::new();
+        let sync = |cache: &mut CompositeCache, bottom: &Grid, top: &Grid, guide: bool| {
+            let layers = [
+                LayerView::new(bottom),
+                LayerView {
+                    guide,
+                    opacity: 0.5,
+                    ..LayerView::new(top)
+                },
+            ];
+            let rebuilt = cache.sync(&layers, 40, 20);
+            let exported: Vec<_> = layers.iter().filter(|v| !v.guide).copied().collect();
+            let expected = composite_grid(&exported, 40, 20);
+            assert_eq!(cache.export_grid().cells(), expected.cells());
+            rebuilt
+        };
+        assert_eq!(sync(&mut cache, &bottom, &top, false), 6);
+        assert_eq!(sync(&mut cache, &bottom, &top, false), 0);
+        let chars: Vec<_> = cache.stack(1, 1).iter().map(|(c, i)| (c.ch, *i)).collect();
+        assert_eq!(chars, [('a', 0), ('X', 1)]);
+
+        top.set_char(35, 18, 'Y');
+        assert_eq!(sync(&mut cache, &bottom, &top, false), 1);
+        assert_eq!(cache.export_grid().get(35, 18).unwrap().ch, 'Y');
+        // A property change touches every tile; guides leave the export grid.
+        assert_eq!(sync(&mut cache, &bottom, &top, true), 6);
+        assert_eq!(cache.export_grid().get(1, 1).unwrap().ch, 'a');
+        assert!(cache.stack(40, 0).is_empty());
+    }
+
+    #[test]
+    fn test_offset_layers_track_the_tiles_they_cover() {
+        let (base, mut moved) = (Grid::new(32, 16), Grid::new(32, 16));
+        let mut cache = CompositeCache::new();
+        fn view(grid: &Grid) -> LayerView<'_> {
+            LayerView {
+                offset: (8, 0),
+                ..LayerView::new(grid)
+            }
+        }
+        cache.sync(&[LayerView::new(&base), view(&moved)], 32, 16);
+        // Cell (20, 0) of the layer lands on canvas (28, 0); only the second canvas
+        // tile overlaps the layer's second tile.
+        moved.set_char(20, 0, 'Z');
+        assert_eq!(
+            cache.sync(&[LayerView::new(&base), view(&moved)], 32, 16),
+            1
+        );
+        assert_eq!(cache.stack(28, 0)[0].0.ch, 'Z');
+    }
+}
@@ -0,0 +1,196 @@
+//! Layer compositing - how a stack of layers combines into what is shown and exported.
+//!
+//! Layers are composited bottom to top. By default a layer's blank (space) cells are
+//! see-through and any other character covers what is below. Each layer can change:
+//!
+//! - its offset on the canvas
+//! - its transparent character: cells holding it are see-through; when it is not a
+//!   space, spaces become opaque and blank out the layers below
+//! - its [`BlendMode`]
+//! - its opacity, which only affects on-screen rendering
+//!
+//! Guide layers take part in on-screen compositing only; callers building an export
+//! leave them out.
+
+use crate::core::cell::Cell;
+use crate::core::grid::Grid;
+use serde::{Deserialize, Serialize};
+use smallvec::SmallVec;
+
+/// How a layer's opaque cells combine with the layers below it.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BlendMode {
+    /// Covers whatever is below
+    #[default]
+    Normal,
+    /// Only fills cells that are still empty after the layers below
+    Under,
+}
+
+impl BlendMode {
+    /// Parse a blend mode name (`normal`, `under`).
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_lowercase().as_str() {
+            "normal" => Some(Self::Normal),
+            "under" => Some(Self::Under),
+            _ => None,
+        }
+    }
+
+    /// Lowercase name.
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Normal => "normal",
+            Self::Under => "under",
+        }
+    }
+}
+
+/// A visible layer as seen by the compositor.
+#[derive(Clone, Copy, Debug)]
+pub struct LayerView<'a> {
+    /// Layer content
+    pub grid: &'a Grid,
+    /// Position of the grid origin on the canvas
+    pub offset: (i32, i32),
+    /// Blend mode
+    pub blend: BlendMode,
+    /// See-through character
+    pub transparent: char,
+    /// On-screen opacity, 0.0 to 1.0
+    pub opacity: f32,
+    /// Guide layer: drawn dimmed on screen and left out of exports
+    pub guide: bool,
+}
+
+impl<'a> LayerView<'a> {
+    /// A normal, fully opaque layer at the canvas origin.
+    pub fn new(grid: &'a Grid) -> Self {
+        Self {
+            grid,
+            offset: (0, 0),
+            blend: BlendMode::Normal,
+            transparent: ' ',
+            opacity: 1.0,
+            guide: false,
+        }
+    }
+
+    /// This layer's opaque cell at canvas position `(x, y)`, if any.
+    pub fn cell_at(&self, x: i32, y: i32) -> Option<Cell> {
+        let cell = *self.grid.get(x - self.offset.0, y - self.offset.1)?;
+        let see_through = if self.transparent == ' ' {
+            !cell.is_visible()
+        } else {
+            cell.ch == self.transparent
+        };
+        (!see_through).then_some(cell)
+    }
+}
+
+/// Topmost cell shown at `(x, y)` and the index of the layer it comes from.
+pub fn composite_cell(layers: &[LayerView], x: i32, y: i32) -> Option<(Cell, usize)> {
+    let mut shown = None;
+    for (i, layer) in layers.iter().enumerate() {
+        let Some(cell) = layer.cell_at(x, y) else {
+            continue;
+        };
+        if layer.blend == BlendMode::Normal || shown.is_none() {
+            shown = Some((cell, i));
+        }
+    }
+    shown
+}
+
+/// Cells to draw at `(x, y)`, bottom to top: the shown cell, preceded by whatever
+/// shows through it while it is translucent.
+pub fn composite_stack(layers: &[LayerView], x: i32, y: i32) -> SmallVec<[(Cell, usize); 2]> {
+    let mut stack = SmallVec::new();
+    let mut end = layers.len();
+    while let Some((cell, i)) = composite_cell(&layers[..end], x, y) {
+        stack.push((cell, i));
+        if layers[i].opacity >= 1.0 {
+            break;
+        }
+        end = i;
+    }
+    stack.reverse();
+    stack
+}
+
+/// Flatten `layers` into one `width`×`height` grid.
+pub fn composite_grid(layers: &[LayerView], width: usize, height: usize) -> Grid {
+    let mut out = Grid::new(width, height);
+    for y in 0..height as i32 {
+        for x in 0..width as i32 {
+            if let Some((cell, _)) = composite_cell(layers, x, y) {
+                out.set(x, y, cell);
+            }
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn grid(rows: &[&str]) -> Grid {
+        let mut g = Grid::new(4, rows.len());
+        for (y, row) in rows.iter().enumerate() {
+            for (x, ch) in row.chars().enumerate() {
+                g.set_char(x as i32, y as i32, ch);
+            }
+        }
+        g
+    }
+
+    fn text(g: &Grid) -> String {
+        g.cells().iter().map(|c| c.ch).collect()
+    }
+
+    #[test]
+    fn test_normal_blend_topmost_wins() {
+        let (bottom, top) = (grid(&["abcd"]), grid(&[" X  "]));
+        let layers = [LayerView::new(&bottom), LayerView::new(&top)];
+        assert_eq!(text(&composite_grid(&layers, 4, 1)), "aXcd");
+    }
+
+    #[test]
+    fn test_under_only_fills_empty_cells() {
+        let (bottom, top) = (grid(&["a  d"]), grid(&["XXXX"]));
+        let under = LayerView {
+            blend: BlendMode::Under,
+            ..LayerView::new(&top)
+        };
+        let layers = [LayerView::new(&bottom), under];
+        assert_eq!(text(&composite_grid(&layers, 4, 1)), "aXXd");
+    }
+
+    #[test]
+    fn test_transparent_char_lets_spaces_blank_out() {
+        let (bottom, top) = (grid(&["abcd"]), grid(&[".  ."]));
+        let mask = LayerView {
+            transparent: '.',
+            ..LayerView::new(&top)
+        };
+        let layers = [LayerView::new(&bottom), mask];
+        assert_eq!(text(&composite_grid(&layers, 4, 1)), "a  d");
+    }
+
+    #[test]
+    fn test_offset_and_translucent_stack() {
+        let (bottom, top) = (grid(&["ab  "]), grid(&["X   "]));
+        let moved = LayerView {
+            offset: (1, 0),
+            opacity: 0.5,
+            ..LayerView::new(&top)
+        };
+        let layers = [LayerView::new(&bottom), moved];
+        assert_eq!(text(&composite_grid(&layers, 4, 1)), "aX  ");
+        let stack = composite_stack(&layers, 1, 0);
+        let chars: Vec<_> = stack.iter().map(|(c, i)| (c.ch, *i)).collect();
+        assert_eq!(chars, [('b', 0), ('X', 1)]);
+    }
+}
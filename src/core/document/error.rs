@@ -0,0 +1,161 @@
+//! Document load errors and non-fatal warnings.
+
+use super::{DOCUMENT_VERSION, MAX_CANVAS_HEIGHT, MAX_CANVAS_WIDTH, MAX_LAYERS, MAX_PAGES};
+use std::fmt;
+
+/// Why a document could not be loaded.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum DocumentError {
+    /// The input is not valid JSON
+    InvalidJson(String),
+    /// The `format` tag is missing or not `ascii-canvas`
+    UnknownFormat(String),
+    /// The `version` field is missing, zero or not a number
+    MissingVersion,
+    /// Written by a newer version of the editor
+    UnsupportedVersion(u32),
+    /// Valid JSON that does not match the schema
+    InvalidSchema(String),
+    /// Canvas is empty or larger than [`MAX_CANVAS_WIDTH`]×[`MAX_CANVAS_HEIGHT`]
+    InvalidCanvasSize {
+        /// Declared width
+        width: usize,
+        /// Declared height
+        height: usize,
+    },
+    /// A page has no layers
+    NoLayers,
+    /// More than [`MAX_LAYERS`] layers on one page
+    TooManyLayers(usize),
+    /// The document has no pages
+    NoPages,
+    /// More than [`MAX_PAGES`] pages
+    TooManyPages(usize),
+}
+
+impl DocumentError {
+    /// Stable machine-readable code (exposed to JS as `error.code`).
+    pub fn code(&self) -> &'static str {
+        match self {
+            Self::InvalidJson(_) => "invalid_json",
+            Self::UnknownFormat(_) => "unknown_format",
+            Self::MissingVersion => "missing_version",
+            Self::UnsupportedVersion(_) => "unsupported_version",
+            Self::InvalidSchema(_) => "invalid_schema",
+            Self::InvalidCanvasSize { .. } => "invalid_canvas_size",
+            Self::NoLayers => "no_layers",
+            Self::TooManyLayers(_) => "too_many_layers",
+            Self::NoPages => "no_pages",
+            Self::TooManyPages(_) => "too_many_pages",
+        }
+    }
+}
+
+impl fmt::Display for DocumentError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidJson(msg) => write!(f, "File is not valid JSON: {msg}"),
+            Self::UnknownFormat(tag) if tag.is_empty() => {
+                write!(f, "Not an ascii-canvas document (no format tag)")
+            }
+            Self::UnknownFormat(tag) => {
+                write!(f, "Not an ascii-canvas document (format {tag:?})")
+            }
+            Self::MissingVersion => write!(f, "Document has no valid version number"),
+            Self::UnsupportedVersion(v) => write!(
+                f,
+                "Document version {v} is newer than this editor supports (up to {DOCUMENT_VERSION}); please update the editor"
+            ),
+            Self::InvalidSchema(msg) => write!(f, "Document structure is invalid: {msg}"),
+            Self::InvalidCanvasSize { width, height } => write!(
+                f,
+                "Canvas {width}×{height} is outside the supported 1×1 to {MAX_CANVAS_WIDTH}×{MAX_CANVAS_HEIGHT}"
+            ),
+            Self::NoLayers => write!(f, "Document has no layers"),
+            Self::TooManyLayers(n) => {
+                write!(f, "Document has {n} layers; at most {MAX_LAYERS} are supported")
+            }
+            Self::NoPages => write!(f, "Document has no pages"),
+            Self::TooManyPages(n) => {
+                write!(f, "Document has {n} pages; at most {MAX_PAGES} are supported")
+            }
+        }
+    }
+}
+
+impl std::error::Error for DocumentError {}
+
+/// Non-fatal problem found while loading; the document still opens.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum LoadWarning {
+    /// Cells outside the canvas were dropped
+    CellsOutOfBounds {
+        /// Layer name
+        layer: String,
+        /// Number of dropped cells
+        count: usize,
+    },
+    /// `ch` strings longer than one character were cut to their first character
+    CharsTruncated {
+        /// Layer name
+        layer: String,
+        /// Number of truncated cells
+        count: usize,
+    },
+    /// Cells with an empty `ch` string were skipped
+    EmptyChars {
+        /// Layer name
+        layer: String,
+        /// Number of skipped cells
+        count: usize,
+    },
+    /// `active_layer` pointed past the last layer and was clamped
+    ActiveLayerClamped {
+        /// Index stored in the file
+        requested: usize,
+    },
+    /// `active_page` pointed past the last page and was clamped
+    ActivePageClamped {
+        /// Index stored in the file
+        requested: usize,
+    },
+}
+
+impl LoadWarning {
+    /// Stable machine-readable code (exposed to JS as `warning.code`).
+    pub fn code(&self) -> &'static str {
+        match self {
+            Self::CellsOutOfBounds { .. } => "cells_out_of_bounds",
+            Self::CharsTruncated { .. } => "chars_truncated",
+            Self::EmptyChars { .. } => "empty_chars",
+            Self::ActiveLayerClamped { .. } => "active_layer_clamped",
+            Self::ActivePageClamped { .. } => "active_page_clamped",
+        }
+    }
+}
+
+impl fmt::Display for LoadWarning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::CellsOutOfBounds { layer, count } => write!(
+                f,
+                "{count} cell(s) outside the canvas were dropped from layer {layer:?}"
+            ),
+            Self::CharsTruncated { layer, count } => write!(
+                f,
+                "{count} multi-character cell(s) in layer {layer:?} were truncated to one character"
+            ),
+            Self::EmptyChars { layer, count } => {
+                write!(f, "{count} empty cell(s) in layer {layer:?} were skipped")
+            }
+            Self::ActiveLayerClamped { requested } => write!(
+                f,
+                "Active layer {requested} does not exist; the top layer was selected"
+            ),
+            Self::ActivePageClamped { requested } => write!(
+                f,
+                "Active page {requested} does not exist; the last page was selected"
+            ),
+        }
+    }
+}
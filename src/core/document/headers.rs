@@ -0,0 +1,143 @@
+//! `=== page ===`, `=== group ===` and `=== layer ===` header lines of the text format.
+
+use super::{CanvasSize, DocumentLayer, DocumentPage, LayerGroup};
+use crate::core::compositing::BlendMode;
+use crate::core::grid::Grid;
+
+/// `=== page "Name" 80x40 active-layer 0 ===`
+pub(super) fn page_header(page: &DocumentPage) -> String {
+    format!(
+        "=== page {} {}x{} active-layer {} ===",
+        quote(&page.name),
+        page.canvas.width,
+        page.canvas.height,
+        page.active_layer
+    )
+}
+
+/// `=== group 1 "Name" hidden locked collapsed ===`
+pub(super) fn group_header(group: &LayerGroup) -> String {
+    let mut header = format!("=== group {} {}", group.id, quote(&group.name));
+    header.push_str(if group.visible { " visible" } else { " hidden" });
+    if group.locked {
+        header.push_str(" locked");
+    }
+    if group.collapsed {
+        header.push_str(" collapsed");
+    }
+    header.push_str(" ===");
+    header
+}
+
+/// `=== layer "Name" visible locked guide group 1 at 3,-2 opacity 0.5 blend under transparent "." ===`
+pub(super) fn layer_header(layer: &DocumentLayer) -> String {
+    let mut header = format!("=== layer {}", quote(&layer.name));
+    header.push_str(if layer.visible { " visible" } else { " hidden" });
+    if layer.locked {
+        header.push_str(" locked");
+    }
+    if layer.guide {
+        header.push_str(" guide");
+    }
+    if let Some(id) = layer.group {
+        header.push_str(&format!(" group {id}"));
+    }
+    if (layer.x, layer.y) != (0, 0) {
+        header.push_str(&format!(" at {},{}", layer.x, layer.y));
+    }
+    if layer.opacity < 1.0 {
+        header.push_str(&format!(" opacity {}", layer.opacity));
+    }
+    if layer.blend != BlendMode::Normal {
+        header.push_str(&format!(" blend {}", layer.blend.name()));
+    }
+    if layer.transparent != ' ' {
+        let ch = quote(&layer.transparent.to_string());
+        header.push_str(&format!(" transparent {ch}"));
+    }
+    header.push_str(" ===");
+    header
+}
+
+pub(super) fn read_size(value: &str) -> Result<CanvasSize, String> {
+    let (w, h) = value.split_once('x').ok_or("expected WxH")?;
+    Ok(CanvasSize {
+        width: w.trim().parse().map_err(|_| "bad width")?,
+        height: h.trim().parse().map_err(|_| "bad height")?,
+    })
+}
+
+/// Split a header into its leading quoted name and the rest.
+fn split_name(inner: &str) -> Option<(String, &str)> {
+    let mut stream = serde_json::Deserializer::from_str(inner).into_iter::<String>();
+    let name = stream.next()?.ok()?;
+    Some((name, &inner[stream.byte_offset()..]))
+}
+
+/// Parse a page header into an empty page.
+pub(super) fn read_page_header(line: &str) -> Option<DocumentPage> {
+    let inner = line.strip_prefix("=== page ")?.strip_suffix(" ===")?;
+    let (name, rest) = split_name(inner)?;
+    let mut words = rest.split_whitespace();
+    let canvas = read_size(words.next()?).ok()?;
+    let mut page = DocumentPage::new(&name, canvas.width, canvas.height);
+    if words.next() == Some("active-layer") {
+        page.active_layer = words.next()?.parse().ok()?;
+    }
+    Some(page)
+}
+
+/// Parse a group header.
+pub(super) fn read_group_header(line: &str) -> Option<LayerGroup> {
+    let inner = line.strip_prefix("=== group ")?.strip_suffix(" ===")?;
+    let (id, inner) = inner.split_once(' ')?;
+    let (name, rest) = split_name(inner)?;
+    let mut group = LayerGroup::new(id.parse().ok()?, &name);
+    for word in rest.split_whitespace() {
+        match word {
+            "visible" => group.visible = true,
+            "hidden" => group.visible = false,
+            "locked" => group.locked = true,
+            "collapsed" => group.collapsed = true,
+            _ => {}
+        }
+    }
+    Some(group)
+}
+
+/// Parse a layer header into an empty layer. Every flag after the visibility is optional.
+pub(super) fn read_layer_header(line: &str) -> Option<DocumentLayer> {
+    let inner = line.strip_prefix("=== layer ")?.strip_suffix(" ===")?;
+    let (name, rest) = split_name(inner)?;
+    let mut layer = DocumentLayer::from_grid(&name, true, false, &Grid::new(0, 0));
+    let mut words = rest.split_whitespace();
+    while let Some(word) = words.next() {
+        match word {
+            "visible" => layer.visible = true,
+            "hidden" => layer.visible = false,
+            "locked" => layer.locked = true,
+            "guide" => layer.guide = true,
+            "group" => layer.group = Some(words.next()?.parse().ok()?),
+            "at" => {
+                let (x, y) = words.next()?.split_once(',')?;
+                (layer.x, layer.y) = (x.parse().ok()?, y.parse().ok()?);
+            }
+            "opacity" => layer.opacity = words.next()?.parse::<f32>().ok()?.clamp(0.0, 1.0),
+            "blend" => layer.blend = BlendMode::from_name(words.next()?)?,
+            "transparent" => {
+                let mut chars = unquote(words.next()?).ok()?.chars().collect::<Vec<_>>();
+                layer.transparent = chars.pop().filter(|_| chars.is_empty())?;
+            }
+            _ => {}
+        }
+    }
+    Some(layer)
+}
+
+pub(super) fn quote(s: &str) -> String {
+    serde_json::to_string(s).unwrap_or_default()
+}
+
+pub(super) fn unquote(s: &str) -> Result<String, String> {
+    serde_json::from_str(s).map_err(|_| format!("expected a quoted string, got {s}"))
+}
@@ -0,0 +1,42 @@
+//! Schema migrations - upgrade older raw documents to the current version.
+
+use super::DOCUMENT_VERSION;
+
+/// Upgrade a raw document from `version` to [`DOCUMENT_VERSION`], one step at a time.
+pub(super) fn migrate(mut value: serde_json::Value, mut version: u32) -> serde_json::Value {
+    while version < DOCUMENT_VERSION {
+        value = match version {
+            1 => migrate_v1_to_v2(value),
+            2 => migrate_v2_to_v3(value),
+            _ => value,
+        };
+        version += 1;
+    }
+    value
+}
+
+/// v1 → v2: every v2 addition has a default (plain cells, default tool settings and
+/// viewport, empty metadata), so only the version tag changes.
+fn migrate_v1_to_v2(mut value: serde_json::Value) -> serde_json::Value {
+    if let Some(obj) = value.as_object_mut() {
+        obj.insert("version".to_string(), serde_json::Value::from(2));
+    }
+    value
+}
+
+/// v2 → v3: the top-level canvas, active layer and layers become the first page.
+fn migrate_v2_to_v3(mut value: serde_json::Value) -> serde_json::Value {
+    if let Some(obj) = value.as_object_mut() {
+        let mut page = serde_json::Map::new();
+        page.insert("name".to_string(), serde_json::Value::from("Page 1"));
+        for key in ["canvas", "active_layer", "layers"] {
+            if let Some(v) = obj.remove(key) {
+                page.insert(key.to_string(), v);
+            }
+        }
+        obj.insert("pages".to_string(), serde_json::Value::from(vec![page]));
+        obj.insert("active_page".to_string(), serde_json::Value::from(0));
+        obj.insert("version".to_string(), serde_json::Value::from(3));
+    }
+    value
+}
@@ -0,0 +1,423 @@
+//! Document model - the `.asc` file schema, version migration and validation.
+//!
+//! Version history:
+//! - v1: canvas size, active layer, and per-layer name/visibility/lock + cell characters
+//! - v2: adds cell styles, tool preferences, viewport and metadata (title, author,
+//!   timestamps)
+//! - v3: moves canvas, active layer and layers into named pages (artboards)
+//!
+//! Older files are upgraded step by step on load; files newer than
+//! [`DOCUMENT_VERSION`] are rejected with [`DocumentError::UnsupportedVersion`].
+
+use crate::core::tools::{BorderStyle, ToolId};
+use serde::{Deserialize, Serialize};
+
+mod error;
+mod headers;
+mod migrate;
+mod packed;
+mod page;
+mod text;
+
+pub use error::{DocumentError, LoadWarning};
+pub use packed::{CellEncoding, PackedCells};
+pub use page::{DocumentCell, DocumentLayer, DocumentPage, LayerGroup};
+
+/// Format tag stored in every document.
+pub const DOCUMENT_FORMAT: &str = "ascii-canvas";
+
+/// Schema version written by [`Document::to_json`].
+pub const DOCUMENT_VERSION: u32 = 3;
+
+/// Largest canvas a document may declare (matches the UI grid Apply caps).
+pub const MAX_CANVAS_WIDTH: usize = 400;
+/// Largest canvas a document may declare (matches the UI grid Apply caps).
+pub const MAX_CANVAS_HEIGHT: usize = 200;
+/// Furthest a layer's origin may sit from the canvas origin, in cells per axis.
+pub const MAX_LAYER_OFFSET: (i32, i32) = (MAX_CANVAS_WIDTH as i32, MAX_CANVAS_HEIGHT as i32);
+/// Upper bound on layers per page, to keep loading from exhausting memory.
+pub const MAX_LAYERS: usize = 32;
+/// Upper bound on pages, to keep loading from exhausting memory.
+pub const MAX_PAGES: usize = 16;
+
+/// Title, author and timestamps (milliseconds since the Unix epoch).
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DocumentMetadata {
+    /// Document title
+    pub title: String,
+    /// Author name
+    pub author: String,
+    /// Creation time
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub created: Option<u64>,
+    /// Last save time
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub modified: Option<u64>,
+}
+
+/// Canvas dimensions in cells.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CanvasSize {
+    /// Width in cells
+    pub width: usize,
+    /// Height in cells
+    pub height: usize,
+}
+
+/// Tool settings restored with the document.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ToolPreferences {
+    /// Active tool
+    pub tool: ToolId,
+    /// Border style for shapes
+    pub border_style: BorderStyle,
+    /// Eraser radius
+    pub eraser_size: i32,
+}
+
+impl Default for ToolPreferences {
+    fn default() -> Self {
+        Self {
+            tool: ToolId::default(),
+            border_style: BorderStyle::default(),
+            eraser_size: 1,
+        }
+    }
+}
+
+/// Zoom and pan at save time.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Viewport {
+    /// Zoom factor
+    pub zoom: f64,
+    /// Horizontal pan in pixels
+    pub pan_x: f64,
+    /// Vertical pan in pixels
+    pub pan_y: f64,
+}
+
+impl Default for Viewport {
+    fn default() -> Self {
+        Self {
+            zoom: 1.0,
+            pan_x: 0.0,
+            pan_y: 0.0,
+        }
+    }
+}
+
+/// A complete `.asc` document.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Document {
+    /// Always [`DOCUMENT_FORMAT`]
+    pub format: String,
+    /// Schema version
+    pub version: u32,
+    /// Title, author, timestamps
+    #[serde(default)]
+    pub metadata: DocumentMetadata,
+    /// Index of the active page
+    #[serde(default)]
+    pub active_page: usize,
+    /// Pages, in display order
+    pub pages: Vec<DocumentPage>,
+    /// Tool settings
+    #[serde(default)]
+    pub preferences: ToolPreferences,
+    /// Zoom and pan
+    #[serde(default)]
+    pub viewport: Viewport,
+}
+
+impl Document {
+    /// Create a current-version document with a single empty `width`×`height` page.
+    pub fn new(width: usize, height: usize) -> Self {
+        Self {
+            format: DOCUMENT_FORMAT.to_string(),
+            version: DOCUMENT_VERSION,
+            metadata: DocumentMetadata::default(),
+            active_page: 0,
+            pages: vec![DocumentPage::new("Page 1", width, height)],
+            preferences: ToolPreferences::default(),
+            viewport: Viewport::default(),
+        }
+    }
+
+    /// Serialize to JSON.
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).unwrap_or_else(|_| "{}".to_string())
+    }
+
+    /// Serialize to JSON with each layer's cells in `encoding`.
+    /// [`Document::from_json`] reads every encoding, so callers need not track which was used.
+    pub fn to_json_with(&self, encoding: CellEncoding) -> String {
+        if encoding == CellEncoding::Json {
+            return self.to_json();
+        }
+        let mut doc = self.clone();
+        for layer in doc.pages.iter_mut().flat_map(|p| &mut p.layers) {
+            layer.packed = Some(PackedCells::pack(&layer.cells, encoding));
+            layer.cells.clear();
+        }
+        doc.to_json()
+    }
+
+    /// Parse either serialized form: the plain-text format (starts with
+    /// `ascii-canvas <version>`) or JSON.
+    pub fn parse(input: &str) -> Result<Self, DocumentError> {
+        let trimmed = input.trim_start_matches('\u{feff}').trim_start();
+        if trimmed.starts_with(DOCUMENT_FORMAT) {
+            Self::from_text(trimmed)
+        } else {
+            Self::from_json(trimmed)
+        }
+    }
+
+    /// Parse a document of any supported version, migrating it to the current schema
+    /// and checking it against the page, canvas and layer limits.
+    pub fn from_json(json: &str) -> Result<Self, DocumentError> {
+        let value: serde_json::Value =
+            serde_json::from_str(json).map_err(|e| DocumentError::InvalidJson(e.to_string()))?;
+
+        let format = value.get("format").and_then(|v| v.as_str()).unwrap_or("");
+        if format != DOCUMENT_FORMAT {
+            return Err(DocumentError::UnknownFormat(format.to_string()));
+        }
+        let version = value
+            .get("version")
+            .and_then(|v| v.as_u64())
+            .ok_or(DocumentError::MissingVersion)?;
+        let version = u32::try_from(version).unwrap_or(u32::MAX);
+        if version == 0 {
+            return Err(DocumentError::MissingVersion);
+        }
+        if version > DOCUMENT_VERSION {
+            return Err(DocumentError::UnsupportedVersion(version));
+        }
+
+        let value = migrate::migrate(value, version);
+        let mut doc: Document = serde_json::from_value(value)
+            .map_err(|e| DocumentError::InvalidSchema(e.to_string()))?;
+        doc.validate()?;
+        for layer in doc.pages.iter_mut().flat_map(|p| &mut p.layers) {
+            if let Some(packed) = layer.packed.take() {
+                let cells = packed.unpack().map_err(|e| {
+                    DocumentError::InvalidSchema(format!("layer {:?}: {e}", layer.name))
+                })?;
+                layer.cells.extend(cells);
+            }
+        }
+        Ok(doc)
+    }
+
+    /// Index of the page to activate, clamped to the last page when out of range.
+    pub fn clamped_active_page(&self) -> (usize, Option<LoadWarning>) {
+        let last = self.pages.len().saturating_sub(1);
+        if self.active_page > last {
+            let warning = LoadWarning::ActivePageClamped {
+                requested: self.active_page,
+            };
+            (last, Some(warning))
+        } else {
+            (self.active_page, None)
+        }
+    }
+
+    fn validate(&self) -> Result<(), DocumentError> {
+        if self.pages.is_empty() {
+            return Err(DocumentError::NoPages);
+        }
+        if self.pages.len() > MAX_PAGES {
+            return Err(DocumentError::TooManyPages(self.pages.len()));
+        }
+        self.pages.iter().try_for_each(DocumentPage::validate)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::cell::{Cell, CellStyle};
+    use crate::core::grid::Grid;
+
+    const V1: &str = r#"{
+        "format":"ascii-canvas","version":1,
+        "canvas":{"width":10,"height":5},"active_layer":0,
+        "layers":[{"name":"Layer 1","visible":true,"locked":false,
+                   "cells":[{"x":1,"y":2,"ch":"A"}]}]
+    }"#;
+
+    #[test]
+    fn test_v1_migrates_to_current() {
+        let doc = Document::from_json(V1).unwrap();
+        assert_eq!(doc.version, DOCUMENT_VERSION);
+        assert_eq!(doc.metadata, DocumentMetadata::default());
+        assert_eq!(doc.preferences, ToolPreferences::default());
+        assert_eq!(doc.pages.len(), 1);
+        assert_eq!(doc.pages[0].name, "Page 1");
+        assert_eq!(
+            doc.pages[0].canvas,
+            CanvasSize {
+                width: 10,
+                height: 5
+            }
+        );
+        assert_eq!(doc.pages[0].layers[0].cells[0].style, CellStyle::NONE);
+    }
+
+    #[test]
+    fn test_round_trip_keeps_everything() {
+        let mut grid = Grid::new(10, 5);
+        grid.set(
+            3,
+            1,
+            Cell::with_style('B', CellStyle::BOLD | CellStyle::ITALIC),
+        );
+
+        let mut doc = Document::new(10, 5);
+        doc.metadata.title = "Flow".to_string();
+        doc.metadata.author = "Ada".to_string();
+        doc.metadata.created = Some(1_700_000_000_000);
+        doc.preferences.border_style = BorderStyle::Double;
+        doc.preferences.tool = ToolId::Arrow;
+        doc.viewport = Viewport {
+            zoom: 2.0,
+            pan_x: 12.0,
+            pan_y: -4.0,
+        };
+        doc.pages[0]
+            .layers
+            .push(DocumentLayer::from_grid("Top", true, true, &grid));
+        let mut second = DocumentPage::new("Containers", 30, 8);
+        second.layers.push(DocumentLayer::from_grid(
+            "Base",
+            true,
+            false,
+            &Grid::new(30, 8),
+        ));
+        doc.pages.push(second);
+        doc.active_page = 1;
+
+        let loaded = Document::from_json(&doc.to_json()).unwrap();
+        assert_eq!(loaded, doc);
+        let (grid, warnings) = loaded.pages[0].layers[0].to_grid(10, 5);
+        assert!(warnings.is_empty());
+        let cell = grid.get(3, 1).copied().unwrap();
+        assert_eq!(cell.style, CellStyle::BOLD | CellStyle::ITALIC);
+    }
+
+    #[test]
+    fn test_future_version_is_rejected() {
+        let json = V1.replace("\"version\":1", "\"version\":99");
+        let err = Document::from_json(&json).unwrap_err();
+        assert_eq!(err, DocumentError::UnsupportedVersion(99));
+        assert!(err.to_string().contains("newer"));
+    }
+
+    #[test]
+    fn test_rejects_bad_input() {
+        assert!(matches!(
+            Document::from_json("nope"),
+            Err(DocumentError::InvalidJson(_))
+        ));
+        assert!(matches!(
+            Document::from_json(r#"{"format":"other","version":1}"#),
+            Err(DocumentError::UnknownFormat(_))
+        ));
+        let huge = V1.replace("\"width\":10", "\"width\":50000");
+        assert!(matches!(
+            Document::from_json(&huge),
+            Err(DocumentError::InvalidCanvasSize { .. })
+        ));
+        let far = V1.replace("\"locked\":false", "\"locked\":false,\"x\":-2147483648");
+        assert_eq!(
+            Document::from_json(&far).unwrap_err().code(),
+            "invalid_schema"
+        );
+    }
+
+    #[test]
+    fn test_error_codes() {
+        let no_layers = V1.replace(
+            r#"[{"name":"Layer 1","visible":true,"locked":false,
+                   "cells":[{"x":1,"y":2,"ch":"A"}]}]"#,
+            "[]",
+        );
+        assert_eq!(
+            Document::from_json(&no_layers).unwrap_err().code(),
+            "no_layers"
+        );
+        assert_eq!(
+            Document::from_json(r#"{"format":"ascii-canvas"}"#)
+                .unwrap_err()
+                .code(),
+            "missing_version"
+        );
+        assert_eq!(DocumentError::TooManyLayers(40).code(), "too_many_layers");
+        let no_pages = r#"{"format":"ascii-canvas","version":3,"pages":[]}"#;
+        assert_eq!(
+            Document::from_json(no_pages).unwrap_err().code(),
+            "no_pages"
+        );
+    }
+
+    #[test]
+    fn test_active_page_is_clamped() {
+        let mut doc = Document::from_json(V1).unwrap();
+        doc.active_page = 4;
+        let (active, warning) = doc.clamped_active_page();
+        assert_eq!(active, 0);
+        assert_eq!(warning.unwrap().code(), "active_page_clamped");
+    }
+
+    #[test]
+    fn test_load_warnings() {
+        let json = V1
+            .replace(
+                r#"{"x":1,"y":2,"ch":"A"}"#,
+                r#"{"x":1,"y":2,"ch":"AB"},{"x":99,"y":0,"ch":"C"},{"x":0,"y":0,"ch":""}"#,
+            )
+            .replace(r#""active_layer":0"#, r#""active_layer":5"#);
+        let doc = Document::from_json(&json).unwrap();
+
+        let (grid, warnings) = doc.pages[0].layers[0].to_grid(10, 5);
+        assert_eq!(grid.get(1, 2).unwrap().ch, 'A');
+        let codes: Vec<_> = warnings.iter().map(LoadWarning::code).collect();
+        assert_eq!(
+            codes,
+            ["cells_out_of_bounds", "chars_truncated", "empty_chars"]
+        );
+
+        let (active, warning) = doc.pages[0].clamped_active_layer();
+        assert_eq!(active, 0);
+        assert_eq!(warning.unwrap().code(), "active_layer_clamped");
+    }
+
+    #[test]
+    fn test_packed_encodings_match_json() {
+        let mut grid = Grid::new(40, 10);
+        for x in 0..40 {
+            grid.set(x, 0, Cell::with_style('═', CellStyle::BOLD));
+            grid.set_char(x, 9, if x % 2 == 0 { '1' } else { '~' });
+        }
+        let mut doc = Document::new(40, 10);
+        let layers = &mut doc.pages[0].layers;
+        layers.push(DocumentLayer::from_grid("Base", true, false, &grid));
+        layers.push(DocumentLayer::from_grid(
+            "Empty",
+            false,
+            true,
+            &Grid::new(40, 10),
+        ));
+
+        let json = Document::from_json(&doc.to_json()).unwrap();
+        for encoding in [CellEncoding::Rle, CellEncoding::RleDeflate] {
+            let text = doc.to_json_with(encoding);
+            assert!(text.len() < doc.to_json().len());
+            assert_eq!(Document::from_json(&text).unwrap(), json);
+        }
+    }
+}
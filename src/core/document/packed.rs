@@ -0,0 +1,298 @@
+//! Packed cell encodings - a compact alternative to the per-cell JSON list.
+//!
+//! The `rle` form writes each layer as text rows, one row per grid line, with runs of
+//! identical characters collapsed:
+//!
+//! - `<count><char>` repeats a character (`┌4─┐` is `┌────┐`); a count of 1 is omitted
+//! - blanks are written as spaces and trailing blanks / empty trailing rows are dropped;
+//!   only `' '` is a blank, other whitespace (tab, NBSP, U+3000) is kept as a cell
+//! - `~<hh>` sets the `CellStyle` bits, as two hex digits, for the following runs
+//!   (reset on each row)
+//! - `\` escapes the next character (used for digits, `~`, `\` and control characters)
+//!
+//! `rle+deflate` additionally deflates the RLE text and stores it as base64.
+
+use super::{DocumentCell, MAX_CANVAS_HEIGHT, MAX_CANVAS_WIDTH};
+use crate::core::cell::CellStyle;
+use base64::Engine;
+use serde::{Deserialize, Serialize};
+
+/// Upper bound on inflated RLE text, so a crafted payload cannot exhaust memory.
+const MAX_INFLATED_BYTES: usize = 16 * 1024 * 1024;
+
+/// How a layer's cells are written.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum CellEncoding {
+    /// One `{x, y, ch}` object per cell
+    #[default]
+    #[serde(rename = "json")]
+    Json,
+    /// Run-length encoded text rows
+    #[serde(rename = "rle")]
+    Rle,
+    /// Run-length encoded, deflated and base64-encoded
+    #[serde(rename = "rle+deflate")]
+    RleDeflate,
+}
+
+impl CellEncoding {
+    /// Parse an encoding name (`json`, `rle`, `rle+deflate`).
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_lowercase().as_str() {
+            "" | "json" => Some(Self::Json),
+            "rle" => Some(Self::Rle),
+            "rle+deflate" | "compact" => Some(Self::RleDeflate),
+            _ => None,
+        }
+    }
+}
+
+/// A layer's cells in one of the packed encodings.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PackedCells {
+    /// Encoding of `data`
+    pub encoding: CellEncoding,
+    /// Encoded cells
+    pub data: String,
+}
+
+impl PackedCells {
+    /// Pack `cells` with `encoding` (`Json` is treated as `Rle`).
+    pub fn pack(cells: &[DocumentCell], encoding: CellEncoding) -> Self {
+        let rle = encode_rle(cells);
+        match encoding {
+            CellEncoding::RleDeflate => Self {
+                encoding,
+                data: base64::engine::general_purpose::STANDARD
+                    .encode(miniz_oxide::deflate::compress_to_vec(rle.as_bytes(), 6)),
+            },
+            _ => Self {
+                encoding: CellEncoding::Rle,
+                data: rle,
+            },
+        }
+    }
+
+    /// Decode back into a cell list.
+    pub fn unpack(&self) -> Result<Vec<DocumentCell>, String> {
+        match self.encoding {
+            CellEncoding::Json => Err("packed data cannot use the json encoding".to_string()),
+            CellEncoding::Rle => decode_rle(&self.data),
+            CellEncoding::RleDeflate => {
+                let bytes = base64::engine::general_purpose::STANDARD
+                    .decode(self.data.trim())
+                    .map_err(|e| format!("bad base64: {e}"))?;
+                let inflated =
+                    miniz_oxide::inflate::decompress_to_vec_with_limit(&bytes, MAX_INFLATED_BYTES)
+                        .map_err(|e| format!("bad deflate stream: {e}"))?;
+                let text = String::from_utf8(inflated).map_err(|_| "not UTF-8".to_string())?;
+                decode_rle(&text)
+            }
+        }
+    }
+}
+
+fn encode_rle(cells: &[DocumentCell]) -> String {
+    let mut rows: Vec<Vec<(usize, char, CellStyle)>> = Vec::new();
+    for cell in cells {
+        let (Ok(x), Ok(y)) = (usize::try_from(cell.x), usize::try_from(cell.y)) else {
+            continue;
+        };
+        let Some(ch) = cell.ch.chars().next() else {
+            continue;
+        };
+        if rows.len() <= y {
+            rows.resize_with(y + 1, Vec::new);
+        }
+        rows[y].push((x, ch, cell.style));
+    }
+
+    let mut out = String::new();
+    for (y, row) in rows.iter_mut().enumerate() {
+        if y > 0 {
+            out.push('\n');
+        }
+        row.sort_by_key(|&(x, _, _)| x);
+        row.dedup_by_key(|&mut (x, _, _)| x);
+
+        let mut style = CellStyle::NONE;
+        let mut next_x = 0;
+        let mut i = 0;
+        while i < row.len() {
+            let (x, ch, cell_style) = row[i];
+            if x > next_x {
+                push_run(&mut out, x - next_x, ' ');
+            }
+            let mut len = 1;
+            while i + len < row.len() {
+                let (nx, nch, nstyle) = row[i + len];
+                if nx != x + len || nch != ch || nstyle != cell_style {
+                    break;
+                }
+                len += 1;
+            }
+            if cell_style != style {
+                out.push_str(&format!("~{:02x}", cell_style.bits()));
+                style = cell_style;
+            }
+            push_run(&mut out, len, ch);
+            next_x = x + len;
+            i += len;
+        }
+    }
+    out
+}
+
+fn push_run(out: &mut String, count: usize, ch: char) {
+    if count > 1 {
+        out.push_str(&count.to_string());
+    }
+    if ch.is_ascii_digit() || ch == '~' || ch == '\\' || ch.is_control() {
+        out.push('\\');
+    }
+    out.push(ch);
+}
+
+fn decode_rle(text: &str) -> Result<Vec<DocumentCell>, String> {
+    let mut cells = Vec::new();
+    let (mut x, mut y) = (0usize, 0usize);
+    let mut style = CellStyle::NONE;
+    let mut count: Option<usize> = None;
+    let mut chars = text.chars();
+
+    while let Some(c) = chars.next() {
+        let ch = match c {
+            '\n' => {
+                (x, y, style, count) = (0, y + 1, CellStyle::NONE, None);
+                if y >= MAX_CANVAS_HEIGHT {
+                    return Err(format!("more than {MAX_CANVAS_HEIGHT} rows"));
+                }
+                continue;
+            }
+            '~' => {
+                let mut hex = || chars.next().and_then(|h| h.to_digit(16));
+                let (Some(high), Some(low)) = (hex(), hex()) else {
+                    return Err("bad style marker".to_string());
+                };
+                style = CellStyle::from_bits_truncate((high * 16 + low) as u8);
+                continue;
+            }
+            '0'..='9' => {
+                let digit = c as usize - '0' as usize;
+                let n = count.unwrap_or(0) * 10 + digit;
+                if n > MAX_CANVAS_WIDTH {
+                    return Err(format!("run longer than {MAX_CANVAS_WIDTH}"));
+                }
+                count = Some(n);
+                continue;
+            }
+            '\\' => chars.next().ok_or("dangling escape")?,
+            c => c,
+        };
+
+        let run = count.take().unwrap_or(1);
+        if x + run > MAX_CANVAS_WIDTH {
+            return Err(format!("row {y} wider than {MAX_CANVAS_WIDTH}"));
+        }
+        if ch != ' ' {
+            cells.extend((x..x + run).map(|cx| DocumentCell {
+                x: cx as i32,
+                y: y as i32,
+                ch: ch.to_string(),
+                style,
+            }));
+        }
+        x += run;
+    }
+    Ok(cells)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cell(x: i32, y: i32, ch: char, style: CellStyle) -> DocumentCell {
+        DocumentCell {
+            x,
+            y,
+            ch: ch.to_string(),
+            style,
+        }
+    }
+
+    #[test]
+    fn test_rle_text_is_readable() {
+        let cells: Vec<_> = "┌────┐"
+            .chars()
+            .enumerate()
+            .map(|(x, ch)| cell(x as i32 + 2, 1, ch, CellStyle::NONE))
+            .collect();
+        let packed = PackedCells::pack(&cells, CellEncoding::Rle);
+        assert_eq!(packed.data, "\n2 ┌4─┐");
+        assert_eq!(packed.unpack().unwrap(), cells);
+    }
+
+    #[test]
+    fn test_rle_escapes_and_styles() {
+        let cells = vec![
+            cell(0, 0, '7', CellStyle::NONE),
+            cell(1, 0, '7', CellStyle::NONE),
+            cell(2, 0, '~', CellStyle::BOLD),
+            cell(3, 0, '\\', CellStyle::BOLD | CellStyle::UNDERLINE),
+        ];
+        let packed = PackedCells::pack(&cells, CellEncoding::Rle);
+        assert_eq!(packed.data, "2\\7~01\\~~05\\\\");
+        assert_eq!(packed.unpack().unwrap(), cells);
+    }
+
+    #[test]
+    fn test_rle_keeps_whitespace_cells_other_than_space() {
+        let styles = CellStyle::all();
+        let cells = vec![
+            cell(0, 0, '\u{a0}', CellStyle::NONE),
+            cell(1, 0, '\u{3000}', CellStyle::HIGHLIGHT),
+            cell(3, 0, '\t', styles),
+            cell(4, 0, 'x', styles),
+        ];
+        let packed = PackedCells::pack(&cells, CellEncoding::Rle);
+        assert_eq!(packed.data, "\u{a0}~08\u{3000} ~0f\\\tx");
+        assert_eq!(packed.unpack().unwrap(), cells);
+        let deflated = PackedCells::pack(&cells, CellEncoding::RleDeflate);
+        assert_eq!(deflated.unpack().unwrap(), cells);
+    }
+
+    #[test]
+    fn test_deflate_round_trip() {
+        let cells: Vec<_> = (0..200)
+            .flat_map(|y| (0..400).map(move |x| (x, y)))
+            .map(|(x, y)| {
+                cell(
+                    x,
+                    y,
+                    if (x + y) % 7 == 0 { '#' } else { '.' },
+                    CellStyle::NONE,
+                )
+            })
+            .collect();
+        let packed = PackedCells::pack(&cells, CellEncoding::RleDeflate);
+        assert!(packed.data.len() < 20_000, "{} bytes", packed.data.len());
+        assert_eq!(packed.unpack().unwrap(), cells);
+    }
+
+    #[test]
+    fn test_rejects_hostile_input() {
+        let rle = |data: &str| PackedCells {
+            encoding: CellEncoding::Rle,
+            data: data.to_string(),
+        };
+        assert!(rle("99999999x").unpack().is_err());
+        assert!(rle("~").unpack().is_err());
+        assert!(rle("~1x").unpack().is_err());
+        assert!(rle("\\").unpack().is_err());
+        let bad = PackedCells {
+            encoding: CellEncoding::RleDeflate,
+            data: "not base64!".to_string(),
+        };
+        assert!(bad.unpack().is_err());
+    }
+}
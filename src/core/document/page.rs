@@ -0,0 +1,266 @@
+//! Pages, layers and cells - the drawing content of a document.
+
+use super::{
+    CanvasSize, DocumentError, LoadWarning, PackedCells, MAX_CANVAS_HEIGHT, MAX_CANVAS_WIDTH,
+    MAX_LAYERS, MAX_LAYER_OFFSET,
+};
+use crate::core::cell::{Cell, CellStyle};
+use crate::core::compositing::BlendMode;
+use crate::core::grid::Grid;
+use serde::{Deserialize, Serialize};
+
+/// A single non-empty cell.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DocumentCell {
+    /// Column
+    pub x: i32,
+    /// Row
+    pub y: i32,
+    /// Character (stored as a string for readability)
+    pub ch: String,
+    /// Style flags, omitted when plain
+    #[serde(default, skip_serializing_if = "CellStyle::is_empty")]
+    pub style: CellStyle,
+}
+
+/// A layer and its sparse cell content.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct DocumentLayer {
+    /// Layer name
+    pub name: String,
+    /// Whether the layer is shown
+    #[serde(default = "default_true")]
+    pub visible: bool,
+    /// Whether the layer rejects edits
+    #[serde(default)]
+    pub locked: bool,
+    /// Reference or notes layer: shown dimmed, never exported
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub guide: bool,
+    /// Id of the [`LayerGroup`] the layer belongs to
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub group: Option<u32>,
+    /// Column of the layer's origin on the canvas
+    #[serde(default, skip_serializing_if = "is_zero")]
+    pub x: i32,
+    /// Row of the layer's origin on the canvas
+    #[serde(default, skip_serializing_if = "is_zero")]
+    pub y: i32,
+    /// On-screen opacity, 0.0 to 1.0
+    #[serde(default = "default_opacity", skip_serializing_if = "is_opaque")]
+    pub opacity: f32,
+    /// How the layer combines with the layers below
+    #[serde(default, skip_serializing_if = "is_normal")]
+    pub blend: BlendMode,
+    /// See-through character
+    #[serde(default = "default_transparent", skip_serializing_if = "is_space")]
+    pub transparent: char,
+    /// Non-empty cells
+    #[serde(default)]
+    pub cells: Vec<DocumentCell>,
+    /// Cells in a compact encoding; unpacked into `cells` by [`Document::from_json`]
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub packed: Option<PackedCells>,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+fn is_false(v: &bool) -> bool {
+    !*v
+}
+
+fn is_zero(v: &i32) -> bool {
+    *v == 0
+}
+
+fn default_opacity() -> f32 {
+    1.0
+}
+
+fn is_opaque(v: &f32) -> bool {
+    *v >= 1.0
+}
+
+fn is_normal(v: &BlendMode) -> bool {
+    *v == BlendMode::Normal
+}
+
+fn default_transparent() -> char {
+    ' '
+}
+
+fn is_space(v: &char) -> bool {
+    *v == ' '
+}
+
+impl DocumentLayer {
+    /// Snapshot the visible cells of `grid`.
+    pub fn from_grid(name: &str, visible: bool, locked: bool, grid: &Grid) -> Self {
+        let cells = grid
+            .iter_with_coords()
+            .filter(|(_, _, cell)| cell.is_visible())
+            .map(|(x, y, cell)| DocumentCell {
+                x,
+                y,
+                ch: cell.ch.to_string(),
+                style: cell.style,
+            })
+            .collect();
+        Self {
+            name: name.to_string(),
+            visible,
+            locked,
+            guide: false,
+            group: None,
+            x: 0,
+            y: 0,
+            opacity: 1.0,
+            blend: BlendMode::Normal,
+            transparent: ' ',
+            cells,
+            packed: None,
+        }
+    }
+
+    /// Rebuild the layer's grid, reporting cells that could not be restored exactly.
+    pub fn to_grid(&self, width: usize, height: usize) -> (Grid, Vec<LoadWarning>) {
+        let mut grid = Grid::new(width, height);
+        let (mut out_of_bounds, mut truncated, mut empty) = (0, 0, 0);
+        for cell in &self.cells {
+            let mut chars = cell.ch.chars();
+            let Some(ch) = chars.next() else {
+                empty += 1;
+                continue;
+            };
+            if chars.next().is_some() {
+                truncated += 1;
+            }
+            if !grid.set(cell.x, cell.y, Cell::with_style(ch, cell.style)) {
+                out_of_bounds += 1;
+            }
+        }
+
+        let layer = || self.name.clone();
+        let mut warnings = Vec::new();
+        if out_of_bounds > 0 {
+            warnings.push(LoadWarning::CellsOutOfBounds {
+                layer: layer(),
+                count: out_of_bounds,
+            });
+        }
+        if truncated > 0 {
+            warnings.push(LoadWarning::CharsTruncated {
+                layer: layer(),
+                count: truncated,
+            });
+        }
+        if empty > 0 {
+            warnings.push(LoadWarning::EmptyChars {
+                layer: layer(),
+                count: empty,
+            });
+        }
+        (grid, warnings)
+    }
+}
+
+/// A named group of adjacent layers. Its visibility and lock apply to every member.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LayerGroup {
+    /// Id referenced by [`DocumentLayer::group`], unique within a page
+    pub id: u32,
+    /// Group name
+    pub name: String,
+    /// Whether members are shown
+    #[serde(default = "default_true")]
+    pub visible: bool,
+    /// Whether members reject edits
+    #[serde(default)]
+    pub locked: bool,
+    /// Whether the layer panel shows the group folded
+    #[serde(default)]
+    pub collapsed: bool,
+}
+
+impl LayerGroup {
+    /// A visible, unlocked, expanded group.
+    pub fn new(id: u32, name: &str) -> Self {
+        Self {
+            id,
+            name: name.to_string(),
+            visible: true,
+            locked: false,
+            collapsed: false,
+        }
+    }
+}
+
+/// A named page (artboard) with its own canvas size and layer stack.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct DocumentPage {
+    /// Page name
+    pub name: String,
+    /// Canvas dimensions
+    pub canvas: CanvasSize,
+    /// Index of the active layer
+    #[serde(default)]
+    pub active_layer: usize,
+    /// Layer groups; members are listed via [`DocumentLayer::group`]
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub groups: Vec<LayerGroup>,
+    /// Layers, bottom to top
+    pub layers: Vec<DocumentLayer>,
+}
+
+impl DocumentPage {
+    /// Create an empty `width`×`height` page.
+    pub fn new(name: &str, width: usize, height: usize) -> Self {
+        Self {
+            name: name.to_string(),
+            canvas: CanvasSize { width, height },
+            active_layer: 0,
+            groups: Vec::new(),
+            layers: Vec::new(),
+        }
+    }
+
+    /// Index of the layer to activate, clamped to the last layer when out of range.
+    pub fn clamped_active_layer(&self) -> (usize, Option<LoadWarning>) {
+        let last = self.layers.len().saturating_sub(1);
+        if self.active_layer > last {
+            let warning = LoadWarning::ActiveLayerClamped {
+                requested: self.active_layer,
+            };
+            (last, Some(warning))
+        } else {
+            (self.active_layer, None)
+        }
+    }
+
+    pub(super) fn validate(&self) -> Result<(), DocumentError> {
+        let CanvasSize { width, height } = self.canvas;
+        if width == 0 || height == 0 || width > MAX_CANVAS_WIDTH || height > MAX_CANVAS_HEIGHT {
+            return Err(DocumentError::InvalidCanvasSize { width, height });
+        }
+        if self.layers.is_empty() {
+            return Err(DocumentError::NoLayers);
+        }
+        if self.layers.len() > MAX_LAYERS {
+            return Err(DocumentError::TooManyLayers(self.layers.len()));
+        }
+        let (max_x, max_y) = MAX_LAYER_OFFSET;
+        if let Some(layer) = self
+            .layers
+            .iter()
+            .find(|l| l.x.unsigned_abs() > max_x as u32 || l.y.unsigned_abs() > max_y as u32)
+        {
+            return Err(DocumentError::InvalidSchema(format!(
+                "layer {:?} is offset more than {max_x}×{max_y} cells",
+                layer.name
+            )));
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,438 @@
+//! Plain-text document format - a diff-friendly alternative to `.asc` JSON.
+//!
+//! ```text
+//! ascii-canvas 3
+//! title: "Flow"
+//! active-page: 0
+//! border-style: Double
+//!
+//! === page "Context" 80x40 active-layer 0 ===
+//!
+//! === layer "Layer 1" visible ===
+//! ┌──┐
+//! │  │
+//! └──┘
+//! === styles ===
+//! .11
+//! === end ===
+//! ```
+//!
+//! The header is `key: value` lines (strings are JSON-quoted; unknown keys are
+//! ignored). Each page starts with a `page` line giving its name, size and active
+//! layer; its layers follow. Each layer's cells follow as literal rows with trailing blanks trimmed, so
+//! `git diff` shows the picture lines that changed. An optional `styles` block holds
+//! one hex digit of `CellStyle` bits per cell (`.` for plain). Content rows that
+//! would start with `===` or `\` are prefixed with `\`.
+//!
+//! v2 text files have no `page` lines; their `canvas:` and `active-layer:` header
+//! fields describe a single implicit page.
+
+use super::headers::{
+    group_header, layer_header, page_header, quote, read_group_header, read_layer_header,
+    read_page_header, read_size, unquote,
+};
+use super::{
+    Document, DocumentCell, DocumentError, DocumentLayer, DocumentPage, DOCUMENT_FORMAT,
+    DOCUMENT_VERSION,
+};
+use crate::core::cell::CellStyle;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+const END: &str = "=== end ===";
+const STYLES: &str = "=== styles ===";
+
+impl Document {
+    /// Serialize to the plain-text format.
+    pub fn to_text(&self) -> String {
+        let mut out = format!("{DOCUMENT_FORMAT} {DOCUMENT_VERSION}\n");
+        let mut field = |key: &str, value: String| {
+            out.push_str(key);
+            out.push_str(": ");
+            out.push_str(&value);
+            out.push('\n');
+        };
+        let meta = &self.metadata;
+        if !meta.title.is_empty() {
+            field("title", quote(&meta.title));
+        }
+        if !meta.author.is_empty() {
+            field("author", quote(&meta.author));
+        }
+        if let Some(t) = meta.created {
+            field("created", t.to_string());
+        }
+        if let Some(t) = meta.modified {
+            field("modified", t.to_string());
+        }
+        field("active-page", self.active_page.to_string());
+        field("tool", bare(&self.preferences.tool));
+        field("border-style", bare(&self.preferences.border_style));
+        field("eraser-size", self.preferences.eraser_size.to_string());
+        field("zoom", self.viewport.zoom.to_string());
+        field(
+            "pan",
+            format!("{} {}", self.viewport.pan_x, self.viewport.pan_y),
+        );
+
+        for page in &self.pages {
+            out.push('\n');
+            out.push_str(&page_header(page));
+            out.push('\n');
+            for group in &page.groups {
+                out.push_str(&group_header(group));
+                out.push('\n');
+            }
+            for layer in &page.layers {
+                out.push('\n');
+                write_layer(&mut out, layer);
+            }
+        }
+        out
+    }
+
+    /// Parse the plain-text format.
+    pub fn from_text(text: &str) -> Result<Self, DocumentError> {
+        let mut lines = text.lines().enumerate().peekable();
+
+        let (_, first) = lines.next().unwrap_or((0, ""));
+        let mut words = first.split_whitespace();
+        let format = words.next().unwrap_or("");
+        if format != DOCUMENT_FORMAT {
+            return Err(DocumentError::UnknownFormat(format.to_string()));
+        }
+        let version: u32 = match words.next().map(str::parse) {
+            Some(Ok(v)) if v > 0 => v,
+            _ => return Err(DocumentError::MissingVersion),
+        };
+        if version > DOCUMENT_VERSION {
+            return Err(DocumentError::UnsupportedVersion(version));
+        }
+
+        let mut doc = Document::new(0, 0);
+        // v2 files describe their single page in the header.
+        let mut implicit = doc.pages.pop();
+        while let Some((n, line)) = lines.next_if(|(_, l)| !l.starts_with("===")) {
+            let Some((key, value)) = line.split_once(':') else {
+                continue;
+            };
+            let page = implicit.as_mut().ok_or_else(|| invalid(n, "no page"))?;
+            read_field(&mut doc, page, key.trim(), value.trim())
+                .map_err(|e| invalid(n, &format!("{}: {e}", key.trim())))?;
+        }
+
+        while let Some((n, line)) = lines.next() {
+            if line.trim().is_empty() {
+                continue;
+            }
+            if let Some(page) = read_page_header(line) {
+                implicit = None;
+                doc.pages.push(page);
+                continue;
+            }
+            if let Some(group) = read_group_header(line) {
+                let page = doc
+                    .pages
+                    .last_mut()
+                    .ok_or_else(|| invalid(n, "group outside a page"))?;
+                page.groups.push(group);
+                continue;
+            }
+            let layer = read_layer_header(line).ok_or_else(|| invalid(n, "expected a layer"))?;
+            if let Some(page) = implicit.take() {
+                doc.pages.push(page);
+            }
+            let page = doc
+                .pages
+                .last_mut()
+                .ok_or_else(|| invalid(n, "layer outside a page"))?;
+            page.layers.push(read_layer_body(layer, &mut lines)?);
+        }
+
+        doc.validate()?;
+        Ok(doc)
+    }
+}
+
+fn write_layer(out: &mut String, layer: &DocumentLayer) {
+    let mut rows: Vec<Vec<(char, CellStyle)>> = Vec::new();
+    for cell in &layer.cells {
+        let (Ok(x), Ok(y)) = (usize::try_from(cell.x), usize::try_from(cell.y)) else {
+            continue;
+        };
+        let Some(ch) = cell.ch.chars().next() else {
+            continue;
+        };
+        if rows.len() <= y {
+            rows.resize_with(y + 1, Vec::new);
+        }
+        if rows[y].len() <= x {
+            rows[y].resize(x + 1, (' ', CellStyle::NONE));
+        }
+        rows[y][x] = (ch, cell.style);
+    }
+
+    out.push_str(&layer_header(layer));
+    out.push('\n');
+    for row in &rows {
+        let text: String = row.iter().map(|&(ch, _)| ch).collect();
+        if text.starts_with("===") || text.starts_with('\\') {
+            out.push('\\');
+        }
+        out.push_str(text.trim_end());
+        out.push('\n');
+    }
+
+    let styled = rows
+        .iter()
+        .rposition(|row| row.iter().any(|(_, s)| !s.is_empty()));
+    if let Some(last) = styled {
+        out.push_str(STYLES);
+        out.push('\n');
+        for row in &rows[..=last] {
+            let text: String = row
+                .iter()
+                .map(|(_, s)| match s.bits() {
+                    0 => '.',
+                    b => char::from_digit(u32::from(b), 16).unwrap_or('.'),
+                })
+                .collect();
+            out.push_str(text.trim_end_matches('.'));
+            out.push('\n');
+        }
+    }
+    out.push_str(END);
+    out.push('\n');
+}
+
+fn read_field(
+    doc: &mut Document,
+    page: &mut DocumentPage,
+    key: &str,
+    value: &str,
+) -> Result<(), String> {
+    let number = |v: &str| v.parse::<f64>().map_err(|e| e.to_string());
+    match key {
+        "title" => doc.metadata.title = unquote(value)?,
+        "author" => doc.metadata.author = unquote(value)?,
+        "created" => doc.metadata.created = Some(value.parse().map_err(|_| "bad time")?),
+        "modified" => doc.metadata.modified = Some(value.parse().map_err(|_| "bad time")?),
+        "canvas" => page.canvas = read_size(value)?,
+        "active-layer" => page.active_layer = value.parse().map_err(|_| "bad index")?,
+        "active-page" => doc.active_page = value.parse().map_err(|_| "bad index")?,
+        "tool" => doc.preferences.tool = from_bare(value)?,
+        "border-style" => doc.preferences.border_style = from_bare(value)?,
+        "eraser-size" => doc.preferences.eraser_size = value.parse().map_err(|_| "bad size")?,
+        "zoom" => doc.viewport.zoom = number(value)?,
+        "pan" => {
+            let (x, y) = value.split_once(' ').ok_or("expected X Y")?;
+            doc.viewport.pan_x = number(x.trim())?;
+            doc.viewport.pan_y = number(y.trim())?;
+        }
+        _ => {}
+    }
+    Ok(())
+}
+
+fn read_layer_body<'a>(
+    mut layer: DocumentLayer,
+    lines: &mut impl Iterator<Item = (usize, &'a str)>,
+) -> Result<DocumentLayer, DocumentError> {
+    let mut rows: Vec<&str> = Vec::new();
+    let mut styles: Vec<Vec<CellStyle>> = Vec::new();
+    let mut in_styles = false;
+    let mut closed = false;
+    for (n, line) in lines.by_ref() {
+        if line == END {
+            closed = true;
+            break;
+        }
+        // Content rows starting with `===` are escaped, so this is the next section.
+        if read_page_header(line).is_some()
+            || read_group_header(line).is_some()
+            || read_layer_header(line).is_some()
+        {
+            return Err(invalid(
+                n,
+                &format!("layer {:?} is missing \"{END}\"", layer.name),
+            ));
+        }
+        if line == STYLES {
+            in_styles = true;
+        } else if in_styles {
+            let row = line
+                .chars()
+                .map(|c| match c {
+                    '.' | ' ' => Some(CellStyle::NONE),
+                    c => c
+                        .to_digit(16)
+                        .map(|bits| CellStyle::from_bits_truncate(bits as u8)),
+                })
+                .collect::<Option<Vec<_>>>()
+                .ok_or_else(|| invalid(n, "bad style digit"))?;
+            styles.push(row);
+        } else {
+            rows.push(line.strip_prefix('\\').unwrap_or(line));
+        }
+    }
+    if !closed {
+        return Err(DocumentError::InvalidSchema(format!(
+            "layer {:?} is missing \"{END}\"",
+            layer.name
+        )));
+    }
+
+    for (y, row) in rows.iter().enumerate() {
+        let row_styles = styles.get(y);
+        for (x, ch) in row.chars().enumerate() {
+            if ch.is_whitespace() {
+                continue;
+            }
+            layer.cells.push(DocumentCell {
+                x: x as i32,
+                y: y as i32,
+                ch: ch.to_string(),
+                style: row_styles
+                    .and_then(|r| r.get(x).copied())
+                    .unwrap_or(CellStyle::NONE),
+            });
+        }
+    }
+    Ok(layer)
+}
+
+fn invalid(line: usize, msg: &str) -> DocumentError {
+    DocumentError::InvalidSchema(format!("line {}: {msg}", line + 1))
+}
+
+/// Unit enum variant name without JSON quotes (`Double`).
+fn bare<T: Serialize>(value: &T) -> String {
+    let json = serde_json::to_string(value).unwrap_or_default();
+    json.trim_matches('"').to_string()
+}
+
+fn from_bare<T: DeserializeOwned>(value: &str) -> Result<T, String> {
+    serde_json::from_str(&quote(value)).map_err(|_| format!("unknown value {value}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::cell::Cell;
+    use crate::core::compositing::BlendMode;
+    use crate::core::document::{CanvasSize, LayerGroup};
+    use crate::core::grid::Grid;
+    use crate::core::tools::BorderStyle;
+
+    fn sample() -> Document {
+        let mut grid = Grid::new(12, 6);
+        for (x, ch) in "┌──┐ ===".chars().enumerate() {
+            grid.set_char(x as i32, 0, ch);
+        }
+        grid.set(1, 1, Cell::with_style('A', CellStyle::BOLD));
+        for (x, ch) in "=== 42".chars().enumerate() {
+            grid.set_char(x as i32, 3, ch);
+        }
+
+        let mut doc = Document::new(12, 6);
+        doc.metadata.title = "Flow: \"v2\"".to_string();
+        doc.metadata.created = Some(1_760_000_000_000);
+        doc.preferences.border_style = BorderStyle::Rounded;
+        doc.viewport.pan_x = -3.5;
+        let layers = &mut doc.pages[0].layers;
+        layers.push(DocumentLayer::from_grid("Base", true, false, &grid));
+        layers.push(DocumentLayer {
+            x: 3,
+            y: -2,
+            opacity: 0.5,
+            blend: BlendMode::Under,
+            transparent: '"',
+            guide: true,
+            ..DocumentLayer::from_grid("Notes \"draft\"", false, true, &Grid::new(12, 6))
+        });
+        let mut second = DocumentPage::new("Components", 20, 4);
+        second.active_layer = 1;
+        second.groups.push(LayerGroup {
+            visible: false,
+            collapsed: true,
+            ..LayerGroup::new(7, "Parts \"v1\"")
+        });
+        for name in ["A", "B"] {
+            second.layers.push(DocumentLayer {
+                group: Some(7),
+                ..DocumentLayer::from_grid(name, true, false, &Grid::new(20, 4))
+            });
+        }
+        doc.pages.push(second);
+        doc.active_page = 1;
+        doc
+    }
+
+    #[test]
+    fn test_text_round_trip_matches_json() {
+        let doc = sample();
+        let text = doc.to_text();
+        assert!(text.contains("\n┌──┐ ===\n"));
+        assert!(text.contains("\n\\=== 42\n"));
+        assert!(text.contains("\n=== group 7 \"Parts \\\"v1\\\"\" hidden collapsed ===\n"));
+        assert!(text.contains("=== layer \"A\" visible group 7 ==="));
+        assert!(text.contains(" hidden locked guide at 3,-2 "));
+        assert_eq!(Document::from_text(&text).unwrap(), doc);
+        assert_eq!(Document::parse(&text).unwrap(), doc);
+    }
+
+    #[test]
+    fn test_edit_changes_one_row() {
+        let doc = sample();
+        let mut edited = doc.clone();
+        edited.pages[0].layers[0].cells.push(DocumentCell {
+            x: 8,
+            y: 1,
+            ch: "B".to_string(),
+            style: CellStyle::NONE,
+        });
+        let before: Vec<_> = doc.to_text().lines().map(str::to_string).collect();
+        let after: Vec<_> = edited.to_text().lines().map(str::to_string).collect();
+        let changed = before.iter().zip(&after).filter(|(a, b)| a != b).count();
+        assert_eq!(changed, 1);
+    }
+
+    #[test]
+    fn test_text_errors() {
+        let text = sample().to_text();
+        let future = text.replacen("ascii-canvas 3", "ascii-canvas 9", 1);
+        assert_eq!(
+            Document::from_text(&future).unwrap_err().code(),
+            "unsupported_version"
+        );
+        let truncated = text.trim_end().trim_end_matches(END);
+        assert_eq!(
+            Document::from_text(truncated).unwrap_err().code(),
+            "invalid_schema"
+        );
+
+        // A layer left open must not swallow the next one as rows.
+        let unclosed = "ascii-canvas 3\n=== page \"P\" 4x2 ===\n=== layer \"A\" visible ===\n\
+                        ab\n=== layer \"B\" visible ===\ncd\n=== end ===\n";
+        let err = Document::from_text(unclosed).unwrap_err();
+        assert_eq!(err.code(), "invalid_schema");
+        assert!(err.to_string().contains("line 5"), "{err}");
+    }
+
+    #[test]
+    fn test_v2_text_loads_as_one_page() {
+        let text = "ascii-canvas 2\ncanvas: 6x2\nactive-layer: 0\n\n\
+                    === layer \"L\" visible ===\n┌──┐\n=== end ===\n";
+        let doc = Document::from_text(text).unwrap();
+        assert_eq!(doc.pages.len(), 1);
+        assert_eq!(doc.pages[0].name, "Page 1");
+        assert_eq!(
+            doc.pages[0].canvas,
+            CanvasSize {
+                width: 6,
+                height: 2
+            }
+        );
+        assert_eq!(doc.pages[0].layers[0].cells.len(), 4);
+    }
+}
@@ -2,11 +2,27 @@
 //!
 //! Uses row-major ordering for O(1) index calculations.
 //! Supports efficient iteration, modification, and boundary checking.
+//!
+//! Every grid also tracks a revision per [`TILE_SIZE`]-square tile of cells, so
+//! caches built from it (see [`crate::core::composite_cache`]) can tell which tiles
+//! changed. Revisions come from one global counter: two tiles with the same revision
+//! hold the same cells, even across clones.
 
 use super::cell::Cell;
 use serde::{Deserialize, Serialize};
 use smallvec::SmallVec;
 use std::ops::{Index, IndexMut};
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// Side, in cells, of the square tiles that revisions are tracked for.
+pub const TILE_SIZE: usize = 16;
+
+/// Source of grid and tile revisions.
+static NEXT_REVISION: AtomicU64 = AtomicU64::new(1);
+
+fn next_revision() -> u64 {
+    NEXT_REVISION.fetch_add(1, Ordering::Relaxed)
+}
 
 /// A 2D grid of ASCII cells using flat storage.
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -17,6 +33,12 @@ pub struct Grid {
     width: usize,
     /// Grid height in cells
     height: usize,
+    /// Revision of every tile not listed in `tile_revisions`
+    #[serde(skip, default = "next_revision")]
+    revision: u64,
+    /// Per-tile revisions, row-major; empty until a single tile changes
+    #[serde(skip)]
+    tile_revisions: Vec<u64>,
 }
 
 impl Grid {
@@ -26,6 +48,8 @@ impl Grid {
             cells: vec![Cell::default(); width * height],
             width,
             height,
+            revision: next_revision(),
+            tile_revisions: Vec::new(),
         }
     }
 
@@ -36,6 +60,8 @@ impl Grid {
             cells,
             width,
             height,
+            revision: next_revision(),
+            tile_revisions: Vec::new(),
         }
     }
 
@@ -96,6 +122,7 @@ impl Grid {
     pub fn get_mut(&mut self, x: i32, y: i32) -> Option<&mut Cell> {
         if self.in_bounds(x, y) {
             let idx = self.index_of(x as usize, y as usize);
+            self.touch(idx);
             Some(&mut self.cells[idx])
         } else {
             None
@@ -137,6 +164,7 @@ impl Grid {
 
     /// Clear all cells in the grid.
     pub fn clear(&mut self) {
+        self.touch_all();
         for cell in &mut self.cells {
             cell.clear();
         }
@@ -152,6 +180,7 @@ impl Grid {
 
     /// Get mutable iterator over all cells with coordinates.
     pub fn iter_mut_with_coords(&mut self) -> impl Iterator<Item = (i32, i32, &mut Cell)> {
+        self.touch_all();
         let width = self.width;
         self.cells.iter_mut().enumerate().map(move |(i, cell)| {
             let x = i % width;
@@ -187,6 +216,41 @@ impl Grid {
         self.cells = new_cells;
         self.width = new_width;
         self.height = new_height;
+        self.touch_all();
+    }
+
+    /// Number of tiles across and down.
+    pub fn tile_count(&self) -> (usize, usize) {
+        (
+            (self.width + TILE_SIZE - 1) / TILE_SIZE,
+            (self.height + TILE_SIZE - 1) / TILE_SIZE,
+        )
+    }
+
+    /// Revision of tile (`tx`, `ty`). It changes whenever a cell in the tile may
+    /// have changed.
+    pub fn tile_revision(&self, tx: usize, ty: usize) -> u64 {
+        let across = self.tile_count().0;
+        self.tile_revisions
+            .get(ty * across + tx)
+            .copied()
+            .unwrap_or(self.revision)
+    }
+
+    /// Give the tile holding cell `index` a new revision.
+    fn touch(&mut self, index: usize) {
+        let (across, down) = self.tile_count();
+        if self.tile_revisions.is_empty() {
+            self.tile_revisions = vec![self.revision; across * down];
+        }
+        let (x, y) = self.coords_of(index);
+        self.tile_revisions[(y / TILE_SIZE) * across + x / TILE_SIZE] = next_revision();
+    }
+
+    /// Give every tile a new revision.
+    fn touch_all(&mut self) {
+        self.revision = next_revision();
+        self.tile_revisions.clear();
     }
 
     /// Fill a rectangular region with a character.
@@ -234,6 +298,7 @@ impl Index<usize> for Grid {
 
 impl IndexMut<usize> for Grid {
     fn index_mut(&mut self, index: usize) -> &mut Self::Output {
+        self.touch(index);
         &mut self.cells[index]
     }
 }
@@ -249,6 +314,7 @@ impl Index<(usize, usize)> for Grid {
 impl IndexMut<(usize, usize)> for Grid {
     fn index_mut(&mut self, (x, y): (usize, usize)) -> &mut Self::Output {
         let idx = self.index_of(x, y);
+        self.touch(idx);
         &mut self.cells[idx]
     }
 }
@@ -298,6 +364,24 @@ mod tests {
         assert!(grid.get(6, 2).unwrap().is_empty());
     }
 
+    #[test]
+    fn test_tile_revisions_follow_edits() {
+        let mut grid = Grid::new(40, 20);
+        assert_eq!(grid.tile_count(), (3, 2));
+        let before: Vec<_> = (0..3).map(|tx| grid.tile_revision(tx, 1)).collect();
+        let copy = grid.clone();
+        grid.set_char(20, 18, 'X');
+        assert_eq!(grid.tile_revision(0, 1), before[0]);
+        assert_ne!(grid.tile_revision(1, 1), before[1]);
+        assert_eq!(copy.tile_revision(1, 1), before[1]);
+        grid.clear();
+        assert_ne!(grid.tile_revision(0, 1), before[0]);
+        assert_ne!(
+            Grid::new(40, 20).tile_revision(0, 0),
+            copy.tile_revision(0, 0)
+        );
+    }
+
     #[test]
     fn test_grid_indexing_out_of_bounds() {
         let grid = Grid::new(10, 10);
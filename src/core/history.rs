@@ -1,6 +1,6 @@
 //! History module - Undo/Redo system using a ring buffer.
 
-use crate::core::commands::Command;
+use crate::core::commands::{Command, LayerTarget};
 use crate::core::grid::Grid;
 use std::collections::VecDeque;
 
@@ -66,9 +66,22 @@ impl History {
     }
 
     /// Perform undo, returning the command to the redo stack.
+    ///
+    /// Only the cells are restored; use [`undo_layer`](Self::undo_layer) when the
+    /// history belongs to a layer whose properties commands may change.
     pub fn undo(&mut self, grid: &mut Grid) -> bool {
+        self.undo_layer(&mut LayerTarget::new(grid, (0, 0)))
+    }
+
+    /// Perform redo, returning the command to the undo stack.
+    pub fn redo(&mut self, grid: &mut Grid) -> bool {
+        self.redo_layer(&mut LayerTarget::new(grid, (0, 0)))
+    }
+
+    /// Undo on a layer, its properties included.
+    pub fn undo_layer(&mut self, layer: &mut LayerTarget) -> bool {
         if let Some(mut cmd) = self.undo_stack.pop_back() {
-            cmd.undo(grid);
+            cmd.undo_layer(layer);
             self.redo_stack.push_back(cmd);
             true
         } else {
@@ -76,10 +89,10 @@ impl History {
         }
     }
 
-    /// Perform redo, returning the command to the undo stack.
-    pub fn redo(&mut self, grid: &mut Grid) -> bool {
+    /// Redo on a layer, its properties included.
+    pub fn redo_layer(&mut self, layer: &mut LayerTarget) -> bool {
         if let Some(mut cmd) = self.redo_stack.pop_back() {
-            cmd.apply(grid);
+            cmd.apply_layer(layer);
             self.undo_stack.push_back(cmd);
             true
         } else {
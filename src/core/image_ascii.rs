@@ -0,0 +1,391 @@
+//! Image-to-ASCII conversion - maps an RGBA pixel buffer onto grid characters.
+//!
+//! The source image is box-sampled into a `target_width` × N cell raster, where N
+//! accounts for the cell aspect ratio so the result is not vertically stretched.
+//! Each cell's "ink" (darkness weighted by alpha) selects a character from a
+//! density ramp, optionally with Floyd–Steinberg dithering. Edge mode runs a Sobel
+//! filter over the cell raster and draws `- | / \` along strong gradients.
+
+use crate::core::grid::Grid;
+
+/// Classic density ramp, lightest to darkest.
+pub const STANDARD_RAMP: &str = " .:-=+*#%@";
+
+/// Unicode block-shade ramp, lightest to darkest.
+pub const BLOCK_RAMP: &str = " ░▒▓█";
+
+/// Largest grid a single conversion may produce (matches the canvas caps).
+const MAX_TARGET_WIDTH: usize = 400;
+const MAX_TARGET_HEIGHT: usize = 200;
+
+/// Character ramp used to map ink density to glyphs.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DensityRamp {
+    chars: Vec<char>,
+}
+
+impl Default for DensityRamp {
+    fn default() -> Self {
+        Self::standard()
+    }
+}
+
+impl DensityRamp {
+    /// The ` .:-=+*#%@` ramp.
+    pub fn standard() -> Self {
+        Self::custom(STANDARD_RAMP).unwrap_or(Self { chars: vec![' '] })
+    }
+
+    /// The ` ░▒▓█` block-shade ramp.
+    pub fn blocks() -> Self {
+        Self::custom(BLOCK_RAMP).unwrap_or(Self { chars: vec![' '] })
+    }
+
+    /// Build a ramp from a string ordered lightest to darkest.
+    /// Returns `None` if fewer than two characters are given.
+    pub fn custom(chars: &str) -> Option<Self> {
+        let chars: Vec<char> = chars.chars().filter(|c| !c.is_control()).collect();
+        if chars.len() < 2 {
+            return None;
+        }
+        Some(Self { chars })
+    }
+
+    /// Resolve a ramp by name (`standard`, `blocks`), falling back to treating the
+    /// input as a custom ramp string.
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_lowercase().as_str() {
+            "" | "standard" | "ascii" => Some(Self::standard()),
+            "blocks" | "block" | "shades" => Some(Self::blocks()),
+            _ => Self::custom(name),
+        }
+    }
+
+    /// Characters in the ramp, lightest first.
+    pub fn chars(&self) -> &[char] {
+        &self.chars
+    }
+
+    /// Number of distinct levels.
+    pub fn levels(&self) -> usize {
+        self.chars.len()
+    }
+
+    /// Character for a given ink value in `[0, 1]`.
+    pub fn char_for(&self, ink: f32) -> char {
+        self.chars[self.level_for(ink)]
+    }
+
+    fn level_for(&self, ink: f32) -> usize {
+        let max = (self.chars.len() - 1) as f32;
+        (ink.clamp(0.0, 1.0) * max).round() as usize
+    }
+}
+
+/// How pixel data is turned into characters.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum ImageMode {
+    /// Map luminance directly onto the density ramp.
+    #[default]
+    Luminance,
+    /// Luminance mapping with Floyd–Steinberg error diffusion.
+    Dithered,
+    /// Draw directional strokes along detected edges.
+    Edges,
+}
+
+impl ImageMode {
+    /// Parse a mode name (`luminance`, `dither`/`dithered`, `edges`).
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_lowercase().as_str() {
+            "" | "luminance" | "density" => Some(Self::Luminance),
+            "dither" | "dithered" => Some(Self::Dithered),
+            "edge" | "edges" => Some(Self::Edges),
+            _ => None,
+        }
+    }
+}
+
+/// Options for [`image_to_grid`].
+#[derive(Clone, Debug)]
+pub struct ImageToAsciiOptions {
+    /// Output width in cells
+    pub target_width: usize,
+    /// Character ramp (lightest to darkest)
+    pub ramp: DensityRamp,
+    /// Conversion mode
+    pub mode: ImageMode,
+    /// Cell width divided by cell height (e.g. `char_width / line_height`)
+    pub cell_aspect: f64,
+    /// Minimum normalized gradient magnitude that counts as an edge
+    pub edge_threshold: f32,
+}
+
+impl Default for ImageToAsciiOptions {
+    fn default() -> Self {
+        Self {
+            target_width: 80,
+            ramp: DensityRamp::standard(),
+            mode: ImageMode::Luminance,
+            cell_aspect: 0.5,
+            edge_threshold: 0.25,
+        }
+    }
+}
+
+/// Compute the output grid size for an image of `width` × `height` pixels.
+pub fn target_size(width: usize, height: usize, options: &ImageToAsciiOptions) -> (usize, usize) {
+    if width == 0 || height == 0 {
+        return (0, 0);
+    }
+    let cols = options.target_width.clamp(1, MAX_TARGET_WIDTH);
+    let aspect = if options.cell_aspect.is_finite() && options.cell_aspect > 0.0 {
+        options.cell_aspect
+    } else {
+        0.5
+    };
+    let rows = (height as f64 / width as f64 * cols as f64 * aspect).round() as usize;
+    (cols, rows.clamp(1, MAX_TARGET_HEIGHT))
+}
+
+/// Convert an RGBA buffer (`width * height * 4` bytes) to a grid of characters.
+///
+/// Returns `None` if the buffer size does not match the dimensions.
+pub fn image_to_grid(
+    rgba: &[u8],
+    width: usize,
+    height: usize,
+    options: &ImageToAsciiOptions,
+) -> Option<Grid> {
+    if width == 0 || height == 0 || rgba.len() != width.checked_mul(height)?.checked_mul(4)? {
+        return None;
+    }
+
+    let (cols, rows) = target_size(width, height, options);
+    let ink = sample_ink(rgba, width, height, cols, rows);
+    let mut grid = Grid::new(cols, rows);
+
+    match options.mode {
+        ImageMode::Luminance => {
+            for (i, &value) in ink.iter().enumerate() {
+                grid[i].ch = options.ramp.char_for(value);
+            }
+        }
+        ImageMode::Dithered => {
+            for (i, level) in dither(&ink, cols, rows, &options.ramp)
+                .into_iter()
+                .enumerate()
+            {
+                grid[i].ch = options.ramp.chars()[level];
+            }
+        }
+        ImageMode::Edges => {
+            for (i, ch) in edges(&ink, cols, rows, options.edge_threshold)
+                .into_iter()
+                .enumerate()
+            {
+                grid[i].ch = ch;
+            }
+        }
+    }
+
+    Some(grid)
+}
+
+/// Box-sample the image into `cols` × `rows` ink values in `[0, 1]`.
+///
+/// Ink is darkness weighted by alpha, so transparent pixels count as blank paper.
+fn sample_ink(rgba: &[u8], width: usize, height: usize, cols: usize, rows: usize) -> Vec<f32> {
+    let mut out = Vec::with_capacity(cols * rows);
+    for row in 0..rows {
+        let y0 = row * height / rows;
+        let y1 = ((row + 1) * height / rows).max(y0 + 1).min(height);
+        for col in 0..cols {
+            let x0 = col * width / cols;
+            let x1 = ((col + 1) * width / cols).max(x0 + 1).min(width);
+
+            let mut sum = 0.0f32;
+            for y in y0..y1 {
+                for x in x0..x1 {
+                    let idx = (y * width + x) * 4;
+                    sum += pixel_ink(&rgba[idx..idx + 4]);
+                }
+            }
+            out.push(sum / ((x1 - x0) * (y1 - y0)) as f32);
+        }
+    }
+    out
+}
+
+/// Rec. 709 luminance inverted into ink, scaled by alpha.
+fn pixel_ink(px: &[u8]) -> f32 {
+    let luminance = (0.2126 * px[0] as f32 + 0.7152 * px[1] as f32 + 0.0722 * px[2] as f32) / 255.0;
+    (1.0 - luminance) * (px[3] as f32 / 255.0)
+}
+
+/// Floyd–Steinberg error diffusion onto the ramp levels.
+fn dither(ink: &[f32], cols: usize, rows: usize, ramp: &DensityRamp) -> Vec<usize> {
+    let mut values = ink.to_vec();
+    let mut levels = vec![0usize; ink.len()];
+    let max = (ramp.levels() - 1) as f32;
+
+    for y in 0..rows {
+        for x in 0..cols {
+            let i = y * cols + x;
+            let level = ramp.level_for(values[i]);
+            levels[i] = level;
+            let error = values[i] - level as f32 / max;
+
+            let mut spread = |dx: isize, dy: usize, weight: f32| {
+                let nx = x as isize + dx;
+                let ny = y + dy;
+                if nx >= 0 && (nx as usize) < cols && ny < rows {
+                    values[ny * cols + nx as usize] += error * weight;
+                }
+            };
+            spread(1, 0, 7.0 / 16.0);
+            spread(-1, 1, 3.0 / 16.0);
+            spread(0, 1, 5.0 / 16.0);
+            spread(1, 1, 1.0 / 16.0);
+        }
+    }
+    levels
+}
+
+/// Sobel edge detection over the cell raster, emitting stroke characters.
+fn edges(ink: &[f32], cols: usize, rows: usize, threshold: f32) -> Vec<char> {
+    let at = |x: isize, y: isize| -> f32 {
+        let cx = x.clamp(0, cols as isize - 1) as usize;
+        let cy = y.clamp(0, rows as isize - 1) as usize;
+        ink[cy * cols + cx]
+    };
+
+    let mut out = Vec::with_capacity(cols * rows);
+    for y in 0..rows as isize {
+        for x in 0..cols as isize {
+            let gx = (at(x + 1, y - 1) + 2.0 * at(x + 1, y) + at(x + 1, y + 1))
+                - (at(x - 1, y - 1) + 2.0 * at(x - 1, y) + at(x - 1, y + 1));
+            let gy = (at(x - 1, y + 1) + 2.0 * at(x, y + 1) + at(x + 1, y + 1))
+                - (at(x - 1, y - 1) + 2.0 * at(x, y - 1) + at(x + 1, y - 1));
+            // Max Sobel response for a unit step is 4.
+            let magnitude = (gx * gx + gy * gy).sqrt() / 4.0;
+            if magnitude < threshold {
+                out.push(' ');
+                continue;
+            }
+            out.push(edge_char(gx, gy));
+        }
+    }
+    out
+}
+
+/// Stroke character perpendicular to the gradient direction.
+fn edge_char(gx: f32, gy: f32) -> char {
+    // Gradient angle in [0, 180) degrees; y grows downward in the raster.
+    let mut angle = gy.atan2(gx).to_degrees();
+    if angle < 0.0 {
+        angle += 180.0;
+    }
+    if !(22.5..157.5).contains(&angle) {
+        '|'
+    } else if angle < 67.5 {
+        '/'
+    } else if angle < 112.5 {
+        '-'
+    } else {
+        '\\'
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn solid(width: usize, height: usize, rgba: [u8; 4]) -> Vec<u8> {
+        rgba.iter()
+            .copied()
+            .cycle()
+            .take(width * height * 4)
+            .collect()
+    }
+
+    #[test]
+    fn test_ramp_from_name() {
+        assert_eq!(DensityRamp::from_name("standard").unwrap().levels(), 10);
+        assert_eq!(DensityRamp::from_name("Blocks").unwrap().chars()[4], '█');
+        assert_eq!(DensityRamp::from_name("@ ").unwrap().chars(), &['@', ' ']);
+        assert!(DensityRamp::from_name("x").is_none());
+    }
+
+    #[test]
+    fn test_rejects_mismatched_buffer() {
+        let options = ImageToAsciiOptions::default();
+        assert!(image_to_grid(&[0; 10], 2, 2, &options).is_none());
+        assert!(image_to_grid(&[], 0, 0, &options).is_none());
+    }
+
+    #[test]
+    fn test_target_size_uses_cell_aspect() {
+        let options = ImageToAsciiOptions {
+            target_width: 40,
+            cell_aspect: 0.5,
+            ..Default::default()
+        };
+        // A square image at half-height cells becomes 40×20.
+        assert_eq!(target_size(100, 100, &options), (40, 20));
+    }
+
+    #[test]
+    fn test_luminance_extremes() {
+        let options = ImageToAsciiOptions {
+            target_width: 4,
+            cell_aspect: 1.0,
+            ..Default::default()
+        };
+        let black = image_to_grid(&solid(8, 8, [0, 0, 0, 255]), 8, 8, &options).unwrap();
+        assert!(black.cells().iter().all(|c| c.ch == '@'));
+
+        let white = image_to_grid(&solid(8, 8, [255, 255, 255, 255]), 8, 8, &options).unwrap();
+        assert!(white.cells().iter().all(|c| c.ch == ' '));
+
+        let clear = image_to_grid(&solid(8, 8, [0, 0, 0, 0]), 8, 8, &options).unwrap();
+        assert!(clear.cells().iter().all(|c| c.ch == ' '));
+    }
+
+    #[test]
+    fn test_dither_mixes_levels_for_mid_gray() {
+        let options = ImageToAsciiOptions {
+            target_width: 8,
+            cell_aspect: 1.0,
+            ramp: DensityRamp::custom(" #").unwrap(),
+            mode: ImageMode::Dithered,
+            ..Default::default()
+        };
+        let gray = image_to_grid(&solid(8, 8, [128, 128, 128, 255]), 8, 8, &options).unwrap();
+        let inked = gray.cells().iter().filter(|c| c.ch == '#').count();
+        // Roughly half the cells should be inked.
+        assert!((20..=44).contains(&inked), "inked = {inked}");
+    }
+
+    #[test]
+    fn test_edges_detect_vertical_boundary() {
+        // Left half black, right half white.
+        let mut rgba = Vec::new();
+        for _y in 0..8 {
+            for x in 0..8 {
+                let v = if x < 4 { 0 } else { 255 };
+                rgba.extend_from_slice(&[v, v, v, 255]);
+            }
+        }
+        let options = ImageToAsciiOptions {
+            target_width: 8,
+            cell_aspect: 1.0,
+            mode: ImageMode::Edges,
+            ..Default::default()
+        };
+        let grid = image_to_grid(&rgba, 8, 8, &options).unwrap();
+        assert_eq!(grid.get(3, 4).unwrap().ch, '|');
+        assert_eq!(grid.get(0, 4).unwrap().ch, ' ');
+        assert_eq!(grid.get(7, 4).unwrap().ch, ' ');
+    }
+}
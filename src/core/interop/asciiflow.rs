@@ -0,0 +1,199 @@
+//! Asciiflow / Monodraw text interop.
+//!
+//! Import reads plain text (Asciiflow's text export, Monodraw's "copy as text")
+//! or Asciiflow's JSON save format (`{"version": 2, "x": .., "y": .., "text": ".."}`),
+//! then re-draws `+ - |` boxes and arrows in the chosen [`BorderStyle`].
+//! Export maps the editor's Unicode glyphs back to the `+ - | < > ^ v / \`
+//! set those tools understand; ordinary text is passed through unchanged.
+
+use super::glyphs::{ascii_symbol, box_links, contextual_links, junction};
+use crate::core::ascii_export::{export_grid, ExportOptions};
+use crate::core::grid::Grid;
+use crate::core::tools::BorderStyle;
+
+/// Largest grid an import may produce (matches the canvas caps).
+const MAX_IMPORT_WIDTH: usize = 400;
+const MAX_IMPORT_HEIGHT: usize = 200;
+
+/// Parse plain text into a grid sized to its longest line (clipped to the canvas caps).
+/// Tabs and other control characters become blanks.
+pub fn parse_text(text: &str) -> Grid {
+    let lines: Vec<&str> = text.lines().take(MAX_IMPORT_HEIGHT).collect();
+    let width = lines
+        .iter()
+        .map(|line| line.chars().count())
+        .max()
+        .unwrap_or(0)
+        .min(MAX_IMPORT_WIDTH);
+
+    let mut grid = Grid::new(width, lines.len());
+    for (y, line) in lines.iter().enumerate() {
+        for (x, ch) in line.chars().take(width).enumerate() {
+            if !ch.is_control() && ch != ' ' {
+                grid.set_char(x as i32, y as i32, ch);
+            }
+        }
+    }
+    grid
+}
+
+/// Extract the drawing text from Asciiflow's JSON save format.
+/// The stored offset is dropped so the drawing lands at the canvas origin.
+pub fn parse_json(json: &str) -> Option<String> {
+    let value: serde_json::Value = serde_json::from_str(json).ok()?;
+    value.get("text")?.as_str().map(str::to_string)
+}
+
+/// Import Asciiflow text or JSON, re-drawing lines and arrows in `style`.
+/// Returns `None` for malformed JSON or a drawing with no content.
+pub fn import_asciiflow(input: &str, style: BorderStyle) -> Option<Grid> {
+    let text = if input.trim_start().starts_with('{') {
+        parse_json(input)?
+    } else {
+        input.to_string()
+    };
+
+    let grid = normalize(&parse_text(&text), style);
+    grid.cells()
+        .iter()
+        .any(|cell| cell.is_visible())
+        .then_some(grid)
+}
+
+/// Re-draw every box/line glyph (ASCII or Unicode) and arrowhead in `style`.
+/// Characters that are not part of a line are left untouched.
+pub fn normalize(grid: &Grid, style: BorderStyle) -> Grid {
+    let mut out = grid.clone();
+    for (x, y, cell) in grid.iter_with_coords() {
+        let replacement = match contextual_links(grid, x, y) {
+            Some(links) => Some(junction(style, links)),
+            None => arrowhead(grid, x, y, cell.ch, style),
+        };
+        if let Some(ch) = replacement {
+            out.set_char(x, y, ch);
+        }
+    }
+    out
+}
+
+/// Arrowhead glyph for `ch` if it terminates a line, in the style's character set.
+fn arrowhead(grid: &Grid, x: i32, y: i32, ch: char, style: BorderStyle) -> Option<char> {
+    let line_at = |dx: i32, dy: i32, horizontal: bool| {
+        contextual_links(grid, x + dx, y + dy).is_some_and(|l| {
+            if horizontal {
+                l.horizontal()
+            } else {
+                l.vertical()
+            }
+        })
+    };
+    let (ascii, unicode) = match ch {
+        '>' | '►' if line_at(-1, 0, true) => ('>', '►'),
+        '<' | '◄' if line_at(1, 0, true) => ('<', '◄'),
+        '^' | '▲' if line_at(0, 1, false) => ('^', '▲'),
+        'v' | 'V' | '▼' if line_at(0, -1, false) => ('v', '▼'),
+        _ => return None,
+    };
+    Some(if style == BorderStyle::Ascii {
+        ascii
+    } else {
+        unicode
+    })
+}
+
+/// Map a grid to the Asciiflow character set: box glyphs become `+ - |`,
+/// arrowheads `< > ^ v`, diagonals `/ \`.
+pub fn to_asciiflow(grid: &Grid) -> Grid {
+    let mut out = grid.clone();
+    for (x, y, cell) in grid.iter_with_coords() {
+        let mapped = match box_links(cell.ch) {
+            Some(links) if links.is_junction() => Some('+'),
+            Some(links) if links.horizontal() => Some('-'),
+            Some(_) => Some('|'),
+            None => ascii_symbol(cell.ch),
+        };
+        if let Some(ch) = mapped {
+            out.set_char(x, y, ch);
+        }
+    }
+    out
+}
+
+/// Export as Asciiflow-compatible plain text (trimmed to content, no trailing blanks).
+pub fn export_asciiflow(grid: &Grid) -> String {
+    export_grid(&to_asciiflow(grid), &ExportOptions::default())
+        .lines()
+        .map(str::trim_end)
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Export in Asciiflow's JSON save format.
+pub fn export_asciiflow_json(grid: &Grid) -> String {
+    serde_json::json!({
+        "version": 2,
+        "x": 0,
+        "y": 0,
+        "text": export_asciiflow(grid),
+    })
+    .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BOX: &str = "+--+\n|  |\n+--+";
+
+    fn rows(grid: &Grid) -> Vec<String> {
+        (0..grid.height() as i32)
+            .map(|y| {
+                (0..grid.width() as i32)
+                    .map(|x| grid.get(x, y).unwrap().ch)
+                    .collect()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_import_box_in_style() {
+        let grid = import_asciiflow(BOX, BorderStyle::Single).unwrap();
+        assert_eq!(rows(&grid), vec!["┌──┐", "│  │", "└──┘"]);
+
+        let grid = import_asciiflow(BOX, BorderStyle::Double).unwrap();
+        assert_eq!(rows(&grid)[0], "╔══╗");
+    }
+
+    #[test]
+    fn test_import_junctions_and_arrows() {
+        let text = "+--+-->\n|  |\n+--+";
+        let grid = import_asciiflow(text, BorderStyle::Single).unwrap();
+        assert_eq!(rows(&grid)[0], "┌──┬──►");
+        assert_eq!(rows(&grid)[2], "└──┘   ");
+    }
+
+    #[test]
+    fn test_import_leaves_text_alone() {
+        let grid = import_asciiflow("a-b v1+1", BorderStyle::Single).unwrap();
+        assert_eq!(rows(&grid)[0], "a-b v1+1");
+    }
+
+    #[test]
+    fn test_import_json() {
+        let json = r#"{"version":2,"x":100,"y":50,"text":"+-+\n+-+"}"#;
+        let grid = import_asciiflow(json, BorderStyle::Rounded).unwrap();
+        assert_eq!(rows(&grid), vec!["╭─╮", "╰─╯"]);
+
+        assert!(import_asciiflow("{not json", BorderStyle::Single).is_none());
+        assert!(import_asciiflow("   \n  ", BorderStyle::Single).is_none());
+    }
+
+    #[test]
+    fn test_export_round_trip() {
+        let grid = import_asciiflow("+--+-->\n|  |\n+--+", BorderStyle::Heavy).unwrap();
+        assert_eq!(export_asciiflow(&grid), "+--+-->\n|  |\n+--+");
+
+        let json = export_asciiflow_json(&grid);
+        assert_eq!(parse_json(&json).unwrap(), "+--+-->\n|  |\n+--+");
+    }
+}
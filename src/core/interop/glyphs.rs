@@ -0,0 +1,242 @@
+//! Glyph connectivity - which sides of a cell a line-drawing character joins.
+//!
+//! Shared by the importers/exporters so that boxes can be re-drawn in another
+//! character set without losing corners and junctions.
+
+use crate::core::grid::Grid;
+use crate::core::tools::BorderStyle;
+
+/// Sides of a cell that a line glyph connects to.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Links {
+    /// Connects to the cell above
+    pub up: bool,
+    /// Connects to the cell below
+    pub down: bool,
+    /// Connects to the cell on the left
+    pub left: bool,
+    /// Connects to the cell on the right
+    pub right: bool,
+}
+
+impl Links {
+    const fn new(up: bool, down: bool, left: bool, right: bool) -> Self {
+        Self {
+            up,
+            down,
+            left,
+            right,
+        }
+    }
+
+    /// Number of connected sides.
+    pub fn count(&self) -> usize {
+        [self.up, self.down, self.left, self.right]
+            .iter()
+            .filter(|&&b| b)
+            .count()
+    }
+
+    /// Whether the glyph has any horizontal connection.
+    pub fn horizontal(&self) -> bool {
+        self.left || self.right
+    }
+
+    /// Whether the glyph has any vertical connection.
+    pub fn vertical(&self) -> bool {
+        self.up || self.down
+    }
+
+    /// Whether the glyph bends or branches (corner, tee or cross).
+    pub fn is_junction(&self) -> bool {
+        self.horizontal() && self.vertical()
+    }
+}
+
+/// Intrinsic links of a Unicode box-drawing character, or `None` for anything else.
+pub fn box_links(ch: char) -> Option<Links> {
+    let links = match ch {
+        '─' | '━' | '═' | '┄' | '┅' | '┈' | '┉' | '╌' | '╍' => {
+            Links::new(false, false, true, true)
+        }
+        '│' | '┃' | '║' | '┆' | '┇' | '┊' | '┋' | '╎' | '╏' => {
+            Links::new(true, true, false, false)
+        }
+        '┌' | '┏' | '╔' | '╭' | '┍' | '┎' | '╒' | '╓' => {
+            Links::new(false, true, false, true)
+        }
+        '┐' | '┓' | '╗' | '╮' | '┑' | '┒' | '╕' | '╖' => {
+            Links::new(false, true, true, false)
+        }
+        '└' | '┗' | '╚' | '╰' | '┕' | '┖' | '╘' | '╙' => {
+            Links::new(true, false, false, true)
+        }
+        '┘' | '┛' | '╝' | '╯' | '┙' | '┚' | '╛' | '╜' => {
+            Links::new(true, false, true, false)
+        }
+        '├' | '┣' | '╠' | '┝' | '┞' | '┟' | '┠' | '┡' | '┢' | '╞' | '╟' => {
+            Links::new(true, true, false, true)
+        }
+        '┤' | '┫' | '╣' | '┥' | '┦' | '┧' | '┨' | '┩' | '┪' | '╡' | '╢' => {
+            Links::new(true, true, true, false)
+        }
+        '┬' | '┳' | '╦' | '┭' | '┮' | '┯' | '┰' | '┱' | '┲' | '╤' | '╥' => {
+            Links::new(false, true, true, true)
+        }
+        '┴' | '┻' | '╩' | '┵' | '┶' | '┷' | '┸' | '┹' | '┺' | '╧' | '╨' => {
+            Links::new(true, false, true, true)
+        }
+        '┼' | '╋' | '╬' | '┽' | '┾' | '┿' | '╀' | '╁' | '╂' | '╪' | '╫' => {
+            Links::new(true, true, true, true)
+        }
+        '╴' | '╸' => Links::new(false, false, true, false),
+        '╶' | '╺' => Links::new(false, false, false, true),
+        '╵' | '╹' => Links::new(true, false, false, false),
+        '╷' | '╻' => Links::new(false, true, false, false),
+        _ => return None,
+    };
+    Some(links)
+}
+
+/// Line links of a cell in context: Unicode box glyphs use their intrinsic links;
+/// ASCII `+ - |` (and arrowheads) connect only toward neighbors that continue the line,
+/// so `a-b` or `1+1` in ordinary text is left alone.
+pub fn contextual_links(grid: &Grid, x: i32, y: i32) -> Option<Links> {
+    let ch = grid.get(x, y)?.ch;
+    if let Some(links) = box_links(ch) {
+        return Some(links);
+    }
+
+    let at = |dx: i32, dy: i32| grid.get(x + dx, y + dy).map(|c| c.ch).unwrap_or(' ');
+    let up = joins_down(at(0, -1));
+    let down = joins_up(at(0, 1));
+    let left = joins_right(at(-1, 0));
+    let right = joins_left(at(1, 0));
+
+    let links = match ch {
+        '+' => Links::new(up, down, left, right),
+        '-' if left || right => Links::new(false, false, true, true),
+        '|' if up || down => Links::new(true, true, false, false),
+        _ => return None,
+    };
+    (links.count() > 0).then_some(links)
+}
+
+fn joins_down(ch: char) -> bool {
+    matches!(ch, '|' | '+' | '^' | '▲') || box_links(ch).is_some_and(|l| l.down)
+}
+
+fn joins_up(ch: char) -> bool {
+    matches!(ch, '|' | '+' | 'v' | 'V' | '▼') || box_links(ch).is_some_and(|l| l.up)
+}
+
+fn joins_right(ch: char) -> bool {
+    matches!(ch, '-' | '+' | '<' | '◄') || box_links(ch).is_some_and(|l| l.right)
+}
+
+fn joins_left(ch: char) -> bool {
+    matches!(ch, '-' | '+' | '>' | '►') || box_links(ch).is_some_and(|l| l.left)
+}
+
+/// Character for a line cell with the given links, drawn in `style`.
+pub fn junction(style: BorderStyle, links: Links) -> char {
+    if !links.vertical() {
+        return style.horizontal();
+    }
+    if !links.horizontal() {
+        return style.vertical();
+    }
+    let [top_left, top_right, bottom_left, bottom_right] = style.corners();
+    match (links.up, links.down, links.left, links.right) {
+        (false, true, false, true) => top_left,
+        (false, true, true, false) => top_right,
+        (true, false, false, true) => bottom_left,
+        (true, false, true, false) => bottom_right,
+        (up, down, left, right) => tee(style, up, down, left, right),
+    }
+}
+
+fn tee(style: BorderStyle, up: bool, down: bool, left: bool, right: bool) -> char {
+    let set = match style {
+        BorderStyle::Single | BorderStyle::Rounded => ['├', '┤', '┬', '┴', '┼'],
+        BorderStyle::Double => ['╠', '╣', '╦', '╩', '╬'],
+        BorderStyle::Heavy => ['┣', '┫', '┳', '┻', '╋'],
+        BorderStyle::Ascii => ['+'; 5],
+        BorderStyle::Dotted => ['*'; 5],
+    };
+    match (up, down, left, right) {
+        (true, true, false, true) => set[0],
+        (true, true, true, false) => set[1],
+        (false, true, true, true) => set[2],
+        (true, false, true, true) => set[3],
+        _ => set[4],
+    }
+}
+
+/// ASCII stand-in for the editor's Unicode arrowheads, markers and shades.
+pub fn ascii_symbol(ch: char) -> Option<char> {
+    let mapped = match ch {
+        '►' | '▶' | '→' => '>',
+        '◄' | '◀' | '←' => '<',
+        '▲' | '↑' => '^',
+        '▼' | '↓' => 'v',
+        '╱' => '/',
+        '╲' => '\\',
+        '╳' => 'X',
+        '◆' | '●' | '•' | '○' | '◇' => '*',
+        '·' => '.',
+        '░' | '▒' => ':',
+        '▓' | '█' => '#',
+        _ => return None,
+    };
+    Some(mapped)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn grid_from(lines: &[&str]) -> Grid {
+        let width = lines.iter().map(|l| l.chars().count()).max().unwrap_or(0);
+        let mut grid = Grid::new(width, lines.len());
+        for (y, line) in lines.iter().enumerate() {
+            for (x, ch) in line.chars().enumerate() {
+                grid.set_char(x as i32, y as i32, ch);
+            }
+        }
+        grid
+    }
+
+    #[test]
+    fn test_box_links() {
+        assert_eq!(box_links('┌'), Some(Links::new(false, true, false, true)));
+        assert_eq!(box_links('╬').map(|l| l.count()), Some(4));
+        assert_eq!(box_links('x'), None);
+    }
+
+    #[test]
+    fn test_contextual_links_ignore_text() {
+        let grid = grid_from(&["a-b 1+1", "+--+   "]);
+        assert!(contextual_links(&grid, 1, 0).is_none());
+        assert!(contextual_links(&grid, 5, 0).is_none());
+        assert_eq!(
+            contextual_links(&grid, 0, 1),
+            Some(Links::new(false, false, false, true))
+        );
+    }
+
+    #[test]
+    fn test_junction_per_style() {
+        let corner = Links::new(false, true, false, true);
+        assert_eq!(junction(BorderStyle::Single, corner), '┌');
+        assert_eq!(junction(BorderStyle::Double, corner), '╔');
+        assert_eq!(junction(BorderStyle::Ascii, corner), '+');
+
+        let tee = Links::new(true, true, false, true);
+        assert_eq!(junction(BorderStyle::Heavy, tee), '┣');
+        assert_eq!(
+            junction(BorderStyle::Single, Links::new(false, false, true, false)),
+            '─'
+        );
+    }
+}
@@ -0,0 +1,8 @@
+//! Interop module - converting drawings to and from other ASCII diagram tools.
+
+pub mod asciiflow;
+pub mod glyphs;
+pub mod svgbob;
+
+pub use asciiflow::{export_asciiflow, export_asciiflow_json, import_asciiflow};
+pub use svgbob::{export_diagram, DiagramFlavor};
@@ -0,0 +1,156 @@
+//! Svgbob / ditaa output - rewrites Unicode art into the plain-ASCII conventions
+//! those renderers recognise.
+//!
+//! Boxes become `+ - |`; rounded corners become `.-.` / `'-'` for svgbob and
+//! `/-\` / `\-/` for ditaa; arrowheads become `< > ^ v`; diamonds are drawn with
+//! `/ \` and, for svgbob, closed with `.` / `'` tips.
+
+use super::glyphs::{ascii_symbol, box_links};
+use crate::core::ascii_export::{export_grid, ExportOptions};
+use crate::core::grid::Grid;
+
+/// Target renderer for [`export_diagram`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum DiagramFlavor {
+    /// svgbob: rounded corners as `.` and `'`
+    #[default]
+    Svgbob,
+    /// ditaa: rounded corners as `/` and `\`, dashed lines as `=` and `:`
+    Ditaa,
+}
+
+impl DiagramFlavor {
+    /// Parse a flavor name (`svgbob`, `ditaa`).
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_lowercase().as_str() {
+            "svgbob" | "bob" => Some(Self::Svgbob),
+            "ditaa" => Some(Self::Ditaa),
+            _ => None,
+        }
+    }
+}
+
+/// Rewrite every cell of `grid` into the flavor's character set.
+pub fn to_diagram(grid: &Grid, flavor: DiagramFlavor) -> Grid {
+    let mut out = grid.clone();
+    for (x, y, cell) in grid.iter_with_coords() {
+        let mapped = rounded_corner(cell.ch, flavor)
+            .or_else(|| dashed_line(cell.ch, flavor))
+            .or_else(|| {
+                box_links(cell.ch).map(|links| {
+                    if links.is_junction() {
+                        '+'
+                    } else if links.horizontal() {
+                        '-'
+                    } else {
+                        '|'
+                    }
+                })
+            })
+            .or_else(|| ascii_symbol(cell.ch));
+        if let Some(ch) = mapped {
+            out.set_char(x, y, ch);
+        }
+    }
+
+    if flavor == DiagramFlavor::Svgbob {
+        close_diamond_tips(grid, &mut out);
+    }
+    out
+}
+
+/// Export visible content as svgbob- or ditaa-ready text (trimmed, no trailing blanks).
+pub fn export_diagram(grid: &Grid, flavor: DiagramFlavor) -> String {
+    export_grid(&to_diagram(grid, flavor), &ExportOptions::default())
+        .lines()
+        .map(str::trim_end)
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn rounded_corner(ch: char, flavor: DiagramFlavor) -> Option<char> {
+    let [top_left, top_right, bottom_left, bottom_right] = match flavor {
+        DiagramFlavor::Svgbob => ['.', '.', '\'', '\''],
+        DiagramFlavor::Ditaa => ['/', '\\', '\\', '/'],
+    };
+    match ch {
+        '╭' => Some(top_left),
+        '╮' => Some(top_right),
+        '╰' => Some(bottom_left),
+        '╯' => Some(bottom_right),
+        _ => None,
+    }
+}
+
+fn dashed_line(ch: char, flavor: DiagramFlavor) -> Option<char> {
+    if flavor != DiagramFlavor::Ditaa {
+        return None;
+    }
+    match ch {
+        '┄' | '┅' | '┈' | '┉' | '╌' | '╍' => Some('='),
+        '┆' | '┇' | '┊' | '┋' | '╎' | '╏' => Some(':'),
+        _ => None,
+    }
+}
+
+/// Replace the top and bottom vertices of `/\` diamonds with `.` and `'` so svgbob
+/// draws a closed outline. A vertex is a diagonal whose two diagonal neighbours
+/// both lie on the row below (top tip) or above (bottom tip).
+fn close_diamond_tips(source: &Grid, out: &mut Grid) {
+    let is_diag = |x: i32, y: i32, want: char| {
+        source
+            .get(x, y)
+            .map(|c| ascii_symbol(c.ch) == Some(want))
+            .unwrap_or(false)
+    };
+    for (x, y, cell) in source.iter_with_coords() {
+        if !matches!(cell.ch, '╱' | '╲') {
+            continue;
+        }
+        if is_diag(x - 1, y + 1, '/') && is_diag(x + 1, y + 1, '\\') {
+            out.set_char(x, y, '.');
+        } else if is_diag(x - 1, y - 1, '\\') && is_diag(x + 1, y - 1, '/') {
+            out.set_char(x, y, '\'');
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::interop::asciiflow::parse_text;
+
+    #[test]
+    fn test_boxes_and_arrows() {
+        let grid = parse_text("┌──┬──►\n│  ▼\n└──┘");
+        assert_eq!(
+            export_diagram(&grid, DiagramFlavor::Svgbob),
+            "+--+-->\n|  v\n+--+"
+        );
+    }
+
+    #[test]
+    fn test_rounded_corners_per_flavor() {
+        let grid = parse_text("╭─╮\n╰─╯");
+        assert_eq!(export_diagram(&grid, DiagramFlavor::Svgbob), ".-.\n'-'");
+        assert_eq!(export_diagram(&grid, DiagramFlavor::Ditaa), "/-\\\n\\-/");
+    }
+
+    #[test]
+    fn test_diamond_tips() {
+        let grid = parse_text(" ╲\n╱ ╲\n╲ ╱\n ╱");
+        assert_eq!(
+            export_diagram(&grid, DiagramFlavor::Svgbob),
+            " .\n/ \\\n\\ /\n '"
+        );
+    }
+
+    #[test]
+    fn test_flavor_from_name() {
+        assert_eq!(
+            DiagramFlavor::from_name("Ditaa"),
+            Some(DiagramFlavor::Ditaa)
+        );
+        assert_eq!(DiagramFlavor::from_name("mermaid"), None);
+    }
+}
@@ -6,13 +6,22 @@
 //! - Drawing tools
 //! - Command pattern for undo/redo
 //! - History management
+//! - Layer compositing (offsets, blend modes, transparency)
 //! - ASCII export
+//! - `.asc` document schema and migration
+//! - Image-to-ASCII conversion
+//! - Interop with other ASCII diagram tools
 
 pub mod ascii_export;
 pub mod cell;
 pub mod commands;
+pub mod composite_cache;
+pub mod compositing;
+pub mod document;
 pub mod grid;
 pub mod history;
+pub mod image_ascii;
+pub mod interop;
 pub mod selection;
 pub mod tools;
 
@@ -20,8 +29,12 @@ pub mod tools;
 pub use ascii_export::{export_grid, ExportOptions};
 pub use cell::{Cell, CellStyle};
 pub use commands::Command;
+pub use composite_cache::CompositeCache;
+pub use compositing::{BlendMode, LayerView};
+pub use document::{Document, DocumentError, DocumentMetadata, LoadWarning};
 pub use grid::Grid;
 pub use history::History;
+pub use image_ascii::{image_to_grid, DensityRamp, ImageMode, ImageToAsciiOptions};
 pub use selection::Selection;
 pub use tools::{BorderStyle, DrawOp, Tool, ToolId, ToolResult};
 
@@ -0,0 +1,114 @@
+//! Headless rendering - the editor's pixel-buffer output without a browser.
+//!
+//! [`render_grid`] and [`render_document`] draw with the same code as
+//! `AsciiEditor::render_to_pixel_buffer` (built-in 8×20 font, zoom 1, no selection,
+//! cursor or grid lines) and [`Snapshot::to_png`] encodes the result, so `cargo test`
+//! can compare tool output against golden images.
+
+mod png;
+
+pub use png::encode_png;
+
+use crate::core::compositing::LayerView;
+use crate::core::{CompositeCache, Document, DocumentError, Grid};
+use crate::render::{render_scene, FontAtlas, PixelLayout, PixelPalette, PixelScene};
+use crate::ui::Theme;
+
+/// A rendered RGBA image.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Snapshot {
+    /// Width in pixels
+    pub width: usize,
+    /// Height in pixels
+    pub height: usize,
+    /// RGBA bytes, row-major
+    pub pixels: Vec<u8>,
+}
+
+impl Snapshot {
+    /// The RGBA value of one pixel.
+    pub fn pixel(&self, x: usize, y: usize) -> [u8; 4] {
+        let i = (y * self.width + x) * 4;
+        [
+            self.pixels[i],
+            self.pixels[i + 1],
+            self.pixels[i + 2],
+            self.pixels[i + 3],
+        ]
+    }
+
+    /// Encode as a PNG file.
+    pub fn to_png(&self) -> Vec<u8> {
+        encode_png(self.width, self.height, &self.pixels)
+    }
+}
+
+/// Render a single grid as the editor shows it with `theme`.
+pub fn render_grid(grid: &Grid, theme: &Theme) -> Snapshot {
+    render_layers(&[LayerView::new(grid)], grid.width(), grid.height(), theme)
+}
+
+/// Render a `width`×`height` canvas composited from `layers` (bottom to top).
+pub fn render_layers(layers: &[LayerView], width: usize, height: usize, theme: &Theme) -> Snapshot {
+    let atlas = FontAtlas::new();
+    let cell = (atlas.glyph_width, atlas.glyph_height);
+    let layout = PixelLayout::canvas(width, height, cell);
+    let mut composite = CompositeCache::new();
+    composite.sync(layers, width, height);
+    let scene = PixelScene::new(layers, &composite, width, height);
+    let palette = PixelPalette::from_theme(|name| theme.variable(name));
+    Snapshot {
+        width: layout.width,
+        height: layout.height,
+        pixels: render_scene(&atlas, &layout, &scene, &palette),
+    }
+}
+
+/// Render the active page of a `.asc` document (JSON or plain text) with its
+/// shown layers, guides included, as the editor does after loading it.
+pub fn render_document(input: &str, theme: &Theme) -> Result<Snapshot, DocumentError> {
+    let doc = Document::parse(input)?;
+    let page = &doc.pages[doc.clamped_active_page().0];
+    let (width, height) = (page.canvas.width, page.canvas.height);
+    let shown: Vec<_> = page
+        .layers
+        .iter()
+        .filter(|layer| {
+            let group = layer
+                .group
+                .and_then(|id| page.groups.iter().find(|g| g.id == id));
+            layer.visible && group.map_or(true, |g| g.visible)
+        })
+        .collect();
+    let grids: Vec<Grid> = shown.iter().map(|l| l.to_grid(width, height).0).collect();
+    let views: Vec<LayerView> = shown
+        .iter()
+        .zip(&grids)
+        .map(|(layer, grid)| LayerView {
+            grid,
+            offset: (layer.x, layer.y),
+            blend: layer.blend,
+            transparent: layer.transparent,
+            opacity: layer.opacity,
+            guide: layer.guide,
+        })
+        .collect();
+    Ok(render_layers(&views, width, height, theme))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_theme_colors_reach_the_snapshot() {
+        let mut grid = Grid::new(2, 1);
+        grid.set(0, 0, crate::core::Cell::new('#'));
+        let light = render_grid(&grid, &Theme::light());
+        let dark = render_grid(&grid, &Theme::figma_dark());
+        assert_ne!(light.pixel(12, 10), dark.pixel(12, 10));
+        let theme = Theme::light();
+        let palette = PixelPalette::from_theme(|name| theme.variable(name));
+        assert_eq!(light.pixel(12, 10), palette.background);
+    }
+}
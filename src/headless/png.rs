@@ -0,0 +1,93 @@
+//! Minimal PNG encoder for RGBA snapshots.
+//!
+//! Writes 8-bit RGBA, no filtering, one zlib-compressed `IDAT` chunk. The output is
+//! deterministic for a given input, which is what golden-image tests want.
+
+/// PNG file signature.
+const SIGNATURE: [u8; 8] = [0x89, b'P', b'N', b'G', b'\r', b'\n', 0x1a, b'\n'];
+/// Bit depth 8, color type 6 (RGBA).
+const BIT_DEPTH: u8 = 8;
+const COLOR_RGBA: u8 = 6;
+/// zlib compression level for image data.
+const LEVEL: u8 = 6;
+
+/// Encode `width`×`height` RGBA pixels (row-major, 4 bytes each) as a PNG file.
+///
+/// # Panics
+/// If `rgba` is shorter than `width * height * 4` bytes.
+pub fn encode_png(width: usize, height: usize, rgba: &[u8]) -> Vec<u8> {
+    let stride = width * 4;
+    assert!(rgba.len() >= stride * height, "pixel buffer too small");
+
+    let mut header = Vec::with_capacity(13);
+    header.extend((width as u32).to_be_bytes());
+    header.extend((height as u32).to_be_bytes());
+    header.extend([BIT_DEPTH, COLOR_RGBA, 0, 0, 0]);
+
+    // Each row starts with its filter type, 0 (none).
+    let mut raw = Vec::with_capacity((stride + 1) * height);
+    for row in rgba.chunks_exact(stride.max(1)).take(height) {
+        raw.push(0);
+        raw.extend_from_slice(row);
+    }
+    let data = miniz_oxide::deflate::compress_to_vec_zlib(&raw, LEVEL);
+
+    let mut png = SIGNATURE.to_vec();
+    write_chunk(&mut png, b"IHDR", &header);
+    write_chunk(&mut png, b"IDAT", &data);
+    write_chunk(&mut png, b"IEND", &[]);
+    png
+}
+
+/// Append a chunk: length, type, data and the CRC of type and data.
+fn write_chunk(out: &mut Vec<u8>, kind: &[u8; 4], data: &[u8]) {
+    out.extend((data.len() as u32).to_be_bytes());
+    let start = out.len();
+    out.extend_from_slice(kind);
+    out.extend_from_slice(data);
+    let crc = crc32(&out[start..]);
+    out.extend(crc.to_be_bytes());
+}
+
+/// CRC-32 (ISO 3309, as used by PNG).
+fn crc32(bytes: &[u8]) -> u32 {
+    let mut crc = !0u32;
+    for &b in bytes {
+        crc ^= u32::from(b);
+        for _ in 0..8 {
+            crc = if crc & 1 != 0 {
+                (crc >> 1) ^ 0xedb8_8320
+            } else {
+                crc >> 1
+            };
+        }
+    }
+    !crc
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_png_layout_and_checksums() {
+        assert_eq!(crc32(b"IEND"), 0xae42_6082);
+        assert_eq!(crc32(b"123456789"), 0xcbf4_3926);
+
+        let pixels = [255, 0, 0, 255, 0, 255, 0, 255];
+        let png = encode_png(2, 1, &pixels);
+        assert_eq!(png[..8], SIGNATURE);
+        assert_eq!(&png[12..16], b"IHDR");
+        assert_eq!(png[16..24], [0, 0, 0, 2, 0, 0, 0, 1]);
+        assert_eq!(
+            png[png.len() - 12..],
+            [0, 0, 0, 0, b'I', b'E', b'N', b'D', 0xae, 0x42, 0x60, 0x82]
+        );
+
+        // The image data inflates back to filter byte + row.
+        let idat_len = u32::from_be_bytes(png[33..37].try_into().unwrap()) as usize;
+        assert_eq!(&png[37..41], b"IDAT");
+        let raw = miniz_oxide::inflate::decompress_to_vec_zlib(&png[41..41 + idat_len]).unwrap();
+        assert_eq!(raw, [0, 255, 0, 0, 255, 0, 255, 0, 255]);
+    }
+}
@@ -20,6 +20,7 @@
 //! - **Render**: Canvas rendering with dirty-rect optimization
 //! - **WASM**: WebAssembly bindings for JavaScript interop
 //! - **UI**: Toolbar, shortcuts, and theming
+//! - **Headless**: Native snapshot rendering and PNG encoding for tests
 //!
 //! # Example
 //!
@@ -38,6 +39,8 @@
 #![warn(clippy::all)]
 
 pub mod core;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod headless;
 pub mod render;
 pub mod ui;
 pub mod utils;
@@ -0,0 +1,363 @@
+//! Bitmap font loading - BDF and PSF2 font files for the pixel renderer.
+//!
+//! Glyphs are decoded into one alpha byte per pixel (0 or 255), each placed in a
+//! fixed `width`×`height` cell so they can be copied straight into a `FontAtlas`.
+
+use std::collections::HashMap;
+use std::fmt;
+
+/// Largest glyph cell accepted from a font file, in pixels.
+pub const MAX_GLYPH_WIDTH: usize = 32;
+/// Largest glyph cell height accepted from a font file, in pixels.
+pub const MAX_GLYPH_HEIGHT: usize = 64;
+
+const PSF2_MAGIC: [u8; 4] = [0x72, 0xb5, 0x4a, 0x86];
+const PSF2_HAS_UNICODE_TABLE: u32 = 1;
+
+/// Why a font file could not be loaded.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum FontError {
+    /// Neither a BDF nor a PSF2 file
+    UnknownFormat,
+    /// File ends before the data its header describes
+    Truncated,
+    /// Malformed header or glyph entry
+    Invalid(String),
+    /// Glyph cell larger than [`MAX_GLYPH_WIDTH`]×[`MAX_GLYPH_HEIGHT`] or empty
+    UnsupportedSize {
+        /// Cell width in pixels
+        width: usize,
+        /// Cell height in pixels
+        height: usize,
+    },
+}
+
+impl fmt::Display for FontError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UnknownFormat => write!(f, "Not a BDF or PSF2 font"),
+            Self::Truncated => write!(f, "Font file is truncated"),
+            Self::Invalid(msg) => write!(f, "Font file is invalid: {msg}"),
+            Self::UnsupportedSize { width, height } => write!(
+                f,
+                "Glyph size {width}×{height} is outside the supported 1×1 to {MAX_GLYPH_WIDTH}×{MAX_GLYPH_HEIGHT}"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for FontError {}
+
+/// A monospace bitmap font decoded from a font file.
+#[derive(Clone, Debug)]
+pub struct BitmapFont {
+    /// Glyph cell width in pixels
+    pub width: usize,
+    /// Glyph cell height in pixels
+    pub height: usize,
+    glyphs: HashMap<char, Vec<u8>>,
+}
+
+impl BitmapFont {
+    /// Decode a BDF or PSF2 file, detected from its contents.
+    pub fn parse(bytes: &[u8]) -> Result<Self, FontError> {
+        if bytes.starts_with(&PSF2_MAGIC) {
+            Self::parse_psf2(bytes)
+        } else if bytes.starts_with(b"STARTFONT") {
+            Self::parse_bdf(bytes)
+        } else {
+            Err(FontError::UnknownFormat)
+        }
+    }
+
+    /// Alpha mask of a glyph, `width * height` bytes, row-major.
+    pub fn glyph(&self, ch: char) -> Option<&[u8]> {
+        self.glyphs.get(&ch).map(Vec::as_slice)
+    }
+
+    /// Characters the font covers.
+    pub fn chars(&self) -> impl Iterator<Item = char> + '_ {
+        self.glyphs.keys().copied()
+    }
+
+    /// Number of glyphs.
+    pub fn len(&self) -> usize {
+        self.glyphs.len()
+    }
+
+    /// Whether the font has no glyphs.
+    pub fn is_empty(&self) -> bool {
+        self.glyphs.is_empty()
+    }
+
+    fn new(width: usize, height: usize) -> Result<Self, FontError> {
+        if width == 0 || height == 0 || width > MAX_GLYPH_WIDTH || height > MAX_GLYPH_HEIGHT {
+            return Err(FontError::UnsupportedSize { width, height });
+        }
+        Ok(Self {
+            width,
+            height,
+            glyphs: HashMap::new(),
+        })
+    }
+
+    /// PSF2: a little-endian header, fixed-size glyph bitmaps and an optional
+    /// Unicode table mapping each glyph to the characters it draws.
+    fn parse_psf2(bytes: &[u8]) -> Result<Self, FontError> {
+        let field = |i: usize| -> Result<u32, FontError> {
+            let b = bytes.get(i * 4..i * 4 + 4).ok_or(FontError::Truncated)?;
+            Ok(u32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+        };
+        let header_size = field(2)? as usize;
+        let flags = field(3)?;
+        let count = field(4)? as usize;
+        let char_size = field(5)? as usize;
+        let mut font = Self::new(field(7)? as usize, field(6)? as usize)?;
+
+        let row_bytes = (font.width + 7) / 8;
+        if char_size != row_bytes * font.height {
+            return Err(FontError::Invalid(format!(
+                "glyph size {char_size} does not match {}×{}",
+                font.width, font.height
+            )));
+        }
+        let table_start = count
+            .checked_mul(char_size)
+            .and_then(|n| n.checked_add(header_size))
+            .filter(|&end| end <= bytes.len())
+            .ok_or(FontError::Truncated)?;
+
+        let masks: Vec<_> = (0..count)
+            .map(|i| {
+                let start = header_size + i * char_size;
+                font.unpack(&bytes[start..start + char_size], row_bytes)
+            })
+            .collect();
+
+        if flags & PSF2_HAS_UNICODE_TABLE == 0 {
+            for (i, mask) in masks.into_iter().enumerate() {
+                if let Some(ch) = char::from_u32(i as u32) {
+                    font.glyphs.insert(ch, mask);
+                }
+            }
+            return Ok(font);
+        }
+
+        // One entry per glyph: UTF-8 characters, then optional 0xFE-prefixed
+        // combining sequences (ignored), terminated by 0xFF.
+        let mut entries = bytes[table_start..].split(|&b| b == 0xff);
+        for mask in masks {
+            let entry = entries.next().ok_or(FontError::Truncated)?;
+            let singles = entry.split(|&b| b == 0xfe).next().unwrap_or_default();
+            let text = std::str::from_utf8(singles)
+                .map_err(|_| FontError::Invalid("bad UTF-8 in Unicode table".into()))?;
+            for ch in text.chars() {
+                font.glyphs.insert(ch, mask.clone());
+            }
+        }
+        Ok(font)
+    }
+
+    /// BDF: a text format with a font bounding box and per-glyph `BBX` boxes and
+    /// hex bitmap rows. `ENCODING` is taken as the Unicode code point.
+    fn parse_bdf(bytes: &[u8]) -> Result<Self, FontError> {
+        let text = std::str::from_utf8(bytes)
+            .map_err(|_| FontError::Invalid("BDF file is not UTF-8".into()))?;
+        let mut lines = text.lines().map(str::trim);
+        let numbers = |rest: &str| -> Result<Vec<i32>, FontError> {
+            rest.split_whitespace()
+                .map(|n| n.parse().map_err(|_| bad_line(rest)))
+                .collect()
+        };
+
+        let mut font = None;
+        let mut origin = (0, 0);
+        let mut encoding = None;
+        let mut bbx = None;
+        while let Some(line) = lines.next() {
+            let (key, rest) = line.split_once(' ').unwrap_or((line, ""));
+            match key {
+                "FONTBOUNDINGBOX" => {
+                    let n = numbers(rest)?;
+                    let [w, h, x, y] = n[..] else {
+                        return Err(bad_line(line));
+                    };
+                    font = Some(Self::new(w.max(0) as usize, h.max(0) as usize)?);
+                    origin = (x, y);
+                }
+                "STARTCHAR" => (encoding, bbx) = (None, None),
+                "ENCODING" => {
+                    encoding = numbers(rest)?
+                        .first()
+                        .and_then(|&n| u32::try_from(n).ok())
+                        .and_then(char::from_u32);
+                }
+                "BBX" => {
+                    let n = numbers(rest)?;
+                    let [w, h, x, y] = n[..] else {
+                        return Err(bad_line(line));
+                    };
+                    bbx = Some((w.max(0) as usize, h.max(0) as usize, x, y));
+                }
+                "BITMAP" => {
+                    let font = font.as_mut().ok_or_else(|| {
+                        FontError::Invalid("BITMAP before FONTBOUNDINGBOX".into())
+                    })?;
+                    let (w, h, x, y) = bbx.ok_or_else(|| bad_line(line))?;
+                    let rows: Vec<&str> = lines.by_ref().take(h).collect();
+                    if rows.len() < h {
+                        return Err(FontError::Truncated);
+                    }
+                    if let Some(ch) = encoding {
+                        // Rows are counted down from the top of the font bounding box.
+                        let top = (font.height as i32 + origin.1) - (h as i32 + y);
+                        let left = x - origin.0;
+                        let mask = font.place_bdf(&rows, w, (left, top))?;
+                        font.glyphs.insert(ch, mask);
+                    }
+                }
+                _ => {}
+            }
+        }
+        font.ok_or_else(|| FontError::Invalid("missing FONTBOUNDINGBOX".into()))
+    }
+
+    /// Expand packed 1-bit rows (most significant bit first) into an alpha mask.
+    fn unpack(&self, packed: &[u8], row_bytes: usize) -> Vec<u8> {
+        let mut mask = vec![0u8; self.width * self.height];
+        for (y, row) in packed.chunks(row_bytes).take(self.height).enumerate() {
+            for x in 0..self.width {
+                if row[x / 8] & (0x80 >> (x % 8)) != 0 {
+                    mask[y * self.width + x] = 255;
+                }
+            }
+        }
+        mask
+    }
+
+    /// Draw hex bitmap rows `w` pixels wide into a cell-sized mask at `(left, top)`.
+    fn place_bdf(
+        &self,
+        rows: &[&str],
+        w: usize,
+        (left, top): (i32, i32),
+    ) -> Result<Vec<u8>, FontError> {
+        let mut mask = vec![0u8; self.width * self.height];
+        for (dy, row) in rows.iter().enumerate() {
+            let bits = (0..row.len())
+                .step_by(2)
+                .map(|i| {
+                    row.get(i..i + 2)
+                        .and_then(|b| u8::from_str_radix(b, 16).ok())
+                })
+                .collect::<Option<Vec<u8>>>()
+                .ok_or_else(|| bad_line(row))?;
+            for dx in 0..w.min(bits.len() * 8) {
+                if bits[dx / 8] & (0x80 >> (dx % 8)) == 0 {
+                    continue;
+                }
+                let (x, y) = (left + dx as i32, top + dy as i32);
+                if (0..self.width as i32).contains(&x) && (0..self.height as i32).contains(&y) {
+                    mask[y as usize * self.width + x as usize] = 255;
+                }
+            }
+        }
+        Ok(mask)
+    }
+}
+
+fn bad_line(line: &str) -> FontError {
+    FontError::Invalid(format!("unexpected line {line:?}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BDF: &str = "STARTFONT 2.1
+FONT -test-fixed-medium-r-normal--4-40-75-75-c-40-iso10646-1
+SIZE 4 75 75
+FONTBOUNDINGBOX 4 4 0 -1
+CHARS 2
+STARTCHAR A
+ENCODING 65
+BBX 3 3 0 0
+BITMAP
+40
+A0
+E0
+ENDCHAR
+STARTCHAR box
+ENCODING 9472
+BBX 4 1 0 1
+BITMAP
+F0
+ENDCHAR
+ENDFONT
+";
+
+    fn psf2(unicode: &[u8]) -> Vec<u8> {
+        let mut bytes = PSF2_MAGIC.to_vec();
+        let flags = u32::from(!unicode.is_empty());
+        for field in [0, 32, flags, 2, 2, 2, 4] {
+            bytes.extend(u32::to_le_bytes(field));
+        }
+        // Glyph 0: a 4-wide bar on the top row; glyph 1: a dot on the second row.
+        bytes.extend([0xf0, 0x00, 0x00, 0x40]);
+        bytes.extend(unicode);
+        bytes
+    }
+
+    #[test]
+    fn test_bdf_glyphs_sit_on_the_baseline() {
+        let font = BitmapFont::parse(BDF.as_bytes()).unwrap();
+        assert_eq!((font.width, font.height), (4, 4));
+        assert_eq!(font.len(), 2);
+        #[rustfmt::skip]
+        let a = [
+            0, 255, 0, 0,
+            255, 0, 255, 0,
+            255, 255, 255, 0,
+            0, 0, 0, 0,
+        ];
+        assert_eq!(font.glyph('A').unwrap(), a);
+        assert_eq!(&font.glyph('─').unwrap()[4..8], [255; 4]);
+    }
+
+    #[test]
+    fn test_psf2_with_and_without_unicode_table() {
+        let font = BitmapFont::parse(&psf2(&[])).unwrap();
+        assert_eq!((font.width, font.height), (4, 2));
+        assert_eq!(
+            font.glyph('\u{0}').unwrap(),
+            [255, 255, 255, 255, 0, 0, 0, 0]
+        );
+
+        let mut table = "─━".as_bytes().to_vec();
+        table.push(0xff);
+        table.extend("·".as_bytes());
+        table.extend([0xfe, b'x', 0xff]);
+        let font = BitmapFont::parse(&psf2(&table)).unwrap();
+        assert_eq!(font.glyph('━'), font.glyph('─'));
+        assert_eq!(font.glyph('·').unwrap()[5], 255);
+        assert!(font.glyph('x').is_none());
+    }
+
+    #[test]
+    fn test_rejects_bad_fonts() {
+        assert_eq!(
+            BitmapFont::parse(b"hello").unwrap_err(),
+            FontError::UnknownFormat
+        );
+        let bytes = psf2(&[]);
+        assert_eq!(
+            BitmapFont::parse(&bytes[..bytes.len() - 1]).unwrap_err(),
+            FontError::Truncated
+        );
+        let huge = BDF.replace("FONTBOUNDINGBOX 4 4", "FONTBOUNDINGBOX 400 4");
+        assert!(matches!(
+            BitmapFont::parse(huge.as_bytes()),
+            Err(FontError::UnsupportedSize { width: 400, .. })
+        ));
+    }
+}
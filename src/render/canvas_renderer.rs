@@ -70,6 +70,32 @@ impl CanvasRenderer {
         self.canvas_height = height;
     }
 
+    /// Get canvas size.
+    pub fn canvas_size(&self) -> (f64, f64) {
+        (self.canvas_width, self.canvas_height)
+    }
+
+    /// Grid area shown on the canvas, `(x0, y0, x1, y1)` in fractional cells.
+    pub fn visible_grid_rect(&self) -> (f64, f64, f64, f64) {
+        let cell_w = self.metrics.char_width * self.zoom;
+        let cell_h = self.metrics.line_height * self.zoom;
+        let (x0, y0) = (-self.pan_x / cell_w, -self.pan_y / cell_h);
+        (
+            x0,
+            y0,
+            x0 + self.canvas_width / cell_w,
+            y0 + self.canvas_height / cell_h,
+        )
+    }
+
+    /// Pan offset that puts a (fractional) grid position in the middle of the canvas.
+    pub fn pan_centering(&self, grid_x: f64, grid_y: f64) -> (f64, f64) {
+        (
+            self.canvas_width / 2.0 - grid_x * self.metrics.char_width * self.zoom,
+            self.canvas_height / 2.0 - grid_y * self.metrics.line_height * self.zoom,
+        )
+    }
+
     /// Set background color.
     pub fn set_bg_color(&mut self, color: impl Into<String>) {
         self.bg_color = color.into();
@@ -154,6 +180,7 @@ impl CanvasRenderer {
                         y: sy + self.metrics.baseline * self.zoom,
                         char: cell.ch,
                         scale: self.zoom,
+                        style: cell.style.bits(),
                     });
                 }
             }
@@ -215,6 +242,7 @@ impl CanvasRenderer {
                     y: sy + self.metrics.baseline * self.zoom,
                     char: cell.ch,
                     scale: self.zoom,
+                    style: cell.style.bits(),
                 });
             }
         }
@@ -228,6 +256,7 @@ impl CanvasRenderer {
                     y: sy + self.metrics.baseline * self.zoom,
                     char: op.cell.ch,
                     scale: self.zoom,
+                    style: op.cell.style.bits(),
                 });
             }
         }
@@ -287,6 +316,9 @@ pub enum RenderCommand {
         char: char,
         /// Font scaling factor
         scale: f64,
+        /// `CellStyle` bits: bold, italic, underline, highlight (omitted when plain)
+        #[serde(default, skip_serializing_if = "is_plain")]
+        style: u8,
     },
     /// Draw a preview character at position
     DrawPreviewChar {
@@ -298,6 +330,9 @@ pub enum RenderCommand {
         char: char,
         /// Font scaling factor
         scale: f64,
+        /// `CellStyle` bits: bold, italic, underline, highlight (omitted when plain)
+        #[serde(default, skip_serializing_if = "is_plain")]
+        style: u8,
     },
     /// Draw a rectangle (for selection)
     DrawRect {
@@ -319,6 +354,10 @@ pub enum RenderCommand {
     },
 }
 
+fn is_plain(style: &u8) -> bool {
+    *style == 0
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -351,6 +390,22 @@ mod tests {
         assert_eq!(gy, 3);
     }
 
+    #[test]
+    fn test_visible_grid_rect_follows_zoom_and_pan() {
+        let mut renderer = CanvasRenderer::new();
+        renderer.set_metrics(FontMetrics {
+            char_width: 8.0,
+            ..FontMetrics::default()
+        });
+        renderer.set_canvas_size(400.0, 200.0);
+        renderer.set_zoom(2.0);
+        renderer.set_pan(-160.0, -40.0);
+        assert_eq!(renderer.visible_grid_rect(), (10.0, 1.0, 35.0, 6.0));
+
+        let (pan_x, pan_y) = renderer.pan_centering(22.5, 3.5);
+        assert_eq!((pan_x, pan_y), (-160.0, -40.0));
+    }
+
     #[test]
     fn test_build_render_commands() {
         let renderer = CanvasRenderer::new();
@@ -379,4 +434,25 @@ mod tests {
         }
         assert!(found_preview, "Should have found a DrawPreviewChar command");
     }
+
+    #[test]
+    fn test_draw_char_carries_the_cell_style() {
+        let renderer = CanvasRenderer::new();
+        let mut grid = Grid::new(4, 1);
+        grid.set(0, 0, crate::core::Cell::new('a'));
+        grid.set(
+            1,
+            0,
+            crate::core::Cell::with_style('b', crate::core::CellStyle::BOLD),
+        );
+
+        let commands = renderer.build_render_commands(&grid, &DirtyRect::full(4, 1));
+        let json: Vec<_> = commands
+            .iter()
+            .filter(|c| matches!(c, RenderCommand::DrawChar { .. }))
+            .map(|c| serde_json::to_value(c).unwrap())
+            .collect();
+        assert!(json[0].get("style").is_none(), "plain cells omit the style");
+        assert_eq!(json[1]["style"], 1);
+    }
 }
@@ -0,0 +1,231 @@
+//! Cursor overlay for the pixel buffer - text caret, marching-ants selection border
+//! and the hover outline of the current tool.
+//!
+//! The overlay is animated by a frame time supplied by the caller. Comparing two
+//! overlay states gives the cells whose pixels differ, so an animation step repaints
+//! the caret cell or the selection border instead of the whole canvas.
+
+use super::grid_overlay::{clipped, Target};
+use crate::render::{DirtyRect, PixelLayout, PixelRect};
+
+/// How long the caret stays on, then off, in milliseconds.
+pub const CARET_BLINK_MS: f64 = 600.0;
+/// How long the marching ants take to advance one pixel, in milliseconds.
+pub const ANTS_STEP_MS: f64 = 80.0;
+/// Marching-ants dash length in pixels.
+const DASH: i32 = 4;
+/// Alpha of the caret cell fill (its outline is drawn at full strength).
+const CARET_FILL_ALPHA: u8 = 64;
+
+/// Colors of the cursor overlay.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct OverlayColors {
+    /// Caret cell outline and fill
+    pub caret: [u8; 4],
+    /// Hover outline
+    pub hover: [u8; 4],
+    /// The two alternating marching-ants dash colors
+    pub ants: [[u8; 4]; 2],
+}
+
+/// Cursor decorations at one frame, in canvas cells.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct CursorOverlay {
+    /// Text caret cell
+    pub caret: Option<(i32, i32)>,
+    /// Selection bounds, outlined with marching ants
+    pub selection: Option<DirtyRect>,
+    /// Cells under the pointer for the current tool (the eraser footprint, say)
+    pub hover: Option<DirtyRect>,
+    /// Frame time in milliseconds
+    pub time_ms: f64,
+    /// Frame time the caret last moved; it blinks from there, starting visible
+    pub caret_since_ms: f64,
+}
+
+impl CursorOverlay {
+    /// Whether the caret is drawn at this frame.
+    pub fn caret_on(&self) -> bool {
+        ((self.time_ms - self.caret_since_ms).max(0.0) / CARET_BLINK_MS) as u64 % 2 == 0
+    }
+
+    /// Marching-ants offset in pixels, `0..2 * DASH`.
+    pub fn ants_phase(&self) -> i32 {
+        ((self.time_ms.max(0.0) / ANTS_STEP_MS) as u64 % (2 * DASH) as u64) as i32
+    }
+
+    /// Whether the overlay changes with time alone (caret blink, marching ants).
+    pub fn is_animated(&self) -> bool {
+        self.caret.is_some() || self.selection.is_some()
+    }
+
+    /// Cells drawn differently in `self` than in `previous`.
+    pub fn changed_cells(&self, previous: &CursorOverlay) -> Vec<DirtyRect> {
+        let mut cells = Vec::new();
+        let caret = |o: &CursorOverlay| o.caret.filter(|_| o.caret_on());
+        if caret(self) != caret(previous) {
+            let moved = caret(previous).into_iter().chain(caret(self));
+            cells.extend(moved.map(|(x, y)| DirtyRect::single(x, y)));
+        }
+        let ants = |o: &CursorOverlay| o.selection.map(|rect| (rect, o.ants_phase()));
+        if ants(self) != ants(previous) {
+            outline_changes(previous.selection, self.selection, &mut cells);
+        }
+        if self.hover != previous.hover {
+            outline_changes(previous.hover, self.hover, &mut cells);
+        }
+        cells
+    }
+
+    /// Draw the overlay for the cells in `dirty`. Call after the glyphs.
+    pub fn draw(
+        &self,
+        buffer: &mut [u8],
+        layout: &PixelLayout,
+        dirty: &DirtyRect,
+        colors: &OverlayColors,
+    ) {
+        let mut target = clipped(buffer, layout, dirty);
+        if let Some(hover) = self.hover {
+            outline(&mut target, cell_bounds(layout, &hover), |_, _| {
+                colors.hover
+            });
+        }
+        if let Some(selection) = self.selection {
+            // Dashes are anchored to the canvas origin so scrolled pixels stay valid.
+            let (ox, oy) = layout.origin;
+            let phase = self.ants_phase();
+            outline(&mut target, cell_bounds(layout, &selection), |x, y| {
+                colors.ants[((x - ox + y - oy - phase).div_euclid(DASH) & 1) as usize]
+            });
+        }
+        if let Some((cx, cy)) = self.caret.filter(|_| self.caret_on()) {
+            let (x0, y0, x1, y1) = cell_bounds(layout, &DirtyRect::single(cx, cy));
+            let [r, g, b, _] = colors.caret;
+            for y in y0..y1 {
+                target.hline(x0, x1, y, [r, g, b, CARET_FILL_ALPHA]);
+            }
+            outline(&mut target, (x0, y0, x1, y1), |_, _| colors.caret);
+        }
+    }
+}
+
+/// Mark the border cells of an outline that moved, changed or disappeared.
+fn outline_changes(
+    previous: Option<DirtyRect>,
+    next: Option<DirtyRect>,
+    cells: &mut Vec<DirtyRect>,
+) {
+    let rects = previous
+        .into_iter()
+        .chain(next.filter(|r| Some(*r) != previous));
+    for r in rects {
+        cells.extend([
+            DirtyRect::from_points(r.x1, r.y1, r.x2, r.y1),
+            DirtyRect::from_points(r.x1, r.y2, r.x2, r.y2),
+            DirtyRect::from_points(r.x1, r.y1, r.x1, r.y2),
+            DirtyRect::from_points(r.x2, r.y1, r.x2, r.y2),
+        ]);
+    }
+}
+
+/// Unclipped pixel bounds of the cells in `rect`, `(x0, y0, x1, y1)` exclusive.
+pub(super) fn cell_bounds(layout: &PixelLayout, rect: &DirtyRect) -> (i32, i32, i32, i32) {
+    let (x0, y0) = layout.cell_origin(rect.x1, rect.y1);
+    let (x1, y1) = layout.cell_origin(rect.x2 + 1, rect.y2 + 1);
+    (x0, y0, x1, y1)
+}
+
+/// One-pixel outline just inside `bounds`, colored per pixel.
+pub(super) fn outline(
+    target: &mut Target,
+    (x0, y0, x1, y1): (i32, i32, i32, i32),
+    color: impl Fn(i32, i32) -> [u8; 4],
+) {
+    let (cx0, cy0, cx1, cy1): PixelRect = target.clip;
+    for x in x0.max(cx0 as i32)..x1.min(cx1 as i32) {
+        target.blend(x, y0, color(x, y0));
+        target.blend(x, y1 - 1, color(x, y1 - 1));
+    }
+    for y in y0.max(cy0 as i32)..y1.min(cy1 as i32) {
+        target.blend(x0, y, color(x0, y));
+        target.blend(x1 - 1, y, color(x1 - 1, y));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LAYOUT: PixelLayout = PixelLayout {
+        width: 16,
+        height: 16,
+        cell: (4, 4),
+        origin: (0, 0),
+    };
+    const COLORS: OverlayColors = OverlayColors {
+        caret: [255, 0, 0, 255],
+        hover: [0, 255, 0, 255],
+        ants: [[255, 255, 255, 255], [0, 0, 255, 255]],
+    };
+
+    fn pixel(buffer: &[u8], x: usize, y: usize) -> [u8; 3] {
+        let i = (y * 16 + x) * 4;
+        [buffer[i], buffer[i + 1], buffer[i + 2]]
+    }
+
+    #[test]
+    fn test_animation_changes_only_the_caret_and_border() {
+        let overlay = CursorOverlay {
+            caret: Some((1, 1)),
+            selection: Some(DirtyRect::from_points(0, 0, 3, 2)),
+            ..Default::default()
+        };
+        assert!(overlay.caret_on());
+
+        let blink = CursorOverlay {
+            time_ms: CARET_BLINK_MS,
+            ..overlay
+        };
+        assert!(!blink.caret_on());
+        // The blink crosses an ants step too; the border is four edge strips.
+        let changed = blink.changed_cells(&overlay);
+        assert_eq!(changed[0], DirtyRect::single(1, 1));
+        assert_eq!(changed[1..].len(), 4);
+        assert!(changed[1..]
+            .iter()
+            .all(|r| r.width() == 1 || r.height() == 1));
+
+        let still = CursorOverlay {
+            time_ms: ANTS_STEP_MS / 2.0,
+            ..overlay
+        };
+        assert!(still.changed_cells(&overlay).is_empty());
+    }
+
+    #[test]
+    fn test_draws_caret_ants_and_hover_outline() {
+        let overlay = CursorOverlay {
+            caret: Some((3, 3)),
+            selection: Some(DirtyRect::from_points(0, 0, 1, 1)),
+            hover: Some(DirtyRect::single(2, 0)),
+            ..Default::default()
+        };
+        let mut buffer = vec![0u8; 16 * 16 * 4];
+        overlay.draw(&mut buffer, &LAYOUT, &DirtyRect::full(4, 4), &COLORS);
+
+        // Ants alternate every four pixels along the selection edge.
+        assert_eq!(pixel(&buffer, 0, 0), [255, 255, 255]);
+        assert_eq!(pixel(&buffer, 4, 0), [0, 0, 255]);
+        assert_eq!(pixel(&buffer, 3, 3), [0, 0, 0], "inside stays untouched");
+        assert_eq!(pixel(&buffer, 8, 3), [0, 255, 0]);
+        assert_eq!(pixel(&buffer, 12, 12), [255, 0, 0]);
+        assert_eq!(pixel(&buffer, 13, 13), [64, 0, 0]);
+
+        // A redraw limited to one cell leaves the rest of the outline alone.
+        let mut partial = vec![0u8; 16 * 16 * 4];
+        overlay.draw(&mut partial, &LAYOUT, &DirtyRect::single(0, 0), &COLORS);
+        assert_eq!(pixel(&partial, 0, 0), [255, 255, 255]);
+        assert_eq!(pixel(&partial, 4, 0), [0, 0, 0]);
+    }
+}
@@ -2,8 +2,15 @@
 
 use serde::{Deserialize, Serialize};
 
+/// Dirty regions at most this many cells apart are merged into one.
+const MERGE_GAP: i32 = 2;
+/// More separate regions than this fall back to a full redraw.
+pub const MAX_DIRTY_REGIONS: usize = 16;
+/// Once the regions cover this share of the grid (in percent), a full redraw is cheaper.
+const FULL_REDRAW_PERCENT: usize = 50;
+
 /// A rectangular region that needs to be redrawn.
-#[derive(Clone, Debug, Copy, Serialize, Deserialize)]
+#[derive(Clone, Debug, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub struct DirtyRect {
     /// Minimum X coordinate (inclusive)
     pub x1: i32,
@@ -121,6 +128,21 @@ impl DirtyRect {
         self.y2 = self.y2.max(other.y2);
     }
 
+    /// The cells in both rects.
+    pub fn intersection(&self, other: &DirtyRect) -> DirtyRect {
+        let rect = Self {
+            x1: self.x1.max(other.x1),
+            y1: self.y1.max(other.y1),
+            x2: self.x2.min(other.x2),
+            y2: self.y2.min(other.y2),
+        };
+        if rect.is_empty() {
+            Self::empty()
+        } else {
+            rect
+        }
+    }
+
     /// Clamp the rect to grid bounds.
     pub fn clamp(&mut self, width: usize, height: usize) {
         self.x1 = self.x1.max(0).min(width as i32 - 1);
@@ -129,6 +151,16 @@ impl DirtyRect {
         self.y2 = self.y2.max(0).min(height as i32 - 1);
     }
 
+    /// Whether the rects overlap or are at most `gap` cells apart on both axes.
+    pub fn is_near(&self, other: &DirtyRect, gap: i32) -> bool {
+        !self.is_empty()
+            && !other.is_empty()
+            && other.x1 <= self.x2 + 1 + gap
+            && self.x1 <= other.x2 + 1 + gap
+            && other.y1 <= self.y2 + 1 + gap
+            && self.y1 <= other.y2 + 1 + gap
+    }
+
     /// Check if a point is inside the rect.
     pub fn contains(&self, x: i32, y: i32) -> bool {
         x >= self.x1 && x <= self.x2 && y >= self.y1 && y <= self.y2
@@ -141,12 +173,20 @@ impl DirtyRect {
 }
 
 /// Tracker for dirty regions.
+///
+/// Changes are kept as a short list of rectangles; nearby ones are coalesced so
+/// edits in opposite corners of the canvas repaint two small areas rather than the
+/// whole grid in between.
 #[derive(Clone, Debug, Default)]
 pub struct DirtyTracker {
     /// Current dirty rect (union of all dirty regions)
     dirty: DirtyRect,
+    /// Dirty regions, none of them within `MERGE_GAP` cells of another
+    regions: Vec<DirtyRect>,
     /// Whether a full redraw is needed
     needs_full_redraw: bool,
+    /// Whether the view was panned (content unchanged)
+    panned: bool,
 }
 
 impl DirtyTracker {
@@ -157,12 +197,45 @@ impl DirtyTracker {
 
     /// Mark a single cell as dirty.
     pub fn mark_dirty(&mut self, x: i32, y: i32) {
-        self.dirty.include(x, y);
+        self.add_region(DirtyRect::single(x, y));
     }
 
     /// Mark a region as dirty.
     pub fn mark_region_dirty(&mut self, x1: i32, y1: i32, x2: i32, y2: i32) {
-        self.dirty.union(&DirtyRect::from_points(x1, y1, x2, y2));
+        self.add_region(DirtyRect::from_points(x1, y1, x2, y2));
+    }
+
+    /// Add a region, merging it with every region near it. Past `MAX_DIRTY_REGIONS`
+    /// separate regions, a full redraw is requested instead.
+    fn add_region(&mut self, mut rect: DirtyRect) {
+        self.dirty.union(&rect);
+        if self.needs_full_redraw {
+            return;
+        }
+        while let Some(i) = self
+            .regions
+            .iter()
+            .position(|r| r.is_near(&rect, MERGE_GAP))
+        {
+            rect.union(&self.regions.swap_remove(i));
+        }
+        self.regions.push(rect);
+        if self.regions.len() > MAX_DIRTY_REGIONS {
+            self.regions.clear();
+            self.needs_full_redraw = true;
+        }
+    }
+
+    /// The separate dirty regions (meaningless once a full redraw is requested).
+    pub fn regions(&self) -> &[DirtyRect] {
+        &self.regions
+    }
+
+    /// Whether the regions cover so much of a `width`×`height` grid that redrawing
+    /// all of it is cheaper.
+    pub fn covers_most_of(&self, width: usize, height: usize) -> bool {
+        let area: usize = self.regions.iter().map(|r| r.area() as usize).sum();
+        area * 100 > width * height * FULL_REDRAW_PERCENT
     }
 
     /// Request a full redraw.
@@ -170,6 +243,22 @@ impl DirtyTracker {
         self.needs_full_redraw = true;
     }
 
+    /// Record that the view was panned without any content changing.
+    pub fn mark_panned(&mut self) {
+        self.panned = true;
+    }
+
+    /// Whether the view was panned since the last clear.
+    pub fn panned(&self) -> bool {
+        self.panned
+    }
+
+    /// Whether a full redraw was explicitly requested (unlike `needs_full_redraw`,
+    /// an empty dirty rect does not count).
+    pub fn full_redraw_requested(&self) -> bool {
+        self.needs_full_redraw
+    }
+
     /// Get the current dirty rect.
     pub fn dirty_rect(&self) -> &DirtyRect {
         &self.dirty
@@ -183,7 +272,9 @@ impl DirtyTracker {
     /// Clear the dirty state (after rendering).
     pub fn clear(&mut self) {
         self.dirty = DirtyRect::empty();
+        self.regions.clear();
         self.needs_full_redraw = false;
+        self.panned = false;
     }
 }
 
@@ -220,6 +311,14 @@ mod tests {
         assert_eq!(rect.y2, 10);
     }
 
+    #[test]
+    fn test_intersection() {
+        let rect = DirtyRect::from_points(0, 0, 5, 5);
+        let both = rect.intersection(&DirtyRect::from_points(3, 4, 9, 9));
+        assert_eq!((both.x1, both.y1, both.x2, both.y2), (3, 4, 5, 5));
+        assert!(rect.intersection(&DirtyRect::single(6, 0)).is_empty());
+    }
+
     #[test]
     fn test_dirty_tracker() {
         let mut tracker = DirtyTracker::new();
@@ -234,4 +333,34 @@ mod tests {
         tracker.clear();
         assert!(tracker.dirty_rect().is_empty());
     }
+
+    #[test]
+    fn test_far_apart_changes_stay_separate() {
+        let mut tracker = DirtyTracker::new();
+        tracker.mark_dirty(0, 0);
+        tracker.mark_dirty(99, 49);
+        assert_eq!(tracker.regions().len(), 2);
+        assert_eq!(tracker.dirty_rect().area(), 100 * 50);
+        assert!(!tracker.covers_most_of(100, 50));
+
+        // A region bridging both gaps merges all three.
+        tracker.mark_dirty(2, 2);
+        assert_eq!(tracker.regions().len(), 2);
+        tracker.mark_region_dirty(4, 4, 96, 46);
+        assert_eq!(tracker.regions().len(), 1);
+        assert!(tracker.covers_most_of(100, 50));
+    }
+
+    #[test]
+    fn test_too_many_regions_request_a_full_redraw() {
+        let mut tracker = DirtyTracker::new();
+        for i in 0..=MAX_DIRTY_REGIONS as i32 {
+            assert!(!tracker.full_redraw_requested());
+            tracker.mark_dirty(i * 10, 0);
+        }
+        assert!(tracker.full_redraw_requested());
+        assert!(tracker.regions().is_empty());
+        tracker.clear();
+        assert!(!tracker.full_redraw_requested());
+    }
 }
@@ -5,7 +5,14 @@
 
 use std::collections::HashMap;
 
-/// A simple 8x14 monospace bitmap font.
+use crate::core::CellStyle;
+use crate::render::BitmapFont;
+
+/// Size of the embedded placeholder glyphs, which are scaled to the atlas size.
+const PLACEHOLDER_WIDTH: usize = 8;
+const PLACEHOLDER_HEIGHT: usize = 20;
+
+/// Monospace glyph alpha masks: an embedded placeholder font or a loaded bitmap font.
 pub struct FontAtlas {
     /// Glyph width in pixels
     pub glyph_width: usize,
@@ -41,46 +48,85 @@ impl Default for FontAtlas {
 }
 
 impl FontAtlas {
-    /// Create a new font atlas with a simple embedded font.
+    /// Create a new font atlas with a simple embedded 8×20 font.
     pub fn new() -> Self {
-        let glyph_width = 8;
-        let glyph_height = 20;
-        let mut glyph_indices = HashMap::new();
-        let mut data = Vec::new();
-
-        let add_glyph = |ch: char, data: &mut Vec<u8>, indices: &mut HashMap<char, usize>| {
-            let idx = indices.len();
-            indices.insert(ch, idx);
-            let mut glyph_data = vec![0u8; glyph_width * glyph_height];
-            Self::render_glyph_placeholder(&mut glyph_data, ch);
-            data.extend(glyph_data);
-        };
-
-        // Basic ASCII
-        for c in 32..127u8 {
-            add_glyph(c as char, &mut data, &mut glyph_indices);
+        Self::with_size(PLACEHOLDER_WIDTH, PLACEHOLDER_HEIGHT)
+    }
+
+    /// Create the embedded font scaled to `glyph_width`×`glyph_height` pixels.
+    pub fn with_size(glyph_width: usize, glyph_height: usize) -> Self {
+        let mut atlas = Self::empty(glyph_width, glyph_height);
+        for ch in Self::placeholder_chars() {
+            atlas.add_placeholder(ch);
         }
+        atlas
+    }
 
-        // Box drawing characters and symbols
-        let box_chars = [
-            '┌', '┐', '└', '┘', '─', '│', '╔', '╗', '╚', '╝', '═', '║', '┏', '┓', '┗', '┛', '━',
-            '┃', '╭', '╮', '╰', '╯', '+', '-', '|', '*', '·', '•', '●', '▲', '▼', '◄', '►', '╱',
-            '╲', '◆',
-        ];
-        for &ch in &box_chars {
-            if !glyph_indices.contains_key(&ch) {
-                add_glyph(ch, &mut data, &mut glyph_indices);
+    /// Create an atlas from a loaded bitmap font, scaled to `glyph_width`×`glyph_height`
+    /// pixels. Characters of the embedded font that the font lacks keep their
+    /// placeholder glyphs.
+    pub fn from_font(font: &BitmapFont, glyph_width: usize, glyph_height: usize) -> Self {
+        let mut atlas = Self::empty(glyph_width, glyph_height);
+        let mut chars: Vec<char> = font.chars().collect();
+        chars.sort_unstable();
+        for ch in chars {
+            if let Some(mask) = font.glyph(ch) {
+                let glyph =
+                    scale_mask(mask, (font.width, font.height), (glyph_width, glyph_height));
+                atlas.push_glyph(ch, &glyph);
+            }
+        }
+        for ch in Self::placeholder_chars() {
+            if !atlas.glyph_indices.contains_key(&ch) {
+                atlas.add_placeholder(ch);
             }
         }
+        atlas
+    }
+
+    /// Whether the atlas has a glyph for `ch` (others render as `?`).
+    pub fn has_glyph(&self, ch: char) -> bool {
+        self.glyph_indices.contains_key(&ch)
+    }
 
+    fn empty(glyph_width: usize, glyph_height: usize) -> Self {
         Self {
-            glyph_width,
-            glyph_height,
-            glyph_indices,
-            data,
+            glyph_width: glyph_width.max(1),
+            glyph_height: glyph_height.max(1),
+            glyph_indices: HashMap::new(),
+            data: Vec::new(),
         }
     }
 
+    fn push_glyph(&mut self, ch: char, mask: &[u8]) {
+        self.glyph_indices.insert(ch, self.glyph_indices.len());
+        self.data.extend_from_slice(mask);
+    }
+
+    /// Draw the embedded glyph for `ch` and scale it to the atlas size.
+    fn add_placeholder(&mut self, ch: char) {
+        let mut glyph = vec![0u8; PLACEHOLDER_WIDTH * PLACEHOLDER_HEIGHT];
+        Self::render_glyph_placeholder(&mut glyph, ch);
+        let glyph = scale_mask(
+            &glyph,
+            (PLACEHOLDER_WIDTH, PLACEHOLDER_HEIGHT),
+            (self.glyph_width, self.glyph_height),
+        );
+        self.push_glyph(ch, &glyph);
+    }
+
+    /// Basic ASCII, then box drawing characters and symbols.
+    fn placeholder_chars() -> impl Iterator<Item = char> {
+        const BOX_CHARS: [char; 36] = [
+            '┌', '┐', '└', '┘', '─', '│', '╔', '╗', '╚', '╝', '═', '║', '┏', '┓', '┗', '┛', '━',
+            '┃', '╭', '╮', '╰', '╯', '+', '-', '|', '*', '·', '•', '●', '▲', '▼', '◄', '►', '╱',
+            '╲', '◆',
+        ];
+        (32..127u8)
+            .map(char::from)
+            .chain(BOX_CHARS.into_iter().filter(|ch| !ch.is_ascii()))
+    }
+
     /// Render a glyph into the pixel buffer.
     pub fn render_glyph(
         &self,
@@ -91,47 +137,47 @@ impl FontAtlas {
         ch: char,
         color: [u8; 4],
     ) {
-        if let Some(&idx) = self
+        let height = buffer.len() / 4 / buffer_width.max(1);
+        self.render_glyph_clipped(
+            buffer,
+            buffer_width,
+            (0, 0, buffer_width, height),
+            (x as i32, y as i32),
+            ch,
+            color,
+        );
+    }
+
+    /// Render a glyph with its top-left corner at `(x, y)`, which may lie outside the
+    /// buffer, drawing only the pixels inside `clip` (`x0, y0, x1, y1`, exclusive ends).
+    pub fn render_glyph_clipped(
+        &self,
+        buffer: &mut [u8],
+        buffer_width: usize,
+        clip: (usize, usize, usize, usize),
+        origin: (i32, i32),
+        ch: char,
+        color: [u8; 4],
+    ) {
+        self.render_glyph_styled(
+            buffer,
+            buffer_width,
+            clip,
+            origin,
+            ch,
+            color,
+            CellStyle::NONE,
+        );
+    }
+
+    /// Alpha mask of the glyph for `ch`, or of `?` when the atlas lacks it.
+    pub(crate) fn mask(&self, ch: char) -> Option<&[u8]> {
+        let idx = *self
             .glyph_indices
             .get(&ch)
-            .or_else(|| self.glyph_indices.get(&'?'))
-        {
-            let glyph_offset = idx * self.glyph_width * self.glyph_height;
-            let color_f = [color[0] as f32, color[1] as f32, color[2] as f32];
-
-            for gy in 0..self.glyph_height {
-                let buffer_y = y + gy;
-                let glyph_row_offset = glyph_offset + gy * self.glyph_width;
-                let buffer_row_start = (buffer_y * buffer_width + x) * 4;
-
-                for gx in 0..self.glyph_width {
-                    let mask = self.data[glyph_row_offset + gx];
-                    if mask > 0 {
-                        let pixel_idx = buffer_row_start + gx * 4;
-
-                        if pixel_idx + 3 < buffer.len() {
-                            let effective_alpha = (mask as f32 / 255.0) * (color[3] as f32 / 255.0);
-                            if effective_alpha >= 1.0 {
-                                buffer[pixel_idx..pixel_idx + 3].copy_from_slice(&color[0..3]);
-                            } else {
-                                let inv_alpha = 1.0 - effective_alpha;
-
-                                buffer[pixel_idx] = (color_f[0] * effective_alpha
-                                    + buffer[pixel_idx] as f32 * inv_alpha)
-                                    as u8;
-                                buffer[pixel_idx + 1] = (color_f[1] * effective_alpha
-                                    + buffer[pixel_idx + 1] as f32 * inv_alpha)
-                                    as u8;
-                                buffer[pixel_idx + 2] = (color_f[2] * effective_alpha
-                                    + buffer[pixel_idx + 2] as f32 * inv_alpha)
-                                    as u8;
-                            }
-                            buffer[pixel_idx + 3] = 255;
-                        }
-                    }
-                }
-            }
-        }
+            .or_else(|| self.glyph_indices.get(&'?'))?;
+        let size = self.glyph_width * self.glyph_height;
+        self.data.get(idx * size..(idx + 1) * size)
     }
 
     fn render_glyph_placeholder(glyph_data: &mut [u8], ch: char) {
@@ -271,6 +317,21 @@ impl FontAtlas {
     }
 }
 
+/// Nearest-neighbour resize of a `from` (width, height) alpha mask to `to`.
+fn scale_mask(mask: &[u8], from: (usize, usize), to: (usize, usize)) -> Vec<u8> {
+    if from == to {
+        return mask.to_vec();
+    }
+    let mut out = vec![0u8; to.0 * to.1];
+    for y in 0..to.1 {
+        let sy = y * from.1 / to.1;
+        for x in 0..to.0 {
+            out[y * to.0 + x] = mask[sy * from.0 + x * from.0 / to.0];
+        }
+    }
+    out
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -284,6 +345,19 @@ mod tests {
         assert!(atlas.glyph_indices.contains_key(&'┌'));
     }
 
+    #[test]
+    fn test_atlas_sizes() {
+        let atlas = FontAtlas::with_size(16, 40);
+        let dash = atlas.glyph_indices[&'-'] * 16 * 40;
+        // The placeholder dash on row 10 of 20 lands on rows 20 and 21 of 40.
+        assert!(atlas.data[dash + 20 * 16..dash + 22 * 16]
+            .iter()
+            .all(|&a| a == 255));
+        assert!(atlas.data[dash + 19 * 16..dash + 20 * 16]
+            .iter()
+            .all(|&a| a == 0));
+    }
+
     #[test]
     fn test_render_glyph_placeholder() {
         let mut glyph_data = vec![0u8; 8 * 20];
@@ -354,4 +428,26 @@ mod tests {
         assert_eq!(buffer[2], (fg_color[2] as f32 * effective_alpha) as u8);
         assert_eq!(buffer[3], 255);
     }
+
+    #[test]
+    fn test_render_glyph_clipped_does_not_wrap() {
+        const COLOR: [u8; 4] = [9, 9, 9, 255];
+        let mut atlas = FontAtlas::new();
+        let mut custom_mask = vec![0u8; 8 * 20];
+        custom_mask[0] = 255;
+        custom_mask[7] = 255;
+        atlas.update_glyph('?', &custom_mask);
+
+        // Glyph starts 6 pixels left of a 4-pixel-wide buffer: only column 7 lands.
+        let mut buffer = vec![0u8; 4 * 20 * 4];
+        atlas.render_glyph_clipped(&mut buffer, 4, (0, 0, 4, 20), (-6, 0), '?', COLOR);
+        assert_eq!(buffer[4], 9);
+        assert_eq!(buffer.iter().filter(|&&b| b == 9).count(), 3);
+
+        // Starting 2 pixels from the right edge, column 7 must not wrap to row 1.
+        buffer.fill(0);
+        atlas.render_glyph_clipped(&mut buffer, 4, (0, 0, 4, 20), (2, 0), '?', COLOR);
+        assert_eq!(buffer[2 * 4], 9);
+        assert!(buffer[16..].iter().all(|&b| b != 9));
+    }
 }
@@ -0,0 +1,148 @@
+//! Synthetic text styles for bitmap glyphs.
+//!
+//! Bitmap fonts come with a single face, so bold, italic and underline are made up
+//! when a glyph is drawn: bold smears each row to the right, italic shears rows
+//! sideways (top to the right), and underline is a bar near the bottom of the cell.
+//! Everything stays inside the glyph cell, so redrawing one cell never leaves
+//! traces in its neighbours.
+
+use crate::core::CellStyle;
+use crate::render::{FontAtlas, PixelRect};
+
+impl FontAtlas {
+    /// Render a glyph like `render_glyph_clipped`, applying the text parts of `style`
+    /// (bold, italic, underline). Highlight is a cell background and is left to the
+    /// caller.
+    #[allow(clippy::too_many_arguments)]
+    pub fn render_glyph_styled(
+        &self,
+        buffer: &mut [u8],
+        buffer_width: usize,
+        clip: PixelRect,
+        (x, y): (i32, i32),
+        ch: char,
+        color: [u8; 4],
+        style: CellStyle,
+    ) {
+        let Some(mask) = self.mask(ch) else {
+            return;
+        };
+        let (w, h) = (self.glyph_width, self.glyph_height);
+        // Keep to the glyph cell as well as the clip rectangle.
+        let clip = (
+            clip.0.max(x.max(0) as usize),
+            clip.1.max(y.max(0) as usize),
+            clip.2.min((x + w as i32).max(0) as usize).min(buffer_width),
+            clip.3.min((y + h as i32).max(0) as usize),
+        );
+        let bold = if style.contains(CellStyle::BOLD) {
+            (w / 8).max(1)
+        } else {
+            0
+        };
+        let slant = if style.contains(CellStyle::ITALIC) {
+            (w / 4).max(1) as i32
+        } else {
+            0
+        };
+
+        for gy in 0..h {
+            // Rows above the middle lean right, rows below it lean left.
+            let shift = slant * (h - 1 - gy) as i32 / (h as i32 - 1).max(1) - slant / 2;
+            let row = &mask[gy * w..(gy + 1) * w];
+            for gx in 0..w {
+                let coverage = (0..=bold.min(gx)).map(|k| row[gx - k]).max().unwrap_or(0);
+                if coverage > 0 {
+                    blend(
+                        buffer,
+                        buffer_width,
+                        clip,
+                        (x + (gx as i32) + shift, y + gy as i32),
+                        color,
+                        coverage,
+                    );
+                }
+            }
+        }
+
+        if style.contains(CellStyle::UNDERLINE) {
+            let thickness = (h / 20).max(1);
+            for gy in h - 2 * thickness..h - thickness {
+                for gx in 0..w {
+                    let at = (x + gx as i32, y + gy as i32);
+                    blend(buffer, buffer_width, clip, at, color, 255);
+                }
+            }
+        }
+    }
+}
+
+/// Blend `color`, scaled by a coverage mask value, into one pixel inside `clip`.
+fn blend(
+    buffer: &mut [u8],
+    buffer_width: usize,
+    clip: PixelRect,
+    (x, y): (i32, i32),
+    color: [u8; 4],
+    coverage: u8,
+) {
+    if x < clip.0 as i32 || x >= clip.2 as i32 || y < clip.1 as i32 || y >= clip.3 as i32 {
+        return;
+    }
+    let pixel_idx = (y as usize * buffer_width + x as usize) * 4;
+    let Some(px) = buffer.get_mut(pixel_idx..pixel_idx + 4) else {
+        return;
+    };
+    let effective_alpha = (coverage as f32 / 255.0) * (color[3] as f32 / 255.0);
+    if effective_alpha >= 1.0 {
+        px[..3].copy_from_slice(&color[0..3]);
+    } else {
+        let inv_alpha = 1.0 - effective_alpha;
+        for c in 0..3 {
+            px[c] = (color[c] as f32 * effective_alpha + px[c] as f32 * inv_alpha) as u8;
+        }
+    }
+    px[3] = 255;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const WHITE: [u8; 4] = [255, 255, 255, 255];
+
+    /// Columns lit in each row of an 8×20 cell drawn at the buffer origin.
+    fn draw(style: CellStyle) -> Vec<Vec<usize>> {
+        let atlas = FontAtlas::new();
+        let mut buffer = vec![0u8; 8 * 20 * 4];
+        atlas.render_glyph_styled(&mut buffer, 8, (0, 0, 8, 20), (0, 0), '|', WHITE, style);
+        (0..20)
+            .map(|y| (0..8).filter(|x| buffer[(y * 8 + x) * 4] > 0).collect())
+            .collect()
+    }
+
+    #[test]
+    fn test_bold_and_italic_reshape_the_glyph() {
+        let plain = draw(CellStyle::NONE);
+        assert_eq!(plain[0], vec![4]);
+        assert_eq!(draw(CellStyle::BOLD)[0], vec![4, 5]);
+
+        let italic = draw(CellStyle::ITALIC);
+        assert_eq!(italic[0], vec![5]);
+        assert_eq!(italic[19], vec![3]);
+    }
+
+    #[test]
+    fn test_underline_stays_inside_the_cell() {
+        let underlined = draw(CellStyle::UNDERLINE);
+        assert_eq!(underlined[18], (0..8).collect::<Vec<_>>());
+        assert_eq!(underlined[19], vec![4]);
+
+        // Drawn at the right edge of a wider buffer, nothing spills past the cell.
+        let atlas = FontAtlas::new();
+        let mut buffer = vec![0u8; 16 * 20 * 4];
+        let style = CellStyle::ITALIC | CellStyle::BOLD | CellStyle::UNDERLINE;
+        atlas.render_glyph_styled(&mut buffer, 16, (0, 0, 16, 20), (0, 0), '|', WHITE, style);
+        assert!((0..20).all(|y| buffer[(y * 16 + 8) * 4] == 0));
+    }
+}
@@ -0,0 +1,329 @@
+//! Grid overlay for the pixel buffer - cell grid lines, major lines and rulers.
+//!
+//! Grid lines are drawn clipped to the dirty region, so a partial redraw repaints
+//! them inside the region it cleared and leaves the rest of the buffer untouched.
+//! Rulers are bands pinned to the top and left edges of the buffer rather than to the
+//! canvas, so they stay in view when a viewport buffer is panned; they are drawn
+//! over the finished frame.
+
+use crate::render::{DirtyRect, PixelLayout, PixelRect};
+
+/// Ruler tick length in pixels for ordinary and major cells.
+const TICK: usize = 3;
+const MAJOR_TICK: usize = 6;
+/// Major step used for ruler labels when major lines are off.
+const DEFAULT_RULER_STEP: usize = 10;
+/// Height of the top ruler band, and the least width of the left one, in pixels.
+const RULER_BAND: usize = 8;
+
+/// 3×5 digit bitmaps for ruler labels, row-major, most significant bit first.
+const DIGITS: [u16; 10] = [
+    0b111_101_101_101_111,
+    0b010_110_010_010_111,
+    0b111_001_111_100_111,
+    0b111_001_111_001_111,
+    0b101_101_111_001_001,
+    0b111_100_111_001_111,
+    0b111_100_111_101_111,
+    0b111_001_001_001_001,
+    0b111_101_111_101_111,
+    0b111_101_111_001_111,
+];
+
+/// Which grid decorations the pixel-buffer renderer draws.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct GridOverlay {
+    /// Faint line along the top and left edge of every cell
+    pub lines: bool,
+    /// Draw every Nth line at full strength (0 disables major lines)
+    pub major_every: usize,
+    /// Ticks and coordinate labels in bands along the top and left edges of the buffer
+    pub rulers: bool,
+}
+
+/// An RGBA buffer with a clip rectangle, in pixels.
+pub(super) struct Target<'a> {
+    pub(super) buffer: &'a mut [u8],
+    pub(super) width: usize,
+    pub(super) clip: PixelRect,
+}
+
+impl Target<'_> {
+    pub(super) fn blend(&mut self, x: i32, y: i32, color: [u8; 4]) {
+        let (x0, y0, x1, y1) = self.clip;
+        if x < x0 as i32 || x >= x1 as i32 || y < y0 as i32 || y >= y1 as i32 {
+            return;
+        }
+        let idx = (y as usize * self.width + x as usize) * 4;
+        let Some(px) = self.buffer.get_mut(idx..idx + 4) else {
+            return;
+        };
+        let a = u32::from(color[3]);
+        for c in 0..3 {
+            px[c] = ((u32::from(color[c]) * a + u32::from(px[c]) * (255 - a)) / 255) as u8;
+        }
+        px[3] = 255;
+    }
+
+    pub(super) fn hline(&mut self, x0: i32, x1: i32, y: i32, color: [u8; 4]) {
+        for x in x0.max(self.clip.0 as i32)..x1.min(self.clip.2 as i32) {
+            self.blend(x, y, color);
+        }
+    }
+
+    pub(super) fn vline(&mut self, x: i32, y0: i32, y1: i32, color: [u8; 4]) {
+        for y in y0.max(self.clip.1 as i32)..y1.min(self.clip.3 as i32) {
+            self.blend(x, y, color);
+        }
+    }
+
+    fn number(&mut self, x: i32, y: i32, n: usize, color: [u8; 4]) {
+        for (i, d) in n.to_string().bytes().enumerate() {
+            let bits = DIGITS[usize::from(d - b'0')];
+            for row in 0..5 {
+                for col in 0..3 {
+                    if bits & (1 << (14 - row * 3 - col)) != 0 {
+                        self.blend(x + (i * 4 + col) as i32, y + row as i32, color);
+                    }
+                }
+            }
+        }
+    }
+}
+
+impl GridOverlay {
+    /// Whether anything is drawn at all.
+    pub fn is_enabled(&self) -> bool {
+        self.lines || self.major_every > 0 || self.rulers
+    }
+
+    fn is_major(&self, i: usize) -> bool {
+        self.major_every > 0 && i % self.major_every == 0
+    }
+
+    /// Draw grid lines for the cells in `dirty` into an RGBA buffer laid out by
+    /// `layout`. Ordinary lines use `color` at half strength, major lines at full
+    /// strength.
+    pub fn draw_lines(
+        &self,
+        buffer: &mut [u8],
+        layout: &PixelLayout,
+        dirty: &DirtyRect,
+        color: [u8; 4],
+    ) {
+        if !self.lines && self.major_every == 0 {
+            return;
+        }
+        let mut target = clipped(buffer, layout, dirty);
+        let (x0, y0, x1, y1) = target.clip;
+        let (x0, y0, x1, y1) = (x0 as i32, y0 as i32, x1 as i32, y1 as i32);
+        let faint = [color[0], color[1], color[2], color[3] / 2];
+        for cx in dirty.x1.max(0) as usize..=dirty.x2.max(0) as usize {
+            let x = layout.cell_origin(cx as i32, 0).0;
+            if self.is_major(cx) {
+                target.vline(x, y0, y1, color);
+            } else if self.lines {
+                target.vline(x, y0, y1, faint);
+            }
+        }
+        for cy in dirty.y1.max(0) as usize..=dirty.y2.max(0) as usize {
+            let y = layout.cell_origin(0, cy as i32).1;
+            if self.is_major(cy) {
+                target.hline(x0, x1, y, color);
+            } else if self.lines {
+                target.hline(x0, x1, y, faint);
+            }
+        }
+    }
+
+    /// Pixels of the ruler bands along the top and left edges of the buffer, as
+    /// `[top, left]`; both empty when rulers are off. The left band is wide enough
+    /// for the largest row label of a canvas `rows` tall.
+    pub fn ruler_bands(&self, layout: &PixelLayout, rows: usize) -> [PixelRect; 2] {
+        if !self.rulers {
+            return [(0, 0, 0, 0); 2];
+        }
+        let digits = rows.saturating_sub(1).max(1).to_string().len();
+        let left = (digits * 4 + 1).max(RULER_BAND) as i32;
+        let (w, h) = (layout.width as i32, layout.height as i32);
+        [
+            layout.clip(0, 0, w, RULER_BAND as i32),
+            layout.clip(0, 0, left, h),
+        ]
+    }
+
+    /// Draw the ruler bands of a `cols`×`rows` canvas over the buffer: ticks at the
+    /// cells in view and coordinate labels on every major line (every tenth cell when
+    /// major lines are off), on a `background` fill.
+    pub fn draw_rulers(
+        &self,
+        buffer: &mut [u8],
+        layout: &PixelLayout,
+        (cols, rows): (usize, usize),
+        color: [u8; 4],
+        background: [u8; 4],
+    ) {
+        if !self.rulers {
+            return;
+        }
+        let [top, left] = self.ruler_bands(layout, rows);
+        layout.fill(buffer, top, background);
+        layout.fill(buffer, left, background);
+        let (tick, major_tick) = (TICK as i32, MAJOR_TICK as i32);
+        let step = if self.major_every > 0 {
+            self.major_every
+        } else {
+            DEFAULT_RULER_STEP
+        };
+        let faint = [color[0], color[1], color[2], color[3] / 2];
+        let (band_right, band_bottom) = (left.2 as i32, top.3 as i32);
+        let visible = layout.visible_cells(cols, rows);
+
+        // The corner where the bands meet stays empty
+        let mut target = Target {
+            buffer,
+            width: layout.width,
+            clip: (left.2, top.1, top.2, top.3),
+        };
+        target.hline(0, layout.width as i32, band_bottom - 1, faint);
+        if !visible.is_empty() {
+            for cx in visible.x1..=visible.x2 {
+                let x = layout.cell_origin(cx, 0).0;
+                let major = cx as usize % step == 0;
+                target.vline(x, 0, if major { major_tick } else { tick }, color);
+                if major && cx > 0 {
+                    target.number(x + 2, 1, cx as usize, color);
+                }
+            }
+        }
+
+        target.clip = (left.0, top.3, left.2, left.3);
+        target.vline(band_right - 1, 0, layout.height as i32, faint);
+        if !visible.is_empty() {
+            for cy in visible.y1..=visible.y2 {
+                let y = layout.cell_origin(0, cy).1;
+                let major = cy as usize % step == 0;
+                target.hline(0, if major { major_tick } else { tick }, y, color);
+                if major && cy > 0 {
+                    target.number(1, y + 2, cy as usize, color);
+                }
+            }
+        }
+    }
+}
+
+/// `buffer` clipped to the pixels of the cells in `dirty`.
+pub(super) fn clipped<'a>(
+    buffer: &'a mut [u8],
+    layout: &PixelLayout,
+    dirty: &DirtyRect,
+) -> Target<'a> {
+    Target {
+        buffer,
+        width: layout.width,
+        clip: layout.cell_pixels(dirty),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LAYOUT: PixelLayout = PixelLayout {
+        width: 12,
+        height: 12,
+        cell: (4, 4),
+        origin: (0, 0),
+    };
+    const WHITE: [u8; 4] = [255, 255, 255, 255];
+
+    fn lit(buffer: &[u8], width: usize, x: usize, y: usize) -> bool {
+        buffer[(y * width + x) * 4] > 0
+    }
+
+    #[test]
+    fn test_lines_and_major_lines() {
+        let overlay = GridOverlay {
+            lines: true,
+            major_every: 2,
+            rulers: false,
+        };
+        let mut buffer = vec![0u8; 12 * 12 * 4];
+        overlay.draw_lines(&mut buffer, &LAYOUT, &DirtyRect::full(3, 3), WHITE);
+        assert_eq!(buffer[(5 * 12 + 4) * 4], 127, "faint line at cell 1");
+        assert_eq!(buffer[(5 * 12 + 8) * 4], 255, "major line at cell 2");
+        assert!(!lit(&buffer, 12, 5, 5));
+    }
+
+    #[test]
+    fn test_drawing_is_clipped_to_the_dirty_cells() {
+        let overlay = GridOverlay {
+            lines: true,
+            major_every: 0,
+            rulers: true,
+        };
+        let mut buffer = vec![0u8; 12 * 12 * 4];
+        let dirty = DirtyRect::single(1, 1);
+        overlay.draw_lines(&mut buffer, &LAYOUT, &dirty, WHITE);
+        for y in 0..12 {
+            for x in 0..12 {
+                let inside = (4..8).contains(&x) && (4..8).contains(&y);
+                assert!(inside || !lit(&buffer, 12, x, y), "({x}, {y}) drawn");
+            }
+        }
+        assert!(lit(&buffer, 12, 4, 5));
+    }
+
+    #[test]
+    fn test_rulers_label_major_cells() {
+        let overlay = GridOverlay {
+            lines: false,
+            major_every: 0,
+            rulers: true,
+        };
+        let (w, h) = (12 * 8, 40);
+        let mut buffer = vec![0u8; w * h * 4];
+        overlay.draw_rulers(
+            &mut buffer,
+            &PixelLayout::canvas(12, 2, (8, 20)),
+            (12, 2),
+            WHITE,
+            [0, 0, 0, 255],
+        );
+        // Long tick at column 10, short one at column 2, "10" next to column 10.
+        assert!(lit(&buffer, w, 80, 5) && !lit(&buffer, w, 80, 6));
+        assert!(lit(&buffer, w, 16, 2) && !lit(&buffer, w, 16, 3));
+        assert!(lit(&buffer, w, 83, 1) && lit(&buffer, w, 86, 1));
+        // A short tick at row 1; the corner and cells past the bands are left alone.
+        assert!(lit(&buffer, w, 0, 20) && !lit(&buffer, w, 3, 20));
+        assert!(!lit(&buffer, w, 0, 0) && !lit(&buffer, w, 20, 20));
+    }
+
+    #[test]
+    fn test_rulers_stay_on_the_buffer_edges() {
+        let overlay = GridOverlay {
+            lines: false,
+            major_every: 5,
+            rulers: true,
+        };
+        // Canvas scrolled up and left by 40×4 cells
+        let layout = PixelLayout {
+            width: 64,
+            height: 40,
+            cell: (4, 4),
+            origin: (-160, -16),
+        };
+        let mut buffer = vec![0u8; 64 * 40 * 4];
+        overlay.draw_rulers(&mut buffer, &layout, (100, 100), WHITE, [0, 0, 0, 255]);
+        assert_eq!(
+            overlay.ruler_bands(&layout, 100),
+            [(0, 0, 64, 8), (0, 0, 9, 40)]
+        );
+        // Column 45 sits at x 20 with "45" next to it; row 5 at y 4 is hidden by the
+        // top band, row 10 at y 24 carries "10".
+        assert!(lit(&buffer, 64, 20, 5));
+        assert!(lit(&buffer, 64, 22, 1));
+        assert!(lit(&buffer, 64, 0, 24) && lit(&buffer, 64, 2, 26));
+        assert!(!lit(&buffer, 64, 30, 30));
+    }
+}
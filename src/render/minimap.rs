@@ -0,0 +1,167 @@
+//! Minimap - a downscaled overview of the whole canvas in a small RGBA buffer.
+//!
+//! Each minimap pixel averages the colors of the cells it covers (background for
+//! empty cells), so dense areas show up brighter. Canvases smaller than the minimap
+//! get a block of pixels per cell instead.
+
+/// Minimap size and the canvas it shows.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Minimap {
+    /// Canvas width in cells
+    pub cols: usize,
+    /// Canvas height in cells
+    pub rows: usize,
+    /// Minimap width in pixels
+    pub width: usize,
+    /// Minimap height in pixels
+    pub height: usize,
+}
+
+impl Minimap {
+    /// The largest minimap of at most `max_width`×`max_height` pixels that keeps
+    /// the canvas aspect ratio, using whole pixel blocks when the canvas fits.
+    pub fn fit(cols: usize, rows: usize, max_width: usize, max_height: usize) -> Self {
+        let (cols, rows) = (cols.max(1), rows.max(1));
+        let (max_width, max_height) = (max_width.max(1), max_height.max(1));
+        let (width, height) = if cols <= max_width && rows <= max_height {
+            let block = (max_width / cols).min(max_height / rows);
+            (cols * block, rows * block)
+        } else {
+            let shrink = (cols as f64 / max_width as f64).max(rows as f64 / max_height as f64);
+            (
+                ((cols as f64 / shrink) as usize).clamp(1, max_width),
+                ((rows as f64 / shrink) as usize).clamp(1, max_height),
+            )
+        };
+        Self {
+            cols,
+            rows,
+            width,
+            height,
+        }
+    }
+
+    /// Buffer length in bytes.
+    pub fn byte_len(&self) -> usize {
+        self.width * self.height * 4
+    }
+
+    /// Minimap position of a (fractional) cell position.
+    pub fn cell_to_pixel(&self, x: f64, y: f64) -> (f64, f64) {
+        (
+            x * self.width as f64 / self.cols as f64,
+            y * self.height as f64 / self.rows as f64,
+        )
+    }
+
+    /// Cell position under a minimap position.
+    pub fn pixel_to_cell(&self, x: f64, y: f64) -> (f64, f64) {
+        (
+            x * self.cols as f64 / self.width as f64,
+            y * self.rows as f64 / self.height as f64,
+        )
+    }
+
+    /// Render the overview. `color_at` gives the color of an occupied cell, `None`
+    /// for an empty one.
+    pub fn render(
+        &self,
+        buffer: &mut Vec<u8>,
+        bg: [u8; 4],
+        color_at: impl Fn(i32, i32) -> Option<[u8; 4]>,
+    ) {
+        buffer.resize(self.byte_len(), 0);
+        for py in 0..self.height {
+            let ys = span(py, self.rows, self.height);
+            for px in 0..self.width {
+                let xs = span(px, self.cols, self.width);
+                let mut sum = [0u32; 3];
+                let mut count = 0;
+                for y in ys.clone() {
+                    for x in xs.clone() {
+                        let color = color_at(x as i32, y as i32).unwrap_or(bg);
+                        for c in 0..3 {
+                            sum[c] += u32::from(color[c]);
+                        }
+                        count += 1;
+                    }
+                }
+                let i = (py * self.width + px) * 4;
+                for c in 0..3 {
+                    buffer[i + c] = (sum[c] / count) as u8;
+                }
+                buffer[i + 3] = 255;
+            }
+        }
+    }
+
+    /// Outline a cell rectangle `(x0, y0, x1, y1)` (exclusive ends, fractional),
+    /// clipped to the minimap. Returns the outlined pixels as `(x, y, width, height)`.
+    pub fn draw_rect(
+        &self,
+        buffer: &mut [u8],
+        (x0, y0, x1, y1): (f64, f64, f64, f64),
+        color: [u8; 4],
+    ) -> (usize, usize, usize, usize) {
+        let (px0, py0) = self.cell_to_pixel(x0, y0);
+        let (px1, py1) = self.cell_to_pixel(x1, y1);
+        let clamp_x = |v: f64| (v.round().max(0.0) as usize).min(self.width);
+        let clamp_y = |v: f64| (v.round().max(0.0) as usize).min(self.height);
+        let (left, top) = (clamp_x(px0), clamp_y(py0));
+        let (right, bottom) = (clamp_x(px1).max(left + 1), clamp_y(py1).max(top + 1));
+        let (right, bottom) = (right.min(self.width), bottom.min(self.height));
+        if left >= right || top >= bottom {
+            return (0, 0, 0, 0);
+        }
+        let mut put = |x: usize, y: usize| {
+            buffer[(y * self.width + x) * 4..][..4].copy_from_slice(&color);
+        };
+        for x in left..right {
+            put(x, top);
+            put(x, bottom - 1);
+        }
+        for y in top..bottom {
+            put(left, y);
+            put(right - 1, y);
+        }
+        (left, top, right - left, bottom - top)
+    }
+}
+
+/// Cells covered by minimap pixel `p` along an axis of `cells` cells and `pixels`
+/// pixels (at least one).
+fn span(p: usize, cells: usize, pixels: usize) -> std::ops::Range<usize> {
+    let start = p * cells / pixels;
+    let end = ((p + 1) * cells / pixels).max(start + 1);
+    start..end.min(cells)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BG: [u8; 4] = [0, 0, 0, 255];
+    const FG: [u8; 4] = [200, 200, 200, 255];
+
+    #[test]
+    fn test_fit_keeps_the_aspect_ratio() {
+        let small = Minimap::fit(20, 10, 100, 100);
+        assert_eq!((small.width, small.height), (100, 50));
+        let big = Minimap::fit(1000, 250, 200, 200);
+        assert_eq!((big.width, big.height), (200, 50));
+        assert_eq!(big.pixel_to_cell(100.0, 25.0), (500.0, 125.0));
+    }
+
+    #[test]
+    fn test_pixels_average_the_cells_they_cover() {
+        // 4×2 cells into 2×1 pixels: the left pixel covers one occupied cell of four.
+        let map = Minimap::fit(4, 2, 2, 1);
+        let mut buffer = Vec::new();
+        map.render(&mut buffer, BG, |x, y| (x == 0 && y == 0).then_some(FG));
+        assert_eq!(buffer, vec![50, 50, 50, 255, 0, 0, 0, 255]);
+
+        let viewport = map.draw_rect(&mut buffer, (2.0, 0.0, 4.0, 2.0), [255, 0, 0, 255]);
+        assert_eq!(viewport, (1, 0, 1, 1));
+        assert_eq!(buffer[4..8], [255, 0, 0, 255]);
+    }
+}
@@ -1,11 +1,30 @@
 //! Render module - canvas rendering and metrics.
 
+mod bitmap_font;
 mod canvas_renderer;
+mod cursor_overlay;
 mod dirty_rect;
 mod font_renderer;
+mod glyph_style;
+mod grid_overlay;
 mod metrics;
+mod minimap;
+mod palette;
+mod pixel_layout;
+mod pixel_renderer;
+mod preview;
+mod tile_cache;
 
+pub use bitmap_font::{BitmapFont, FontError, MAX_GLYPH_HEIGHT, MAX_GLYPH_WIDTH};
 pub use canvas_renderer::CanvasRenderer;
+pub use cursor_overlay::{CursorOverlay, OverlayColors, ANTS_STEP_MS, CARET_BLINK_MS};
 pub use dirty_rect::{DirtyRect, DirtyTracker};
 pub use font_renderer::FontAtlas;
+pub use grid_overlay::GridOverlay;
 pub use metrics::{FontMetrics, MeasureResult};
+pub use minimap::Minimap;
+pub use palette::{parse_hex_color, PixelPalette};
+pub use pixel_layout::{PixelLayout, PixelRect};
+pub use pixel_renderer::{render_cells, render_scene, PixelScene};
+pub use preview::{PreviewColors, PreviewMode};
+pub use tile_cache::TileCache;
@@ -0,0 +1,138 @@
+//! Pixel palette - the RGBA colors the pixel renderer draws with.
+//!
+//! Built from a theme's CSS variables (`bg`, `fg`, `preview-mode`, ...), so the
+//! pixel buffer, the minimap and the Canvas 2D renderer read one set of colors.
+
+use std::borrow::Cow;
+
+use crate::render::{OverlayColors, PreviewColors, PreviewMode};
+
+/// Colors used by the pixel renderer, as RGBA.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct PixelPalette {
+    /// Canvas background
+    pub background: [u8; 4],
+    /// Glyphs
+    pub foreground: [u8; 4],
+    /// Glyphs on guide layers
+    pub guide: [u8; 4],
+    /// Selection fill
+    pub selection: [u8; 4],
+    /// Background of cells styled `HIGHLIGHT`
+    pub highlight: [u8; 4],
+    /// Grid lines and rulers
+    pub grid: [u8; 4],
+    /// Tool preview mode and colors
+    pub preview: PreviewColors,
+    /// Caret, hover outline and marching ants
+    pub overlay: OverlayColors,
+}
+
+impl Default for PixelPalette {
+    /// The fallbacks used when a theme color cannot be parsed.
+    fn default() -> Self {
+        Self {
+            background: [30, 30, 30, 255],
+            foreground: [212, 212, 212, 255],
+            guide: [128, 128, 128, 255],
+            selection: [38, 79, 120, 255],
+            highlight: [77, 66, 21, 255],
+            grid: [51, 51, 51, 255],
+            preview: PreviewColors::default(),
+            overlay: OverlayColors {
+                caret: [255, 255, 255, 255],
+                hover: [13, 153, 255, 255],
+                ants: [[212, 212, 212, 255], [30, 30, 30, 255]],
+            },
+        }
+    }
+}
+
+impl PixelPalette {
+    /// Build a palette from theme variables looked up by name (`Theme::variable`),
+    /// falling back to the default colors for any that are missing or do not parse.
+    pub fn from_theme<'t>(variable: impl Fn(&str) -> Option<Cow<'t, str>>) -> Self {
+        let fallback = Self::default();
+        let color = |name: &str, default: [u8; 4]| {
+            variable(name)
+                .and_then(|hex| parse_hex_color(&hex))
+                .unwrap_or(default)
+        };
+        let background = color("bg", fallback.background);
+        let foreground = color("fg", fallback.foreground);
+
+        // Ghosted preview glyphs are the foreground, faded
+        let preview = PreviewColors::default();
+        let [r, g, b, a] = foreground;
+        let ghost_alpha = variable("preview-ghost-opacity")
+            .and_then(|v| v.parse::<f32>().ok())
+            .map_or(preview.ghost[3], |o| {
+                (f32::from(a) * o.clamp(0.0, 1.0)) as u8
+            });
+
+        Self {
+            background,
+            foreground,
+            guide: color("muted", fallback.guide),
+            selection: color("selection", fallback.selection),
+            highlight: color("highlight", fallback.highlight),
+            grid: color("grid", fallback.grid),
+            preview: PreviewColors {
+                mode: variable("preview-mode")
+                    .and_then(|mode| PreviewMode::from_name(&mode))
+                    .unwrap_or_default(),
+                tint: color("preview", preview.tint),
+                ghost: [r, g, b, ghost_alpha],
+                added: color("preview-added", preview.added),
+                removed: color("preview-removed", preview.removed),
+            },
+            overlay: OverlayColors {
+                caret: color("cursor", fallback.overlay.caret),
+                hover: color("accent", fallback.overlay.hover),
+                ants: [foreground, background],
+            },
+        }
+    }
+}
+
+/// Parse a `#rrggbb` or `#rrggbbaa` color into RGBA bytes.
+pub fn parse_hex_color(hex: &str) -> Option<[u8; 4]> {
+    let hex = hex.trim_start_matches('#');
+    if hex.len() == 6 {
+        let r = u8::from_str_radix(&hex[0..2], 16).ok()?;
+        let g = u8::from_str_radix(&hex[2..4], 16).ok()?;
+        let b = u8::from_str_radix(&hex[4..6], 16).ok()?;
+        Some([r, g, b, 255])
+    } else if hex.len() == 8 {
+        let r = u8::from_str_radix(&hex[0..2], 16).ok()?;
+        let g = u8::from_str_radix(&hex[2..4], 16).ok()?;
+        let b = u8::from_str_radix(&hex[4..6], 16).ok()?;
+        let a = u8::from_str_radix(&hex[6..8], 16).ok()?;
+        Some([r, g, b, a])
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_theme_reads_variables_and_falls_back() {
+        let palette = PixelPalette::from_theme(|name| match name {
+            "bg" => Some("#ffffff".into()),
+            "fg" => Some("#000000".into()),
+            "grid" => Some("not a color".into()),
+            "preview-mode" => Some("ghost".into()),
+            "preview-ghost-opacity" => Some("0.5".into()),
+            _ => None,
+        });
+        assert_eq!(palette.background, [255, 255, 255, 255]);
+        assert_eq!(palette.grid, PixelPalette::default().grid);
+        assert_eq!(palette.overlay.ants, [[0, 0, 0, 255], [255, 255, 255, 255]]);
+        assert_eq!(palette.preview.mode, PreviewMode::Ghost);
+        assert_eq!(palette.preview.ghost, [0, 0, 0, 127]);
+        assert_eq!(palette.preview.tint, PreviewColors::default().tint);
+    }
+}
@@ -0,0 +1,194 @@
+//! Pixel layout - where grid cells land in an RGBA pixel buffer.
+//!
+//! The buffer either covers the whole canvas (origin at 0, 0) or only the on-screen
+//! viewport, in which case the canvas origin can sit anywhere, including off the
+//! buffer's edges. Pixel rectangles are `(x0, y0, x1, y1)` with exclusive ends.
+
+use crate::render::DirtyRect;
+
+/// A rectangle of buffer pixels, `(x0, y0, x1, y1)` with exclusive ends.
+pub type PixelRect = (usize, usize, usize, usize);
+
+/// Buffer size, cell size and the canvas origin inside the buffer.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PixelLayout {
+    /// Buffer width in pixels
+    pub width: usize,
+    /// Buffer height in pixels
+    pub height: usize,
+    /// Cell (glyph) size in pixels
+    pub cell: (usize, usize),
+    /// Buffer position of the top-left corner of cell (0, 0)
+    pub origin: (i32, i32),
+}
+
+impl PixelLayout {
+    /// A buffer exactly covering a `cols`×`rows` canvas.
+    pub fn canvas(cols: usize, rows: usize, cell: (usize, usize)) -> Self {
+        Self {
+            width: cols * cell.0,
+            height: rows * cell.1,
+            cell,
+            origin: (0, 0),
+        }
+    }
+
+    /// Buffer length in bytes.
+    pub fn byte_len(&self) -> usize {
+        self.width * self.height * 4
+    }
+
+    /// Buffer position of the top-left corner of a cell.
+    pub fn cell_origin(&self, x: i32, y: i32) -> (i32, i32) {
+        (
+            self.origin.0 + x * self.cell.0 as i32,
+            self.origin.1 + y * self.cell.1 as i32,
+        )
+    }
+
+    /// Pixels covered by the cells in `rect`, clipped to the buffer.
+    pub fn cell_pixels(&self, rect: &DirtyRect) -> PixelRect {
+        if rect.is_empty() {
+            return (0, 0, 0, 0);
+        }
+        let (x0, y0) = self.cell_origin(rect.x1, rect.y1);
+        let (x1, y1) = self.cell_origin(rect.x2 + 1, rect.y2 + 1);
+        self.clip(x0, y0, x1, y1)
+    }
+
+    /// Cells overlapping the pixels `rect`, not limited to the canvas.
+    pub fn cells_in(&self, (x0, y0, x1, y1): PixelRect) -> DirtyRect {
+        if x0 >= x1 || y0 >= y1 {
+            return DirtyRect::empty();
+        }
+        let (cw, ch) = (self.cell.0 as i32, self.cell.1 as i32);
+        let (ox, oy) = self.origin;
+        DirtyRect::from_points(
+            (x0 as i32 - ox).div_euclid(cw),
+            (y0 as i32 - oy).div_euclid(ch),
+            (x1 as i32 - 1 - ox).div_euclid(cw),
+            (y1 as i32 - 1 - oy).div_euclid(ch),
+        )
+    }
+
+    /// Cells of a `cols`×`rows` canvas that appear in the buffer.
+    pub fn visible_cells(&self, cols: usize, rows: usize) -> DirtyRect {
+        let mut cells = self.cells_in((0, 0, self.width, self.height));
+        if cells.is_empty() || cols == 0 || rows == 0 {
+            return DirtyRect::empty();
+        }
+        cells.x1 = cells.x1.max(0);
+        cells.y1 = cells.y1.max(0);
+        cells.x2 = cells.x2.min(cols as i32 - 1);
+        cells.y2 = cells.y2.min(rows as i32 - 1);
+        cells
+    }
+
+    /// Clip signed pixel bounds to the buffer.
+    pub fn clip(&self, x0: i32, y0: i32, x1: i32, y1: i32) -> PixelRect {
+        let w = self.width as i32;
+        let h = self.height as i32;
+        (
+            x0.clamp(0, w) as usize,
+            y0.clamp(0, h) as usize,
+            x1.clamp(0, w) as usize,
+            y1.clamp(0, h) as usize,
+        )
+    }
+
+    /// Fill pixels with a solid color.
+    pub fn fill(&self, buffer: &mut [u8], (x0, y0, x1, y1): PixelRect, color: [u8; 4]) {
+        for y in y0..y1 {
+            let row = (y * self.width + x0) * 4..(y * self.width + x1) * 4;
+            if let Some(row) = buffer.get_mut(row) {
+                for px in row.chunks_exact_mut(4) {
+                    px.copy_from_slice(&color);
+                }
+            }
+        }
+    }
+
+    /// Move the buffer contents by (`dx`, `dy`) pixels. Returns the strips left
+    /// uncovered, which still hold stale pixels; none when nothing is kept.
+    pub fn scroll(&self, buffer: &mut [u8], dx: i32, dy: i32) -> Option<Vec<PixelRect>> {
+        let (w, h) = (self.width as i32, self.height as i32);
+        if dx.abs() >= w || dy.abs() >= h || buffer.len() < self.byte_len() {
+            return None;
+        }
+        let stride = self.width * 4;
+        let span = (w - dx.abs()) as usize * 4;
+        let (src_x, dst_x) = if dx >= 0 {
+            (0, dx as usize * 4)
+        } else {
+            ((-dx) as usize * 4, 0)
+        };
+        let rows: Box<dyn Iterator<Item = i32>> = if dy > 0 {
+            Box::new((0..h - dy).rev())
+        } else {
+            Box::new(-dy..h)
+        };
+        for src_y in rows {
+            let dst_y = (src_y + dy) as usize;
+            let src = src_y as usize * stride + src_x;
+            buffer.copy_within(src..src + span, dst_y * stride + dst_x);
+        }
+
+        let mut exposed = Vec::new();
+        let (width, height) = (self.width, self.height);
+        let (adx, ady) = (dx.unsigned_abs() as usize, dy.unsigned_abs() as usize);
+        if dy > 0 {
+            exposed.push((0, 0, width, ady));
+        } else if dy < 0 {
+            exposed.push((0, height - ady, width, height));
+        }
+        let (top, bottom) = if dy > 0 {
+            (ady, height)
+        } else {
+            (0, height - ady)
+        };
+        if dx > 0 {
+            exposed.push((0, top, adx, bottom));
+        } else if dx < 0 {
+            exposed.push((width - adx, top, width, bottom));
+        }
+        Some(exposed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn layout(origin: (i32, i32)) -> PixelLayout {
+        PixelLayout {
+            width: 10,
+            height: 8,
+            cell: (4, 4),
+            origin,
+        }
+    }
+
+    #[test]
+    fn test_cells_map_to_clipped_pixels() {
+        let view = layout((-2, 3));
+        assert_eq!(view.cell_origin(1, 1), (2, 7));
+        assert_eq!(view.cell_pixels(&DirtyRect::single(0, 0)), (0, 3, 2, 7));
+        assert_eq!(view.cell_pixels(&DirtyRect::single(5, 5)), (10, 8, 10, 8));
+        let cells = view.visible_cells(100, 100);
+        assert_eq!((cells.x1, cells.y1, cells.x2, cells.y2), (0, 0, 2, 1));
+        let outside = view.cells_in((0, 0, 1, 1));
+        assert_eq!((outside.x1, outside.y1), (0, -1));
+    }
+
+    #[test]
+    fn test_scroll_moves_pixels_and_reports_exposed_strips() {
+        let view = layout((0, 0));
+        let mut buffer: Vec<u8> = (0..80).flat_map(|i| [i as u8; 4]).collect();
+        let exposed = view.scroll(&mut buffer, 3, -2).unwrap();
+        // Pixel (0, 2) moved to (3, 0).
+        assert_eq!(buffer[3 * 4], 20);
+        assert_eq!(buffer[(5 * 10 + 9) * 4], 76);
+        assert_eq!(exposed, vec![(0, 6, 10, 8), (0, 0, 3, 6)]);
+        assert!(view.scroll(&mut buffer, 10, 0).is_none());
+    }
+}
@@ -0,0 +1,168 @@
+//! Pixel renderer - draws composited layers into an RGBA buffer.
+//!
+//! This is the drawing half of `renderToPixelBuffer`, free of editor state so it can
+//! also run natively (see `crate::headless`). Cells are always redrawn whole, from
+//! background to cursor overlay, so a partial redraw gives the same pixels as a full
+//! one. Rulers are pinned to the buffer edges and drawn over the finished frame.
+
+use crate::core::compositing::LayerView;
+use crate::core::grid::Grid;
+use crate::core::tools::DrawOp;
+use crate::core::{CellStyle, CompositeCache};
+use crate::render::preview::draw_preview;
+use crate::render::{CursorOverlay, DirtyRect, FontAtlas, GridOverlay, PixelLayout, PixelPalette};
+
+/// Everything drawn for a canvas, in canvas cells.
+#[derive(Clone, Copy, Debug)]
+pub struct PixelScene<'a> {
+    /// Shown layers, bottom to top
+    pub layers: &'a [LayerView<'a>],
+    /// `layers` flattened, synced with them
+    pub composite: &'a CompositeCache,
+    /// Canvas size in cells
+    pub size: (usize, usize),
+    /// Selected cells
+    pub selection: Option<DirtyRect>,
+    /// Tool preview ops and the offset that puts them on the canvas
+    pub preview: (&'a [DrawOp], (i32, i32)),
+    /// Layer grid the preview ops will be written to, which the diff preview
+    /// compares them with
+    pub preview_target: Option<&'a Grid>,
+    /// Grid lines and rulers
+    pub grid: GridOverlay,
+    /// Caret, marching ants and hover outline
+    pub cursor: CursorOverlay,
+}
+
+impl<'a> PixelScene<'a> {
+    /// A `width`×`height` canvas showing `layers` (flattened into `composite`) and
+    /// nothing else.
+    pub fn new(
+        layers: &'a [LayerView<'a>],
+        composite: &'a CompositeCache,
+        width: usize,
+        height: usize,
+    ) -> Self {
+        Self {
+            layers,
+            composite,
+            size: (width, height),
+            selection: None,
+            preview: (&[], (0, 0)),
+            preview_target: None,
+            grid: GridOverlay::default(),
+            cursor: CursorOverlay::default(),
+        }
+    }
+
+    /// Cells whose pixels change without the layers changing: selection, cursor
+    /// overlay and tool preview.
+    pub fn volatile_cells(&self) -> Vec<DirtyRect> {
+        let mut cells: Vec<DirtyRect> = [self.selection, self.cursor.selection, self.cursor.hover]
+            .into_iter()
+            .flatten()
+            .collect();
+        cells.extend(self.cursor.caret.map(|(x, y)| DirtyRect::single(x, y)));
+        let (preview, (ox, oy)) = self.preview;
+        if !preview.is_empty() {
+            let mut bounds = DirtyRect::empty();
+            for op in preview {
+                bounds.include(op.x + ox, op.y + oy);
+            }
+            cells.push(bounds);
+        }
+        cells
+    }
+}
+
+/// Render a whole scene into a new buffer laid out by `layout`.
+pub fn render_scene(
+    atlas: &FontAtlas,
+    layout: &PixelLayout,
+    scene: &PixelScene,
+    palette: &PixelPalette,
+) -> Vec<u8> {
+    let mut buffer = vec![0; layout.byte_len()];
+    let all = (0, 0, layout.width, layout.height);
+    layout.fill(&mut buffer, all, palette.background);
+    let visible = layout.visible_cells(scene.size.0, scene.size.1);
+    if !visible.is_empty() {
+        render_cells(atlas, &mut buffer, layout, &visible, scene, palette);
+    }
+    scene.grid.draw_rulers(
+        &mut buffer,
+        layout,
+        scene.size,
+        palette.grid,
+        palette.background,
+    );
+    buffer
+}
+
+/// Draw the cells in `dirty` (within the canvas) from scratch.
+pub fn render_cells(
+    atlas: &FontAtlas,
+    pixels: &mut [u8],
+    layout: &PixelLayout,
+    dirty: &DirtyRect,
+    scene: &PixelScene,
+    palette: &PixelPalette,
+) {
+    let (views, composite) = (scene.layers, scene.composite);
+    let clip = layout.cell_pixels(dirty);
+
+    // 1. Clear only the dirty pixel region to the background
+    layout.fill(pixels, clip, palette.background);
+
+    // 2. Highlighted cells get their own background, under the selection
+    for (gx, gy) in dirty.iter() {
+        let highlighted = composite
+            .stack(gx, gy)
+            .iter()
+            .any(|(cell, _)| cell.is_visible() && cell.style.contains(CellStyle::HIGHLIGHT));
+        if highlighted {
+            let cell_clip = layout.cell_pixels(&DirtyRect::single(gx, gy));
+            layout.fill(pixels, cell_clip, palette.highlight);
+        }
+    }
+
+    // 3. Selection highlight where it intersects the dirty rect
+    if let Some(selected) = scene.selection {
+        let highlighted = layout.cell_pixels(&selected.intersection(dirty));
+        layout.fill(pixels, highlighted, palette.selection);
+    }
+
+    // 4. Grid lines sit under the glyphs
+    scene.grid.draw_lines(pixels, layout, dirty, palette.grid);
+
+    // 5. Composite glyphs (with their bold/italic/underline styles) that fall inside
+    // the dirty rect, using sparse lookup
+    for (gx, gy) in dirty.iter() {
+        for &(cell, i) in composite.stack(gx, gy) {
+            if !cell.is_visible() {
+                continue;
+            }
+            let mut color = if views[i].guide {
+                palette.guide
+            } else {
+                palette.foreground
+            };
+            color[3] = (f32::from(color[3]) * views[i].opacity.clamp(0.0, 1.0)) as u8;
+            atlas.render_glyph_styled(
+                pixels,
+                layout.width,
+                clip,
+                layout.cell_origin(gx, gy),
+                cell.ch,
+                color,
+                cell.style,
+            );
+        }
+    }
+
+    // 6. Preview ops that fall inside the dirty rect, styled by the preview mode
+    draw_preview(atlas, pixels, layout, dirty, scene, &palette.preview);
+
+    // 7. Caret, marching ants and hover outline over the glyphs
+    scene.cursor.draw(pixels, layout, dirty, &palette.overlay);
+}
@@ -0,0 +1,258 @@
+//! Preview paint - how tool preview ops are drawn over the composited layers.
+//!
+//! Preview ops are drawn after the layer glyphs and before the cursor overlay, so
+//! every mode leaves the content under the preview visible. `Diff` compares each op
+//! with the cell it will replace on the layer being edited, showing what a drag
+//! destroys before it is committed.
+
+use crate::render::cursor_overlay::{cell_bounds, outline};
+use crate::render::grid_overlay::clipped;
+use crate::render::{DirtyRect, FontAtlas, PixelLayout, PixelScene};
+
+/// Opacity of the cell wash under diff glyphs.
+const DIFF_WASH_ALPHA: u8 = 0x55;
+/// Length of the dashes and gaps of the preview outline, in pixels.
+const DASH: i32 = 3;
+
+/// How tool preview ops are drawn.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum PreviewMode {
+    /// Preview glyphs in the tint color
+    #[default]
+    Tinted,
+    /// A dashed outline around the preview, no glyphs
+    DashedOutline,
+    /// Faint foreground glyphs
+    Ghost,
+    /// New cells in the added color, overwritten cells in the removed color
+    Diff,
+}
+
+impl PreviewMode {
+    /// Parse a preview mode name (`tinted`, `dashed-outline`, `ghost`, `diff`).
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_lowercase().as_str() {
+            "tinted" => Some(Self::Tinted),
+            "dashed-outline" => Some(Self::DashedOutline),
+            "ghost" => Some(Self::Ghost),
+            "diff" => Some(Self::Diff),
+            _ => None,
+        }
+    }
+
+    /// Lowercase name.
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Tinted => "tinted",
+            Self::DashedOutline => "dashed-outline",
+            Self::Ghost => "ghost",
+            Self::Diff => "diff",
+        }
+    }
+}
+
+/// Preview mode and colors, as RGBA.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct PreviewColors {
+    /// Drawing mode
+    pub mode: PreviewMode,
+    /// Tinted glyphs and the dashed outline
+    pub tint: [u8; 4],
+    /// Ghosted glyphs
+    pub ghost: [u8; 4],
+    /// Diff: cells that are empty on the edited layer
+    pub added: [u8; 4],
+    /// Diff: cells whose content the preview replaces
+    pub removed: [u8; 4],
+}
+
+impl Default for PreviewColors {
+    fn default() -> Self {
+        Self {
+            mode: PreviewMode::Tinted,
+            tint: [86, 156, 214, 179], // rgba(86, 156, 214, 0.7)
+            ghost: [212, 212, 212, 102],
+            added: [20, 174, 92, 255],
+            removed: [242, 72, 34, 255],
+        }
+    }
+}
+
+/// Draw the scene's preview ops that fall inside `dirty`.
+pub(super) fn draw_preview(
+    atlas: &FontAtlas,
+    pixels: &mut [u8],
+    layout: &PixelLayout,
+    dirty: &DirtyRect,
+    scene: &PixelScene,
+    colors: &PreviewColors,
+) {
+    let (preview, (ox, oy)) = scene.preview;
+    let clip = layout.cell_pixels(dirty);
+    if colors.mode == PreviewMode::DashedOutline {
+        let mut bounds = DirtyRect::empty();
+        for op in preview.iter().filter(|op| op.cell.is_visible()) {
+            bounds.include(op.x + ox, op.y + oy);
+        }
+        if !bounds.is_empty() && !bounds.intersection(dirty).is_empty() {
+            let mut target = clipped(pixels, layout, dirty);
+            let on = |x: i32, y: i32| (x + y).div_euclid(DASH) % 2 == 0;
+            let [r, g, b, a] = colors.tint;
+            let dashed = |x, y| [r, g, b, if on(x, y) { a } else { 0 }];
+            outline(&mut target, cell_bounds(layout, &bounds), dashed);
+        }
+        return;
+    }
+    for op in preview {
+        let (x, y) = (op.x + ox, op.y + oy);
+        if !dirty.contains(x, y) {
+            continue;
+        }
+        let color = match colors.mode {
+            PreviewMode::Tinted => colors.tint,
+            PreviewMode::Ghost => colors.ghost,
+            PreviewMode::Diff => {
+                let under = scene.preview_target.and_then(|grid| grid.get(op.x, op.y));
+                let color = match under.filter(|cell| cell.is_visible()) {
+                    Some(cell) if *cell == op.cell => colors.tint,
+                    Some(_) => colors.removed,
+                    None if op.cell.is_visible() => colors.added,
+                    None => continue,
+                };
+                if color != colors.tint {
+                    let (x0, y0, x1, y1) = cell_bounds(layout, &DirtyRect::single(x, y));
+                    let mut target = clipped(pixels, layout, dirty);
+                    let [r, g, b, _] = color;
+                    for py in y0..y1 {
+                        target.hline(x0, x1, py, [r, g, b, DIFF_WASH_ALPHA]);
+                    }
+                }
+                color
+            }
+            PreviewMode::DashedOutline => unreachable!("drawn above"),
+        };
+        if op.cell.is_visible() {
+            atlas.render_glyph_styled(
+                pixels,
+                layout.width,
+                clip,
+                layout.cell_origin(x, y),
+                op.cell.ch,
+                color,
+                op.cell.style,
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::compositing::LayerView;
+    use crate::core::tools::DrawOp;
+    use crate::core::{CompositeCache, Grid};
+    use crate::render::{render_scene, PixelPalette};
+
+    fn colors(mode: PreviewMode) -> PreviewColors {
+        PreviewColors {
+            mode,
+            ..PreviewColors::default()
+        }
+    }
+
+    /// Render a 3×1 canvas with `grid` under `ops`, returning the top-left pixel of
+    /// each cell.
+    fn corners(grid: &Grid, ops: &[DrawOp], preview: PreviewColors) -> Vec<[u8; 4]> {
+        let atlas = FontAtlas::new();
+        let layout = PixelLayout::canvas(3, 1, (atlas.glyph_width, atlas.glyph_height));
+        let layers = [LayerView::new(grid)];
+        let mut composite = CompositeCache::new();
+        composite.sync(&layers, 3, 1);
+        let mut scene = PixelScene::new(&layers, &composite, 3, 1);
+        scene.preview = (ops, (0, 0));
+        scene.preview_target = Some(grid);
+        let palette = PixelPalette {
+            preview,
+            ..PixelPalette::default()
+        };
+        let pixels = render_scene(&atlas, &layout, &scene, &palette);
+        (0..3)
+            .map(|x| {
+                let (px, py) = layout.cell_origin(x, 0);
+                let i = (py as usize * layout.width + px as usize) * 4;
+                pixels[i..i + 4].try_into().unwrap()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_diff_marks_overwritten_and_new_cells() {
+        let mut grid = Grid::new(3, 1);
+        grid.set_char(0, 0, '#');
+        grid.set_char(1, 0, '-');
+        let ops = [
+            DrawOp::new(0, 0, '+'),
+            DrawOp::new(1, 0, '-'),
+            DrawOp::new(2, 0, '+'),
+        ];
+        let background = PixelPalette::default().background;
+        let [removed, unchanged, added] = corners(&grid, &ops, colors(PreviewMode::Diff))[..]
+        else {
+            unreachable!()
+        };
+        assert!(removed[0] > background[0] && removed[1] < removed[0]);
+        assert_eq!(unchanged, background);
+        assert!(added[1] > background[1] && added[0] < added[1]);
+
+        // Other modes leave the cell backgrounds alone.
+        for mode in [PreviewMode::Tinted, PreviewMode::Ghost] {
+            assert_eq!(corners(&grid, &ops, colors(mode)), [background; 3]);
+        }
+    }
+
+    #[test]
+    fn test_dashed_outline_draws_only_the_border() {
+        let grid = Grid::new(3, 1);
+        let ops = [DrawOp::new(1, 0, '#')];
+        let atlas = FontAtlas::new();
+        let layout = PixelLayout::canvas(3, 1, (atlas.glyph_width, atlas.glyph_height));
+        let layers = [LayerView::new(&grid)];
+        let mut composite = CompositeCache::new();
+        composite.sync(&layers, 3, 1);
+        let mut scene = PixelScene::new(&layers, &composite, 3, 1);
+        scene.preview = (&ops, (0, 0));
+        let palette = PixelPalette {
+            preview: colors(PreviewMode::DashedOutline),
+            ..PixelPalette::default()
+        };
+        let pixels = render_scene(&atlas, &layout, &scene, &palette);
+        let at = |x: i32, y: i32| {
+            let i = (y as usize * layout.width + x as usize) * 4;
+            [pixels[i], pixels[i + 1], pixels[i + 2], pixels[i + 3]]
+        };
+        let (x0, y0) = layout.cell_origin(1, 0);
+        let (x1, y1) = layout.cell_origin(2, 1);
+        let background = palette.background;
+        assert_ne!(at(x0, y0), background, "dash at the corner");
+        assert!(
+            (x0..x1).any(|x| at(x, y0) == background),
+            "gaps in the dashes"
+        );
+        assert_eq!(at((x0 + x1) / 2, (y0 + y1) / 2), background, "no glyph");
+        assert_eq!(at(x0 - 1, y0), background);
+    }
+
+    #[test]
+    fn test_mode_names_round_trip() {
+        for mode in [
+            PreviewMode::Tinted,
+            PreviewMode::DashedOutline,
+            PreviewMode::Ghost,
+            PreviewMode::Diff,
+        ] {
+            assert_eq!(PreviewMode::from_name(mode.name()), Some(mode));
+        }
+        assert_eq!(PreviewMode::from_name("Ghost"), Some(PreviewMode::Ghost));
+        assert_eq!(PreviewMode::from_name("sparkle"), None);
+    }
+}
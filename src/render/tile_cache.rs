@@ -0,0 +1,216 @@
+//! Tile cache - rendered pixels of canvas tiles, reused while nothing in them changed.
+//!
+//! Tiles are the [`TILE_SIZE`]-square cell tiles of the composite cache. A cached
+//! tile is copied back instead of drawn when its composite signature and the frame
+//! settings it was drawn with (palette, cell size, grid overlay, canvas size) are
+//! unchanged. Tiles under the selection, cursor overlay or tool preview change from
+//! frame to frame, so they are always drawn and never cached. Tile pixels do not
+//! depend on where the canvas sits in the buffer, so cached tiles survive panning.
+
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+
+use crate::core::grid::TILE_SIZE;
+use crate::render::{
+    render_cells, DirtyRect, FontAtlas, PixelLayout, PixelPalette, PixelRect, PixelScene,
+};
+
+/// Largest total size of cached tile pixels, in bytes.
+const MAX_CACHE_BYTES: usize = 32 * 1024 * 1024;
+
+/// Pixels of one tile and the composite signature they show.
+#[derive(Clone, Debug)]
+struct CachedTile {
+    signature: u64,
+    pixels: Vec<u8>,
+}
+
+/// Rendered tile pixels, keyed by tile position.
+#[derive(Clone, Debug, Default)]
+pub struct TileCache {
+    /// Hash of the frame settings the cached tiles were drawn with
+    settings: u64,
+    tiles: HashMap<(usize, usize), CachedTile>,
+    bytes: usize,
+    /// Tiles copied from the cache instead of drawn, since creation
+    pub reused: usize,
+}
+
+impl TileCache {
+    /// Drop every cached tile (for changes the signatures do not cover, such as new
+    /// glyphs in the font atlas).
+    pub fn clear(&mut self) {
+        self.tiles.clear();
+        self.bytes = 0;
+    }
+
+    /// Number of cached tiles.
+    pub fn len(&self) -> usize {
+        self.tiles.len()
+    }
+
+    /// Whether no tile is cached.
+    pub fn is_empty(&self) -> bool {
+        self.tiles.is_empty()
+    }
+
+    /// Draw the cells in `dirty` (within the canvas) like [`render_cells`], copying
+    /// unchanged tiles from the cache and caching the tiles it draws.
+    pub fn render(
+        &mut self,
+        atlas: &FontAtlas,
+        pixels: &mut [u8],
+        layout: &PixelLayout,
+        dirty: &DirtyRect,
+        scene: &PixelScene,
+        palette: &PixelPalette,
+    ) {
+        let settings = frame_settings(layout, scene, palette);
+        if settings != self.settings {
+            self.clear();
+            self.settings = settings;
+        }
+        let volatile = scene.volatile_cells();
+        let canvas = DirtyRect::full(scene.size.0, scene.size.1);
+        let t = TILE_SIZE as i32;
+        for ty in dirty.y1.div_euclid(t)..=dirty.y2.div_euclid(t) {
+            for tx in dirty.x1.div_euclid(t)..=dirty.x2.div_euclid(t) {
+                let tile = DirtyRect::from_points(tx * t, ty * t, tx * t + t - 1, ty * t + t - 1)
+                    .intersection(&canvas);
+                let part = tile.intersection(dirty);
+                if part.is_empty() {
+                    continue;
+                }
+                let signature = scene.composite.tile_signature(tx as usize, ty as usize);
+                let cacheable = inside_buffer(layout, &tile)
+                    && !volatile.iter().any(|r| !r.intersection(&tile).is_empty());
+                let (Some(signature), true) = (signature, cacheable) else {
+                    render_cells(atlas, pixels, layout, &part, scene, palette);
+                    continue;
+                };
+                let key = (tx as usize, ty as usize);
+                let rect = layout.cell_pixels(&tile);
+                match self.tiles.get(&key) {
+                    Some(cached) if cached.signature == signature => {
+                        copy_in(pixels, layout, rect, layout.cell_pixels(&part), cached);
+                        self.reused += 1;
+                    }
+                    _ => {
+                        render_cells(atlas, pixels, layout, &tile, scene, palette);
+                        self.store(key, signature, copy_out(pixels, layout, rect), layout);
+                    }
+                }
+            }
+        }
+    }
+
+    /// Cache a tile, first dropping tiles outside the buffer when over budget.
+    fn store(
+        &mut self,
+        key: (usize, usize),
+        signature: u64,
+        pixels: Vec<u8>,
+        layout: &PixelLayout,
+    ) {
+        if let Some(old) = self.tiles.remove(&key) {
+            self.bytes -= old.pixels.len();
+        }
+        if self.bytes + pixels.len() > MAX_CACHE_BYTES {
+            let t = TILE_SIZE as i32;
+            let (tiles, bytes) = (&mut self.tiles, &mut self.bytes);
+            tiles.retain(|&(tx, ty), tile| {
+                let (tx, ty) = (tx as i32 * t, ty as i32 * t);
+                let cells = DirtyRect::from_points(tx, ty, tx + t - 1, ty + t - 1);
+                let (x0, y0, x1, y1) = layout.cell_pixels(&cells);
+                let keep = x0 < x1 && y0 < y1;
+                if !keep {
+                    *bytes -= tile.pixels.len();
+                }
+                keep
+            });
+        }
+        if self.bytes + pixels.len() <= MAX_CACHE_BYTES {
+            self.bytes += pixels.len();
+            self.tiles.insert(key, CachedTile { signature, pixels });
+        }
+    }
+}
+
+/// Hash of the settings that shape every tile's pixels.
+fn frame_settings(layout: &PixelLayout, scene: &PixelScene, palette: &PixelPalette) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    (layout.cell, scene.size, scene.grid, palette).hash(&mut hasher);
+    hasher.finish()
+}
+
+/// Whether every pixel of the cells in `tile` lies inside the buffer.
+fn inside_buffer(layout: &PixelLayout, tile: &DirtyRect) -> bool {
+    let (x0, y0) = layout.cell_origin(tile.x1, tile.y1);
+    let (x1, y1) = layout.cell_origin(tile.x2 + 1, tile.y2 + 1);
+    x0 >= 0 && y0 >= 0 && x1 <= layout.width as i32 && y1 <= layout.height as i32
+}
+
+/// Copy the pixels of `rect` out of the buffer.
+fn copy_out(pixels: &[u8], layout: &PixelLayout, (x0, y0, x1, y1): PixelRect) -> Vec<u8> {
+    let mut out = Vec::with_capacity((x1 - x0) * (y1 - y0) * 4);
+    for y in y0..y1 {
+        out.extend_from_slice(&pixels[(y * layout.width + x0) * 4..(y * layout.width + x1) * 4]);
+    }
+    out
+}
+
+/// Copy the `part` of a cached tile covering buffer pixels `rect` back in.
+fn copy_in(
+    pixels: &mut [u8],
+    layout: &PixelLayout,
+    (tx0, ty0, tx1, _): PixelRect,
+    (x0, y0, x1, y1): PixelRect,
+    cached: &CachedTile,
+) {
+    let stride = (tx1 - tx0) * 4;
+    for y in y0..y1 {
+        let src = (y - ty0) * stride + (x0 - tx0) * 4;
+        let dst = (y * layout.width + x0) * 4;
+        let len = (x1 - x0) * 4;
+        pixels[dst..dst + len].copy_from_slice(&cached.pixels[src..src + len]);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::compositing::LayerView;
+    use crate::core::{CompositeCache, Grid};
+    use crate::render::render_scene;
+
+    #[test]
+    fn test_unchanged_tiles_are_copied_and_match_a_fresh_render() {
+        let mut grid = Grid::new(40, 20);
+        grid.set_char(3, 3, '#');
+        let atlas = FontAtlas::new();
+        let layout = PixelLayout::canvas(40, 20, (atlas.glyph_width, atlas.glyph_height));
+        let all = DirtyRect::full(40, 20);
+        let palette = PixelPalette::default();
+        let (mut cache, mut composite) = (TileCache::default(), CompositeCache::new());
+        let mut pixels = vec![0; layout.byte_len()];
+
+        let mut frame = |grid: &Grid, cache: &mut TileCache, selection| {
+            let layers = [LayerView::new(grid)];
+            composite.sync(&layers, 40, 20);
+            let mut scene = PixelScene::new(&layers, &composite, 40, 20);
+            scene.selection = selection;
+            cache.render(&atlas, &mut pixels, &layout, &all, &scene, &palette);
+            assert!(pixels == render_scene(&atlas, &layout, &scene, &palette));
+        };
+        frame(&grid, &mut cache, None);
+        assert_eq!((cache.len(), cache.reused), (6, 0));
+        frame(&grid, &mut cache, None);
+        assert_eq!(cache.reused, 6);
+
+        // An edit redraws its tile; a selection keeps its tile out of the cache.
+        grid.set_char(35, 18, '@');
+        frame(&grid, &mut cache, Some(DirtyRect::single(1, 1)));
+        assert_eq!(cache.reused, 6 + 4);
+    }
+}
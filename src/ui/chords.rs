@@ -0,0 +1,126 @@
+//! Key chords - the `Ctrl+Shift+Z` notation shortcuts are written and saved in.
+
+use std::fmt;
+
+use super::shortcuts::Shortcut;
+
+impl Shortcut {
+    /// Parse a chord such as `Ctrl+Shift+Z`, `Alt+R` or `Ctrl++` into a shortcut.
+    /// Modifier names are case-insensitive; `Control`, `Option`, `Cmd` and
+    /// `Command` are accepted too.
+    pub fn parse(
+        chord: &str,
+        action: impl Into<String>,
+        description: impl Into<String>,
+    ) -> Result<Self, ShortcutError> {
+        let invalid = || ShortcutError::InvalidChord(chord.to_string());
+        let chord = chord.trim();
+        // The last `+` that is not the key itself separates modifiers from the key
+        let last = chord.char_indices().last().map_or(0, |(i, _)| i);
+        let split = chord[..last].rfind('+');
+        let (modifiers, key) = match split {
+            Some(i) => (&chord[..i], &chord[i + 1..]),
+            None => ("", chord),
+        };
+        if key.is_empty() || (key != "+" && key.contains('+')) {
+            return Err(invalid());
+        }
+        let mut shortcut = Self::key(key, action, description);
+        for modifier in modifiers.split('+').filter(|m| !m.is_empty()) {
+            match modifier.trim().to_lowercase().as_str() {
+                "ctrl" | "control" => shortcut.ctrl = true,
+                "shift" => shortcut.shift = true,
+                "alt" | "option" => shortcut.alt = true,
+                "meta" | "cmd" | "command" => shortcut.meta = true,
+                _ => return Err(invalid()),
+            }
+        }
+        Ok(shortcut)
+    }
+
+    /// The chord as written by users, e.g. `Ctrl+Shift+Z`.
+    pub fn chord(&self) -> String {
+        let modifiers = [
+            (self.ctrl, "Ctrl"),
+            (self.shift, "Shift"),
+            (self.alt, "Alt"),
+            (self.meta, "Meta"),
+        ];
+        let mut parts: Vec<&str> = modifiers
+            .iter()
+            .filter(|(on, _)| *on)
+            .map(|(_, name)| *name)
+            .collect();
+        parts.push(&self.key);
+        parts.join("+")
+    }
+}
+
+/// Canonical spelling of a key: uppercase, with the space bar as `SPACE`.
+pub(super) fn normalize_key(key: &str) -> String {
+    match key {
+        " " | "Spacebar" => "SPACE".to_string(),
+        "Esc" => "ESCAPE".to_string(),
+        _ => key.to_uppercase(),
+    }
+}
+
+/// Single-character keys other than letters and digits, typed with or without Shift.
+/// Digits keep Shift so `Shift+0` can be bound apart from `0`.
+pub(super) fn ignores_shift(key: &str) -> bool {
+    let mut chars = key.chars();
+    matches!((chars.next(), chars.next()), (Some(c), None) if !c.is_alphanumeric())
+}
+
+/// Why a binding could not be changed.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ShortcutError {
+    /// No such action
+    UnknownAction(String),
+    /// Malformed chord
+    InvalidChord(String),
+    /// The chord already belongs to another action
+    Conflict {
+        /// The chord, as written
+        chord: String,
+        /// Action the chord is bound to
+        action: String,
+    },
+    /// Saved bindings are not valid JSON
+    Json(String),
+}
+
+impl fmt::Display for ShortcutError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UnknownAction(action) => write!(f, "Unknown shortcut action \"{action}\""),
+            Self::InvalidChord(chord) => write!(f, "Invalid key chord \"{chord}\""),
+            Self::Conflict { chord, action } => {
+                write!(f, "{chord} is already bound to \"{action}\"")
+            }
+            Self::Json(msg) => write!(f, "Invalid shortcut bindings: {msg}"),
+        }
+    }
+}
+
+impl std::error::Error for ShortcutError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_chords() {
+        let parse = |chord| Shortcut::parse(chord, "zoom_in", "").map(|s| s.chord());
+        assert_eq!(parse("ctrl+shift+z"), Ok("Ctrl+Shift+Z".to_string()));
+        assert_eq!(parse("Cmd+Option+K"), Ok("Alt+Meta+K".to_string()));
+        assert_eq!(parse("Ctrl++"), Ok("Ctrl++".to_string()));
+        assert_eq!(parse("+"), Ok("+".to_string()));
+        assert_eq!(parse("Alt+é"), Ok("Alt+É".to_string()));
+        assert!(matches!(
+            parse("Hyper+K"),
+            Err(ShortcutError::InvalidChord(_))
+        ));
+        assert!(parse("Ctrl+").is_err());
+    }
+}
@@ -1,9 +1,11 @@
 //! UI module - user interface components.
 
+mod chords;
 mod shortcuts;
 mod theme;
 mod toolbar;
 
-pub use shortcuts::ShortcutManager;
-pub use theme::Theme;
+pub use chords::ShortcutError;
+pub use shortcuts::{Shortcut, ShortcutManager};
+pub use theme::{PreviewStyle, Theme};
 pub use toolbar::{ToolbarConfig, ToolbarItem};
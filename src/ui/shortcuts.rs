@@ -1,8 +1,15 @@
 //! Keyboard shortcuts management.
+//!
+//! Every key binding the editor and the page react to lives here, so users can
+//! rebind them. Bindings are written as chords such as `Ctrl+Shift+Z`; user changes
+//! are saved as JSON holding only the actions that differ from the defaults.
 
 use crate::core::tools::ToolId;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
+
+pub use super::chords::ShortcutError;
+use super::chords::{ignores_shift, normalize_key};
 
 /// A keyboard shortcut definition.
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -15,6 +22,9 @@ pub struct Shortcut {
     pub shift: bool,
     /// Whether Alt is required
     pub alt: bool,
+    /// Whether Meta (Cmd, Windows key) is required
+    #[serde(default)]
+    pub meta: bool,
     /// Action identifier
     pub action: String,
     /// Human-readable description
@@ -29,10 +39,11 @@ impl Shortcut {
         description: impl Into<String>,
     ) -> Self {
         Self {
-            key: key.into().to_uppercase(),
+            key: normalize_key(&key.into()),
             ctrl: false,
             shift: false,
             alt: false,
+            meta: false,
             action: action.into(),
             description: description.into(),
         }
@@ -45,12 +56,8 @@ impl Shortcut {
         description: impl Into<String>,
     ) -> Self {
         Self {
-            key: key.into().to_uppercase(),
             ctrl: true,
-            shift: false,
-            alt: false,
-            action: action.into(),
-            description: description.into(),
+            ..Self::key(key, action, description)
         }
     }
 
@@ -61,21 +68,25 @@ impl Shortcut {
         description: impl Into<String>,
     ) -> Self {
         Self {
-            key: key.into().to_uppercase(),
-            ctrl: true,
             shift: true,
-            alt: false,
-            action: action.into(),
-            description: description.into(),
+            ..Self::ctrl(key, action, description)
         }
     }
 
-    /// Check if a key event matches this shortcut.
-    pub fn matches(&self, key: &str, ctrl: bool, shift: bool, alt: bool) -> bool {
-        self.key.to_uppercase() == key.to_uppercase()
+    /// Check if a key event matches this shortcut. Shift is ignored for symbol
+    /// keys, whose character already depends on it (`?`, `)`, `+`).
+    pub fn matches(&self, key: &str, ctrl: bool, shift: bool, alt: bool, meta: bool) -> bool {
+        let key = normalize_key(key);
+        normalize_key(&self.key) == key
             && self.ctrl == ctrl
-            && self.shift == shift
+            && (self.shift == shift || ignores_shift(&key))
             && self.alt == alt
+            && self.meta == meta
+    }
+
+    /// Whether a key event could match both shortcuts.
+    pub fn same_chord(&self, other: &Shortcut) -> bool {
+        other.matches(&self.key, self.ctrl, self.shift, self.alt, self.meta)
     }
 }
 
@@ -117,17 +128,31 @@ impl ShortcutManager {
         self.register(Shortcut::key("F", "tool_freehand", "Freehand tool"));
         self.register(Shortcut::key("V", "tool_select", "Select tool"));
         self.register(Shortcut::key("E", "tool_eraser", "Eraser tool"));
+        self.register(Shortcut::key("B", "cycle_border", "Next border style"));
 
         // Edit shortcuts
         self.register(Shortcut::ctrl("Z", "undo", "Undo"));
         self.register(Shortcut::ctrl_shift("Z", "redo", "Redo"));
         self.register(Shortcut::ctrl("Y", "redo", "Redo (alternative)"));
         self.register(Shortcut::ctrl("C", "copy", "Copy ASCII"));
+        self.register(Shortcut::ctrl("X", "cut", "Cut"));
+        self.register(Shortcut::ctrl("V", "paste", "Paste"));
         self.register(Shortcut::ctrl("A", "select_all", "Select all"));
+        self.register(Shortcut::ctrl("B", "bold", "Bold"));
+        self.register(Shortcut::ctrl("I", "italic", "Italic"));
+        self.register(Shortcut::ctrl("U", "underline", "Underline"));
 
         // Navigation
         self.register(Shortcut::key("Space", "pan_mode", "Pan mode (hold)"));
         self.register(Shortcut::key("Escape", "cancel", "Cancel/Deselect"));
+        self.register(Shortcut::key("0", "zoom_reset", "Reset zoom"));
+        self.register(Shortcut::key(")", "zoom_fit", "Fit to view"));
+        // `)` is not Shift+0 on every layout
+        self.register(Shortcut {
+            shift: true,
+            ..Shortcut::key("0", "zoom_fit", "Fit to view")
+        });
+        self.register(Shortcut::key("?", "help", "Keyboard shortcuts"));
     }
 
     /// Register a new shortcut.
@@ -137,11 +162,28 @@ impl ShortcutManager {
         self.shortcuts.push(shortcut);
     }
 
-    /// Find shortcut that matches the given key event.
-    pub fn find_match(&self, key: &str, ctrl: bool, shift: bool, alt: bool) -> Option<&Shortcut> {
-        self.shortcuts
-            .iter()
-            .find(|s| s.matches(key, ctrl, shift, alt))
+    /// Find shortcut that matches the given key event. Meta (Cmd) stands in for
+    /// Ctrl when no shortcut uses it, so Ctrl bindings work on macOS.
+    pub fn find_match(
+        &self,
+        key: &str,
+        ctrl: bool,
+        shift: bool,
+        alt: bool,
+        meta: bool,
+    ) -> Option<&Shortcut> {
+        let find = |ctrl, meta| {
+            self.shortcuts
+                .iter()
+                .find(|s| s.matches(key, ctrl, shift, alt, meta))
+        };
+        find(ctrl, meta).or_else(|| {
+            if meta && !ctrl {
+                find(true, false)
+            } else {
+                None
+            }
+        })
     }
 
     /// Get shortcut by action name.
@@ -156,20 +198,81 @@ impl ShortcutManager {
 
     /// Get shortcut display string.
     pub fn display_string(&self, action: &str) -> Option<String> {
-        self.get_by_action(action).map(|s| {
-            let mut parts = Vec::new();
-            if s.ctrl {
-                parts.push("Ctrl");
-            }
-            if s.shift {
-                parts.push("Shift");
-            }
-            if s.alt {
-                parts.push("Alt");
+        self.get_by_action(action).map(Shortcut::chord)
+    }
+
+    /// Chords bound to `action`, in registration order.
+    pub fn chords(&self, action: &str) -> Vec<String> {
+        self.shortcuts
+            .iter()
+            .filter(|s| s.action == action)
+            .map(Shortcut::chord)
+            .collect()
+    }
+
+    /// Action other than `action` that `chord` is bound to.
+    pub fn conflict(&self, action: &str, chord: &str) -> Result<Option<&str>, ShortcutError> {
+        let wanted = Shortcut::parse(chord, action, "")?;
+        Ok(self
+            .shortcuts
+            .iter()
+            .find(|s| s.action != action && s.same_chord(&wanted))
+            .map(|s| s.action.as_str()))
+    }
+
+    /// Replace the chords of `action`; an empty list unbinds it. Nothing changes
+    /// when a chord is invalid or bound to another action.
+    pub fn rebind(&mut self, action: &str, chords: &[String]) -> Result<(), ShortcutError> {
+        let defaults = Self::new();
+        let description = defaults
+            .get_by_action(action)
+            .map(|s| s.description.clone())
+            .ok_or_else(|| ShortcutError::UnknownAction(action.to_string()))?;
+        let mut bound = Vec::new();
+        for chord in chords {
+            if let Some(other) = self.conflict(action, chord)? {
+                return Err(ShortcutError::Conflict {
+                    chord: chord.clone(),
+                    action: other.to_string(),
+                });
             }
-            parts.push(&s.key);
-            parts.join("+")
-        })
+            bound.push(Shortcut::parse(chord, action, description.as_str())?);
+        }
+        let mut shortcuts = std::mem::take(&mut self.shortcuts);
+        shortcuts.retain(|s| s.action != action);
+        shortcuts.extend(bound);
+        self.by_action.clear();
+        for shortcut in shortcuts {
+            self.register(shortcut);
+        }
+        Ok(())
+    }
+
+    /// Bindings that differ from the defaults, as a JSON object of action to chords.
+    pub fn to_json(&self) -> String {
+        let defaults = Self::new();
+        let changed: BTreeMap<&str, Vec<String>> = defaults
+            .by_action
+            .keys()
+            .filter(|action| self.chords(action) != defaults.chords(action))
+            .map(|action| (action.as_str(), self.chords(action)))
+            .collect();
+        serde_json::to_string(&changed).unwrap_or_else(|_| "{}".to_string())
+    }
+
+    /// The defaults with bindings saved by [`to_json`](Self::to_json) applied.
+    pub fn from_json(json: &str) -> Result<Self, ShortcutError> {
+        let changed: BTreeMap<String, Vec<String>> =
+            serde_json::from_str(json).map_err(|e| ShortcutError::Json(e.to_string()))?;
+        let mut manager = Self::new();
+        // Clear the changed actions first, so bindings may move between them
+        for action in changed.keys() {
+            manager.rebind(action, &[])?;
+        }
+        for (action, chords) in &changed {
+            manager.rebind(action, chords)?;
+        }
+        Ok(manager)
     }
 
     /// Get action from tool ID.
@@ -185,6 +288,21 @@ impl ShortcutManager {
             ToolId::Eraser => "tool_eraser",
         }
     }
+
+    /// Tool an action switches to, if it is a tool action.
+    pub fn tool_from_action(action: &str) -> Option<ToolId> {
+        match action {
+            "tool_rectangle" => Some(ToolId::Rectangle),
+            "tool_line" => Some(ToolId::Line),
+            "tool_arrow" => Some(ToolId::Arrow),
+            "tool_diamond" => Some(ToolId::Diamond),
+            "tool_text" => Some(ToolId::Text),
+            "tool_freehand" => Some(ToolId::Freehand),
+            "tool_select" => Some(ToolId::Select),
+            "tool_eraser" => Some(ToolId::Eraser),
+            _ => None,
+        }
+    }
 }
 
 #[cfg(test)]
@@ -195,23 +313,41 @@ mod tests {
     fn test_shortcut_matches() {
         let shortcut = Shortcut::ctrl("Z", "undo", "Undo");
 
-        assert!(shortcut.matches("z", true, false, false));
-        assert!(shortcut.matches("Z", true, false, false));
-        assert!(!shortcut.matches("z", false, false, false));
-        assert!(!shortcut.matches("z", true, true, false));
+        assert!(shortcut.matches("z", true, false, false, false));
+        assert!(shortcut.matches("Z", true, false, false, false));
+        assert!(!shortcut.matches("z", false, false, false, false));
+        assert!(!shortcut.matches("z", true, true, false, false));
+        assert!(!shortcut.matches("z", true, false, true, false));
     }
 
     #[test]
     fn test_shortcut_manager() {
         let manager = ShortcutManager::new();
 
-        let matched = manager.find_match("R", false, false, false);
+        let matched = manager.find_match("R", false, false, false, false);
         assert!(matched.is_some());
         assert_eq!(matched.unwrap().action, "tool_rectangle");
 
-        let matched = manager.find_match("z", true, false, false);
+        let matched = manager.find_match("z", true, false, false, false);
         assert!(matched.is_some());
         assert_eq!(matched.unwrap().action, "undo");
+
+        // Cmd+Z falls back to Ctrl+Z; the space bar and `?` (typed with Shift) match.
+        let action = |key, shift, meta| {
+            manager
+                .find_match(key, false, shift, false, meta)
+                .map(|s| s.action.as_str())
+        };
+        assert_eq!(action("z", true, true), Some("redo"));
+        assert_eq!(action(" ", false, false), Some("pan_mode"));
+        assert_eq!(action("?", true, false), Some("help"));
+        assert_eq!(action("R", true, false), None);
+
+        // Digits keep Shift: `0` resets the zoom, Shift+0 (or `)`) fits the view.
+        assert_eq!(action("0", false, false), Some("zoom_reset"));
+        assert_eq!(action("0", true, false), Some("zoom_fit"));
+        assert_eq!(action(")", true, false), Some("zoom_fit"));
+        assert_eq!(manager.chords("zoom_fit"), [")", "Shift+0"]);
     }
 
     #[test]
@@ -226,4 +362,61 @@ mod tests {
         let display = manager.display_string("redo");
         assert_eq!(display, Some("Ctrl+Y".to_string()));
     }
+
+    #[test]
+    fn test_rebind_detects_conflicts() {
+        let mut manager = ShortcutManager::new();
+        assert_eq!(manager.conflict("undo", "ctrl+y"), Ok(Some("redo")));
+        assert_eq!(
+            manager.rebind("undo", &["Ctrl+Y".to_string()]),
+            Err(ShortcutError::Conflict {
+                chord: "Ctrl+Y".to_string(),
+                action: "redo".to_string(),
+            })
+        );
+        assert_eq!(manager.chords("undo"), ["Ctrl+Z"]);
+        assert!(matches!(
+            manager.rebind("fly", &[]),
+            Err(ShortcutError::UnknownAction(_))
+        ));
+
+        manager
+            .rebind("tool_rectangle", &["Alt+R".to_string(), "Q".to_string()])
+            .unwrap();
+        let matched = manager.find_match("r", false, false, true, false).unwrap();
+        assert_eq!(matched.action, "tool_rectangle");
+        assert!(manager
+            .find_match("r", false, false, false, false)
+            .is_none());
+        // A chord's own action may keep it.
+        assert_eq!(manager.conflict("tool_rectangle", "Q"), Ok(None));
+    }
+
+    #[test]
+    fn test_json_keeps_only_changed_bindings() {
+        let mut manager = ShortcutManager::new();
+        assert_eq!(manager.to_json(), "{}");
+        manager
+            .rebind("redo", &["Meta+Shift+Z".to_string()])
+            .unwrap();
+        manager.rebind("help", &[]).unwrap();
+        let json = manager.to_json();
+        assert_eq!(json, r#"{"help":[],"redo":["Shift+Meta+Z"]}"#);
+
+        let loaded = ShortcutManager::from_json(&json).unwrap();
+        assert_eq!(loaded.chords("redo"), ["Shift+Meta+Z"]);
+        assert!(loaded.chords("help").is_empty());
+        assert_eq!(loaded.chords("undo"), ["Ctrl+Z"]);
+
+        // Swapping two bindings loads; a clash with an unchanged action does not.
+        let swapped = r#"{"tool_line": ["R"], "tool_rectangle": ["L"]}"#;
+        let loaded = ShortcutManager::from_json(swapped).unwrap();
+        let matched = loaded.find_match("r", false, false, false, false).unwrap();
+        assert_eq!(matched.action, "tool_line");
+        assert!(ShortcutManager::from_json(r#"{"undo": ["Ctrl+C"]}"#).is_err());
+        assert!(matches!(
+            ShortcutManager::from_json("[1]"),
+            Err(ShortcutError::Json(_))
+        ));
+    }
 }
@@ -1,6 +1,7 @@
 //! Theme configuration for the UI.
 
 use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
 
 /// Color scheme for the editor.
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -33,14 +34,48 @@ pub struct Theme {
     pub grid: String,
     /// Selection highlight color
     pub selection: String,
+    /// Background of cells styled `HIGHLIGHT`
+    #[serde(default = "default_highlight")]
+    pub highlight: String,
     /// Cursor color
     pub cursor: String,
+    /// How tool previews are drawn
+    #[serde(default)]
+    pub preview: PreviewStyle,
     /// Font family
     pub font_family: String,
     /// Font size (CSS string)
     pub font_size: String,
 }
 
+/// How tool previews (shapes still being dragged) are drawn.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PreviewStyle {
+    /// `tinted`, `dashed-outline`, `ghost` or `diff`
+    pub mode: String,
+    /// Preview glyphs when tinted, the outline when dashed
+    pub tint: String,
+    /// Opacity of ghosted preview glyphs, 0.0 to 1.0
+    pub ghost_opacity: f32,
+    /// Diff color for cells the preview fills
+    pub added: String,
+    /// Diff color for cells whose content the preview overwrites
+    pub removed: String,
+}
+
+impl Default for PreviewStyle {
+    fn default() -> Self {
+        Self {
+            mode: "tinted".to_string(),
+            tint: "#569cd6b3".to_string(),
+            ghost_opacity: 0.4,
+            added: "#14ae5c".to_string(),
+            removed: "#f24822".to_string(),
+        }
+    }
+}
+
 impl Default for Theme {
     fn default() -> Self {
         Self::figma_dark()
@@ -65,7 +100,9 @@ impl Theme {
             warning: "#ffcd29".to_string(),
             grid: "#333333".to_string(),
             selection: "#264f78".to_string(),
+            highlight: "#4d4215".to_string(),
             cursor: "#ffffff".to_string(),
+            preview: PreviewStyle::default(),
             font_family: "'JetBrains Mono', 'Fira Code', 'Consolas', monospace".to_string(),
             font_size: "14px".to_string(),
         }
@@ -88,7 +125,12 @@ impl Theme {
             warning: "#ffcd29".to_string(),
             grid: "#e0e0e0".to_string(),
             selection: "#b4d7ff".to_string(),
+            highlight: "#fff3a8".to_string(),
             cursor: "#000000".to_string(),
+            preview: PreviewStyle {
+                tint: "#1a73e8b3".to_string(),
+                ..PreviewStyle::default()
+            },
             font_family: "'JetBrains Mono', 'Fira Code', 'Consolas', monospace".to_string(),
             font_size: "14px".to_string(),
         }
@@ -111,50 +153,90 @@ impl Theme {
             warning: "#ffff00".to_string(),
             grid: "#333333".to_string(),
             selection: "#00ffff44".to_string(),
+            highlight: "#666600".to_string(),
             cursor: "#ffffff".to_string(),
+            preview: PreviewStyle {
+                mode: "tinted".to_string(),
+                tint: "#00ffffcc".to_string(),
+                ghost_opacity: 0.6,
+                added: "#00ff00".to_string(),
+                removed: "#ff0000".to_string(),
+            },
             font_family: "'JetBrains Mono', 'Fira Code', 'Consolas', monospace".to_string(),
             font_size: "14px".to_string(),
         }
     }
 
+    /// CSS custom property names (without `--`), in the order `to_css_variables`
+    /// writes them.
+    pub const VARIABLES: &'static [&'static str] = &[
+        "bg",
+        "fg",
+        "accent",
+        "bg-secondary",
+        "border",
+        "hover",
+        "active",
+        "muted",
+        "success",
+        "error",
+        "warning",
+        "grid",
+        "selection",
+        "highlight",
+        "cursor",
+        "preview",
+        "preview-added",
+        "preview-removed",
+        "preview-mode",
+        "preview-ghost-opacity",
+        "font-family",
+        "font-size",
+    ];
+
+    /// The value of one CSS custom property, e.g. `variable("bg")`. The pixel
+    /// renderer builds its palette from these, so it reads the same colors as CSS.
+    pub fn variable(&self, name: &str) -> Option<Cow<'_, str>> {
+        let value = match name {
+            "bg" => &self.background,
+            "fg" => &self.foreground,
+            "accent" => &self.accent,
+            "bg-secondary" => &self.secondary_background,
+            "border" => &self.border,
+            "hover" => &self.hover,
+            "active" => &self.active,
+            "muted" => &self.muted,
+            "success" => &self.success,
+            "error" => &self.error,
+            "warning" => &self.warning,
+            "grid" => &self.grid,
+            "selection" => &self.selection,
+            "highlight" => &self.highlight,
+            "cursor" => &self.cursor,
+            "preview" => &self.preview.tint,
+            "preview-added" => &self.preview.added,
+            "preview-removed" => &self.preview.removed,
+            "preview-mode" => &self.preview.mode,
+            "preview-ghost-opacity" => {
+                return Some(Cow::Owned(self.preview.ghost_opacity.to_string()))
+            }
+            "font-family" => &self.font_family,
+            "font-size" => &self.font_size,
+            _ => return None,
+        };
+        Some(Cow::Borrowed(value))
+    }
+
     /// Convert theme to CSS variables.
     pub fn to_css_variables(&self) -> String {
-        format!(
-            r#":root {{
-    --bg: {background};
-    --fg: {foreground};
-    --accent: {accent};
-    --bg-secondary: {secondary_background};
-    --border: {border};
-    --hover: {hover};
-    --active: {active};
-    --muted: {muted};
-    --success: {success};
-    --error: {error};
-    --warning: {warning};
-    --grid: {grid};
-    --selection: {selection};
-    --cursor: {cursor};
-    --font-family: {font_family};
-    --font-size: {font_size};
-}}"#,
-            background = self.background,
-            foreground = self.foreground,
-            accent = self.accent,
-            secondary_background = self.secondary_background,
-            border = self.border,
-            hover = self.hover,
-            active = self.active,
-            muted = self.muted,
-            success = self.success,
-            error = self.error,
-            warning = self.warning,
-            grid = self.grid,
-            selection = self.selection,
-            cursor = self.cursor,
-            font_family = self.font_family,
-            font_size = self.font_size,
-        )
+        let mut css = String::from(":root {\n");
+        for name in Self::VARIABLES {
+            if let Some(value) = self.variable(name) {
+                css.push_str(&format!("    --{name}: {value};\n"));
+            }
+        }
+        css.push('}');
+        css
     }
 
     /// Get all available themes.
@@ -170,6 +252,10 @@ impl Theme {
     }
 }
 
+fn default_highlight() -> String {
+    Theme::figma_dark().highlight
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -189,6 +275,12 @@ mod tests {
         assert!(css.contains("--bg:"));
         assert!(css.contains("--fg:"));
         assert!(css.contains("--accent:"));
+        assert!(css.ends_with("--font-size: 14px;\n}"));
+        for name in Theme::VARIABLES {
+            assert!(theme.variable(name).is_some(), "{name}");
+        }
+        assert_eq!(theme.variable("preview-ghost-opacity").unwrap(), "0.4");
+        assert_eq!(theme.variable("nonexistent"), None);
     }
 
     #[test]
@@ -200,4 +292,20 @@ mod tests {
         let theme = Theme::find("nonexistent");
         assert!(theme.is_none());
     }
+
+    #[test]
+    fn test_preview_style_defaults_when_missing() {
+        let mut value = serde_json::to_value(Theme::light()).unwrap();
+        value.as_object_mut().unwrap().remove("preview");
+        let theme: Theme = serde_json::from_value(value).unwrap();
+        assert_eq!(theme.preview, PreviewStyle::default());
+
+        let partial = r#"{"mode": "diff"}"#;
+        let style: PreviewStyle = serde_json::from_str(partial).unwrap();
+        assert_eq!(style.mode, "diff");
+        assert_eq!(style.tint, PreviewStyle::default().tint);
+        assert!(Theme::light()
+            .to_css_variables()
+            .contains("--preview: #1a73e8b3;"));
+    }
 }
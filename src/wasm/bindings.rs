@@ -1,13 +1,18 @@
 //! WASM bindings - struct definition, constructor, and core methods.
 
+use crate::core::document::LayerGroup;
 use crate::core::history::{History, DEFAULT_MAX_DEPTH};
 use crate::core::selection::{Selection, SelectionClipboard};
 use crate::core::tools::{DrawOp, EraserTool, RectangleTool, Tool, ToolId};
-use crate::core::EditorState;
-use crate::render::{CanvasRenderer, DirtyTracker, FontAtlas, FontMetrics};
+use crate::core::{BlendMode, CellStyle, CompositeCache, DocumentMetadata, EditorState};
+use crate::render::{
+    BitmapFont, CanvasRenderer, CursorOverlay, DirtyTracker, FontAtlas, FontMetrics, GridOverlay,
+    Minimap, PixelRect, TileCache,
+};
 use crate::wasm::tool_manager::{
     parse_tool_id, set_border_style, set_line_direction, set_tool_by_id,
 };
+use std::cell::RefCell;
 use wasm_bindgen::prelude::*;
 
 /// WebAssembly-bindable ASCII editor instance for frontend integration.
@@ -37,12 +42,57 @@ pub struct AsciiEditor {
     pub(crate) full_render_count: u32,
     pub(crate) dirty_render_count: u32,
     pub(crate) pixel_buffer: Vec<u8>,
+    /// Glyphs at the render size: the glyph size times device scale and zoom.
     pub(crate) font_atlas: FontAtlas,
+    /// Recently used atlases at other render sizes, so zooming back is cheap.
+    pub(crate) atlas_cache: Vec<FontAtlas>,
+    /// Glyph cell size in CSS pixels at zoom 1.
+    pub(crate) glyph_size: (usize, usize),
+    /// Device pixels per CSS pixel (`window.devicePixelRatio`).
+    pub(crate) device_scale: f64,
+    /// Bitmap font loaded from JS, kept so the glyph size can be changed later.
+    pub(crate) font: Option<BitmapFont>,
     /// Named layers (background layers + active content mirrored in `state.grid`).
     pub(crate) layers: Vec<LayerData>,
     pub(crate) active_layer: usize,
+    /// Groups of the active page's layers.
+    pub(crate) groups: Vec<LayerGroup>,
+    /// Pages in display order. The active page's entry is a placeholder whose layers
+    /// live in `layers` until another page is activated.
+    pub(crate) pages: Vec<PageData>,
+    pub(crate) active_page: usize,
+    /// Active layer offset when a layer drag started.
+    pub(crate) layer_drag_start: Option<(i32, i32)>,
     pub(crate) eraser_size: i32,
+    /// Style given to characters typed with the text tool.
+    pub(crate) text_style: CellStyle,
     pub(crate) theme: crate::ui::Theme,
+    /// Key bindings, rebindable by the user.
+    pub(crate) shortcuts: crate::ui::ShortcutManager,
+    /// On-screen canvas size in device pixels when the pixel buffer covers only the
+    /// viewport; `None` when it covers the whole canvas.
+    pub(crate) viewport: Option<(usize, usize)>,
+    /// Canvas origin of the pixels currently in the buffer, for scrolling on pan.
+    pub(crate) buffer_origin: (i32, i32),
+    /// Pixel rectangles changed by the last pixel-buffer render.
+    pub(crate) rendered_regions: Vec<PixelRect>,
+    /// Grid lines and rulers drawn into the pixel buffer.
+    pub(crate) grid_overlay: GridOverlay,
+    /// Caret, marching ants and hover outline as last drawn.
+    pub(crate) cursor_overlay: CursorOverlay,
+    /// Latest frame time from JS, in milliseconds.
+    pub(crate) frame_time: f64,
+    /// Whether the pointer is over the canvas (for the hover outline).
+    pub(crate) pointer_over_canvas: bool,
+    /// Size of the last rendered minimap and its RGBA pixels.
+    pub(crate) minimap: Minimap,
+    pub(crate) minimap_buffer: Vec<u8>,
+    /// Shown layers flattened per tile, synced on use (exports only borrow `self`).
+    pub(crate) composite_cache: RefCell<CompositeCache>,
+    /// Rendered pixels of unchanged tiles.
+    pub(crate) tile_cache: TileCache,
+    /// Title, author and timestamps saved with the document.
+    pub(crate) metadata: DocumentMetadata,
 }
 
 /// Serializable layer metadata + content snapshot.
@@ -51,8 +101,38 @@ pub(crate) struct LayerData {
     pub name: String,
     pub visible: bool,
     pub locked: bool,
+    /// Reference layer: shown dimmed, skipped by exports and copy.
+    pub guide: bool,
+    /// Id of the group the layer belongs to.
+    pub group: Option<u32>,
     pub grid: crate::core::Grid,
     pub history: History,
+    /// Position of the layer's grid origin on the canvas, in cells.
+    pub offset: (i32, i32),
+    /// On-screen opacity, 0.0 to 1.0.
+    pub opacity: f32,
+    pub blend: BlendMode,
+    /// See-through character (space by default).
+    pub transparent: char,
+}
+
+impl LayerData {
+    /// A visible, unlocked layer at the canvas origin with fresh history.
+    pub fn new(name: impl Into<String>, grid: crate::core::Grid) -> Self {
+        Self {
+            name: name.into(),
+            visible: true,
+            locked: false,
+            guide: false,
+            group: None,
+            grid,
+            history: History::new(DEFAULT_MAX_DEPTH),
+            offset: (0, 0),
+            opacity: 1.0,
+            blend: BlendMode::Normal,
+            transparent: ' ',
+        }
+    }
 }
 
 impl Clone for LayerData {
@@ -62,12 +142,29 @@ impl Clone for LayerData {
             name: self.name.clone(),
             visible: self.visible,
             locked: self.locked,
+            guide: self.guide,
+            group: self.group,
             grid: self.grid.clone(),
             history: History::new(DEFAULT_MAX_DEPTH),
+            offset: self.offset,
+            opacity: self.opacity,
+            blend: self.blend,
+            transparent: self.transparent,
         }
     }
 }
 
+/// A page (artboard): its own canvas size and layer stack.
+#[derive(Debug)]
+pub(crate) struct PageData {
+    pub name: String,
+    pub width: usize,
+    pub height: usize,
+    pub layers: Vec<LayerData>,
+    pub active_layer: usize,
+    pub groups: Vec<LayerGroup>,
+}
+
 #[wasm_bindgen]
 impl AsciiEditor {
     /// Creates a new `AsciiEditor` instance with the given dimensions.
@@ -75,6 +172,7 @@ impl AsciiEditor {
     pub fn new(width: usize, height: usize) -> Self {
         let state = EditorState::new(width, height);
         let renderer = CanvasRenderer::new();
+        let font_atlas = FontAtlas::new();
 
         Self {
             state,
@@ -95,18 +193,50 @@ impl AsciiEditor {
             last_cursor: None,
             full_render_count: 0,
             dirty_render_count: 0,
-            pixel_buffer: vec![0u8; width * 8 * height * 20 * 4],
-            font_atlas: FontAtlas::new(),
-            layers: vec![LayerData {
-                name: "Layer 1".to_string(),
-                visible: true,
-                locked: false,
-                grid: crate::core::Grid::new(width, height),
-                history: History::new(DEFAULT_MAX_DEPTH),
-            }],
+            pixel_buffer: vec![
+                0u8;
+                width * font_atlas.glyph_width * height * font_atlas.glyph_height * 4
+            ],
+            glyph_size: (font_atlas.glyph_width, font_atlas.glyph_height),
+            font_atlas,
+            atlas_cache: Vec::new(),
+            device_scale: 1.0,
+            font: None,
+            layers: vec![LayerData::new(
+                "Layer 1",
+                crate::core::Grid::new(width, height),
+            )],
             active_layer: 0,
+            groups: Vec::new(),
+            pages: vec![PageData {
+                name: "Page 1".to_string(),
+                width,
+                height,
+                layers: Vec::new(),
+                active_layer: 0,
+                groups: Vec::new(),
+            }],
+            active_page: 0,
+            layer_drag_start: None,
             eraser_size: 1,
+            text_style: CellStyle::NONE,
             theme: crate::ui::Theme::figma_dark(),
+            shortcuts: crate::ui::ShortcutManager::new(),
+            viewport: None,
+            buffer_origin: (0, 0),
+            rendered_regions: Vec::new(),
+            grid_overlay: GridOverlay::default(),
+            cursor_overlay: CursorOverlay::default(),
+            frame_time: 0.0,
+            pointer_over_canvas: false,
+            minimap: Minimap::default(),
+            minimap_buffer: Vec::new(),
+            composite_cache: RefCell::new(CompositeCache::new()),
+            tile_cache: TileCache::default(),
+            metadata: DocumentMetadata {
+                created: Some(crate::wasm::document_api::now_ms()),
+                ..Default::default()
+            },
         }
     }
 
@@ -117,8 +247,8 @@ impl AsciiEditor {
         for layer in &mut self.layers {
             layer.grid.resize(new_width, new_height);
         }
-        self.pixel_buffer = vec![0u8; new_width * 8 * new_height * 20 * 4];
-        self.dirty_tracker.request_full_redraw();
+        self.update_render_scale();
+        self.reset_pixel_buffer();
     }
 
     /// Gets the current width of the canvas grid.
@@ -208,6 +338,7 @@ impl AsciiEditor {
     #[wasm_bindgen(js_name = setZoom)]
     pub fn set_zoom(&mut self, zoom: f64) {
         self.renderer.set_zoom(zoom);
+        self.update_render_scale();
         self.dirty_tracker.request_full_redraw();
     }
 
@@ -220,8 +351,7 @@ impl AsciiEditor {
     /// Sets the pan offset (X and Y coordinates) of the editor viewport.
     #[wasm_bindgen(js_name = setPan)]
     pub fn set_pan(&mut self, x: f64, y: f64) {
-        self.renderer.set_pan(x, y);
-        self.dirty_tracker.request_full_redraw();
+        self.pan_to(x, y);
     }
 
     /// Gets the current pan offset of the editor viewport as a `[x, y]` list.
@@ -247,7 +377,7 @@ impl AsciiEditor {
         if self.is_active_layer_locked() {
             return false;
         }
-        let result = self.history.undo(&mut self.state.grid);
+        let result = self.step_history(History::undo_layer);
         if result {
             self.dirty_tracker.request_full_redraw();
         }
@@ -260,7 +390,7 @@ impl AsciiEditor {
         if self.is_active_layer_locked() {
             return false;
         }
-        let result = self.history.redo(&mut self.state.grid);
+        let result = self.step_history(History::redo_layer);
         if result {
             self.dirty_tracker.request_full_redraw();
         }
@@ -314,7 +444,9 @@ impl AsciiEditor {
     #[wasm_bindgen(js_name = setTheme)]
     pub fn set_theme(&mut self, theme_name: String) -> bool {
         if let Some(theme) = crate::ui::Theme::find(&theme_name) {
-            self.theme = theme;
+            // The preview mode is the user's choice and outlives theme switches
+            let previous = std::mem::replace(&mut self.theme, theme);
+            self.theme.preview.mode = previous.preview.mode;
             self.dirty_tracker.request_full_redraw();
             true
         } else {
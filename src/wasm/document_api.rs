@@ -0,0 +1,422 @@
+//! Document API - `.asc` save/load and document metadata.
+
+use wasm_bindgen::prelude::*;
+
+use super::bindings::{AsciiEditor, LayerData, PageData};
+use crate::core::document::{
+    CellEncoding, Document, DocumentError, DocumentLayer, DocumentPage, LoadWarning,
+    ToolPreferences, Viewport,
+};
+use crate::core::Grid;
+use crate::wasm::tool_manager::set_tool_by_id;
+
+/// Current time in milliseconds since the Unix epoch.
+pub(crate) fn now_ms() -> u64 {
+    #[cfg(target_arch = "wasm32")]
+    {
+        js_sys::Date::now() as u64
+    }
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_millis() as u64)
+            .unwrap_or(0)
+    }
+}
+
+#[wasm_bindgen]
+impl AsciiEditor {
+    /// Serialize diagram to JSON (`.asc` format, current schema version).
+    ///
+    /// `encoding` selects how cells are stored: `json` (default, one object per cell),
+    /// `rle` (run-length text rows) or `rle+deflate` (compressed, for autosave).
+    /// `loadDocument` accepts all of them.
+    #[wasm_bindgen(js_name = serializeDocument)]
+    pub fn serialize_document(&self, encoding: Option<String>) -> String {
+        let encoding = encoding
+            .as_deref()
+            .and_then(CellEncoding::from_name)
+            .unwrap_or_default();
+        self.serialize_document_impl(encoding)
+    }
+
+    /// Serialize diagram to the git-friendly plain-text format (layers as literal rows).
+    #[wasm_bindgen(js_name = serializeDocumentText)]
+    pub fn serialize_document_text(&self) -> String {
+        self.snapshot_document().to_text()
+    }
+
+    /// Load diagram from `.asc` JSON (any supported version) or the plain-text format.
+    ///
+    /// Returns an array of non-fatal warnings (`{ code, message }`). Throws an `Error`
+    /// with `code` and `message` when the document cannot be loaded; the current
+    /// document is left untouched in that case.
+    #[wasm_bindgen(js_name = loadDocument)]
+    pub fn load_document(&mut self, json: String) -> Result<JsValue, JsValue> {
+        match self.try_load_document(&json) {
+            Ok(warnings) => Ok(warnings_to_js(&warnings)),
+            Err(e) => Err(document_error_to_js(&e)),
+        }
+    }
+
+    /// Document title.
+    #[wasm_bindgen(getter = documentTitle)]
+    pub fn document_title(&self) -> String {
+        self.metadata.title.clone()
+    }
+
+    /// Sets the document title.
+    #[wasm_bindgen(setter = documentTitle)]
+    pub fn set_document_title(&mut self, title: String) {
+        self.metadata.title = title;
+    }
+
+    /// Document author.
+    #[wasm_bindgen(getter = documentAuthor)]
+    pub fn document_author(&self) -> String {
+        self.metadata.author.clone()
+    }
+
+    /// Sets the document author.
+    #[wasm_bindgen(setter = documentAuthor)]
+    pub fn set_document_author(&mut self, author: String) {
+        self.metadata.author = author;
+    }
+
+    /// Creation time (ms since the Unix epoch), if known.
+    #[wasm_bindgen(getter = documentCreated)]
+    pub fn document_created(&self) -> Option<f64> {
+        self.metadata.created.map(|t| t as f64)
+    }
+
+    /// Time the document was last saved (ms since the Unix epoch), if ever.
+    #[wasm_bindgen(getter = documentModified)]
+    pub fn document_modified(&self) -> Option<f64> {
+        self.metadata.modified.map(|t| t as f64)
+    }
+}
+
+impl AsciiEditor {
+    pub(crate) fn serialize_document_impl(&self, encoding: CellEncoding) -> String {
+        self.snapshot_document().to_json_with(encoding)
+    }
+
+    /// Current editor state as a [`Document`], stamped with the save time.
+    fn snapshot_document(&self) -> Document {
+        let mut doc = Document::new(self.state.grid.width(), self.state.grid.height());
+        doc.metadata = self.metadata.clone();
+        doc.metadata.modified = Some(now_ms());
+        doc.preferences = ToolPreferences {
+            tool: self.tool_id,
+            border_style: self.state.border_style,
+            eraser_size: self.eraser_size,
+        };
+        let (pan_x, pan_y) = self.renderer.pan();
+        doc.viewport = Viewport {
+            zoom: self.renderer.zoom(),
+            pan_x,
+            pan_y,
+        };
+
+        doc.active_page = self.active_page;
+        doc.pages = self
+            .pages
+            .iter()
+            .enumerate()
+            .map(|(i, page)| {
+                if i == self.active_page {
+                    self.snapshot_active_page(&page.name)
+                } else {
+                    let mut out = DocumentPage::new(&page.name, page.width, page.height);
+                    out.active_layer = page.active_layer;
+                    out.groups = page.groups.clone();
+                    out.layers = page
+                        .layers
+                        .iter()
+                        .map(|l| document_layer(l, &l.grid))
+                        .collect();
+                    out
+                }
+            })
+            .collect();
+        doc
+    }
+
+    fn snapshot_active_page(&self, name: &str) -> DocumentPage {
+        let mut page = DocumentPage::new(name, self.state.grid.width(), self.state.grid.height());
+        page.active_layer = self.active_layer;
+        page.groups = self.groups.clone();
+        // Snapshot active layer content from the live grid without requiring &mut.
+        for (i, layer) in self.layers.iter().enumerate() {
+            let src = if i == self.active_layer {
+                &self.state.grid
+            } else {
+                &layer.grid
+            };
+            page.layers.push(document_layer(layer, src));
+        }
+        page
+    }
+
+    /// Load a `.asc` document (native entry point behind `loadDocument`).
+    /// Returns the non-fatal warnings, or the reason the document was rejected.
+    pub fn try_load_document(&mut self, json: &str) -> Result<Vec<LoadWarning>, DocumentError> {
+        let doc = Document::parse(json)?;
+
+        let mut warnings = Vec::new();
+        let mut pages = Vec::with_capacity(doc.pages.len());
+        for page in &doc.pages {
+            let (w, h) = (page.canvas.width, page.canvas.height);
+            let mut layers = Vec::with_capacity(page.layers.len());
+            for layer in &page.layers {
+                let (grid, layer_warnings) = layer.to_grid(w, h);
+                warnings.extend(layer_warnings);
+                layers.push(LayerData {
+                    visible: layer.visible,
+                    locked: layer.locked,
+                    guide: layer.guide,
+                    group: layer
+                        .group
+                        .filter(|id| page.groups.iter().any(|g| g.id == *id)),
+                    offset: (layer.x, layer.y),
+                    opacity: layer.opacity.clamp(0.0, 1.0),
+                    blend: layer.blend,
+                    transparent: layer.transparent,
+                    ..LayerData::new(layer.name.clone(), grid)
+                });
+            }
+            let (active_layer, clamped) = page.clamped_active_layer();
+            warnings.extend(clamped);
+            pages.push(PageData {
+                name: page.name.clone(),
+                width: w,
+                height: h,
+                layers,
+                active_layer,
+                groups: page.groups.clone(),
+            });
+        }
+
+        let (active, clamped) = doc.clamped_active_page();
+        warnings.extend(clamped);
+        self.pages = pages;
+        self.restore_page(active);
+        self.history.clear();
+        self.clipboard.clear();
+
+        self.metadata = doc.metadata;
+        self.apply_preferences(doc.preferences);
+        self.renderer.set_zoom(doc.viewport.zoom);
+        self.update_render_scale();
+        self.renderer
+            .set_pan(doc.viewport.pan_x, doc.viewport.pan_y);
+        Ok(warnings)
+    }
+
+    fn apply_preferences(&mut self, prefs: ToolPreferences) {
+        self.state.border_style = prefs.border_style;
+        self.eraser_size = prefs.eraser_size;
+        self.tool_id = prefs.tool;
+        set_tool_by_id(
+            &mut self.active_tool,
+            &mut self.tool_id,
+            &mut self.preview_ops,
+            &mut self.state,
+            &mut self.current_selection,
+            self.eraser_size,
+        );
+    }
+}
+
+/// Snapshot of `layer` with `grid` as its content.
+fn document_layer(layer: &LayerData, grid: &Grid) -> DocumentLayer {
+    DocumentLayer {
+        guide: layer.guide,
+        group: layer.group,
+        x: layer.offset.0,
+        y: layer.offset.1,
+        opacity: layer.opacity,
+        blend: layer.blend,
+        transparent: layer.transparent,
+        ..DocumentLayer::from_grid(&layer.name, layer.visible, layer.locked, grid)
+    }
+}
+
+/// `JsError` carrying the error's `code` alongside its message.
+fn document_error_to_js(e: &DocumentError) -> JsValue {
+    let err = JsValue::from(JsError::new(&e.to_string()));
+    let _ = js_sys::Reflect::set(
+        &err,
+        &JsValue::from_str("code"),
+        &JsValue::from_str(e.code()),
+    );
+    err
+}
+
+fn warnings_to_js(warnings: &[LoadWarning]) -> JsValue {
+    #[derive(serde::Serialize)]
+    struct JsWarning {
+        code: &'static str,
+        message: String,
+    }
+    let list: Vec<JsWarning> = warnings
+        .iter()
+        .map(|w| JsWarning {
+            code: w.code(),
+            message: w.to_string(),
+        })
+        .collect();
+    serde_wasm_bindgen::to_value(&list).unwrap_or(JsValue::NULL)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::core::cell::{Cell, CellStyle};
+    use crate::core::tools::{BorderStyle, ToolId};
+    use crate::wasm::bindings::AsciiEditor;
+
+    fn make_canvas_with_box() -> AsciiEditor {
+        let mut canvas = AsciiEditor::new(10, 10);
+        for (x, ch) in "┌───┐".chars().enumerate() {
+            canvas.state.grid.set_char(x as i32, 0, ch);
+        }
+        canvas.state.grid.set_char(0, 1, '│');
+        canvas.state.grid.set_char(4, 1, '│');
+        for (x, ch) in "└───┘".chars().enumerate() {
+            canvas.state.grid.set_char(x as i32, 2, ch);
+        }
+        canvas
+    }
+
+    #[test]
+    fn test_serialize_load_round_trip() {
+        let canvas = make_canvas_with_box();
+        let json = canvas.serialize_document(None);
+        let mut other = AsciiEditor::new(10, 10);
+        assert!(other.try_load_document(&json).unwrap().is_empty());
+        assert_eq!(other.export_for_copy(), canvas.export_for_copy());
+    }
+
+    #[test]
+    fn test_load_document_rejects_oversized_canvas() {
+        let mut canvas = AsciiEditor::new(10, 10);
+        let json = r#"{
+            "format":"ascii-canvas",
+            "version":1,
+            "canvas":{"width":50000,"height":50000},
+            "active_layer":0,
+            "layers":[{"name":"Layer 1","visible":true,"cells":[]}]
+        }"#;
+        let err = canvas.try_load_document(json).unwrap_err();
+        assert_eq!(err.code(), "invalid_canvas_size");
+        // Original canvas unchanged
+        assert_eq!(canvas.state.grid.width(), 10);
+        assert_eq!(canvas.state.grid.height(), 10);
+    }
+
+    #[test]
+    fn test_load_document_rejects_too_many_layers() {
+        let mut canvas = AsciiEditor::new(10, 10);
+        let layers: String = (0..40)
+            .map(|i| format!(r#"{{"name":"L{i}","visible":true,"cells":[]}}"#))
+            .collect::<Vec<_>>()
+            .join(",");
+        let json = format!(
+            r#"{{"format":"ascii-canvas","version":1,"canvas":{{"width":10,"height":10}},"active_layer":0,"layers":[{layers}]}}"#
+        );
+        let err = canvas.try_load_document(&json).unwrap_err();
+        assert_eq!(err.code(), "too_many_layers");
+    }
+
+    #[test]
+    fn test_round_trip_keeps_styles_preferences_and_metadata() {
+        let mut canvas = make_canvas_with_box();
+        canvas
+            .state
+            .grid
+            .set(2, 1, Cell::with_style('X', CellStyle::BOLD));
+        canvas.set_border_style("double".to_string());
+        canvas.set_tool("arrow".to_string());
+        canvas.set_document_title("Flow".to_string());
+        canvas.renderer.set_zoom(2.0);
+        let json = canvas.serialize_document(None);
+
+        let mut other = AsciiEditor::new(10, 10);
+        assert!(other.try_load_document(&json).unwrap().is_empty());
+        assert_eq!(other.state.grid.get(2, 1).unwrap().style, CellStyle::BOLD);
+        assert_eq!(other.state.border_style, BorderStyle::Double);
+        assert_eq!(other.tool_id, ToolId::Arrow);
+        assert_eq!(other.document_title(), "Flow");
+        assert_eq!(other.renderer.zoom(), 2.0);
+        assert!(other.document_modified().is_some());
+    }
+
+    #[test]
+    fn test_load_document_rejects_future_version() {
+        let mut canvas = AsciiEditor::new(10, 10);
+        let json = r#"{"format":"ascii-canvas","version":4,"canvas":{"width":10,"height":10},"layers":[]}"#;
+        let err = canvas.try_load_document(json).unwrap_err();
+        assert_eq!(err.code(), "unsupported_version");
+    }
+
+    #[test]
+    fn test_load_document_reports_warnings() {
+        let mut canvas = AsciiEditor::new(10, 10);
+        let json = r#"{"format":"ascii-canvas","version":1,"canvas":{"width":4,"height":4},
+            "layers":[{"name":"L","cells":[{"x":1,"y":1,"ch":"Hi"},{"x":9,"y":9,"ch":"Z"}]}]}"#;
+        let warnings = canvas.try_load_document(json).unwrap();
+        let codes: Vec<_> = warnings.iter().map(|w| w.code()).collect();
+        assert_eq!(codes, ["cells_out_of_bounds", "chars_truncated"]);
+        assert_eq!(canvas.state.grid.get(1, 1).unwrap().ch, 'H');
+    }
+
+    #[test]
+    fn test_compact_serialization_loads_transparently() {
+        let canvas = make_canvas_with_box();
+        let compact = canvas.serialize_document(Some("rle+deflate".to_string()));
+        assert!(compact.contains("rle+deflate"));
+
+        let mut other = AsciiEditor::new(10, 10);
+        assert!(other.try_load_document(&compact).unwrap().is_empty());
+        assert_eq!(other.export_for_copy(), canvas.export_for_copy());
+    }
+
+    #[test]
+    fn test_text_format_loads_transparently() {
+        let canvas = make_canvas_with_box();
+        let text = canvas.serialize_document_text();
+        assert!(text.contains("\n┌───┐\n"));
+
+        let mut other = AsciiEditor::new(10, 10);
+        assert!(other.try_load_document(&text).unwrap().is_empty());
+        assert_eq!(other.export_for_copy(), canvas.export_for_copy());
+    }
+
+    #[test]
+    fn test_pages_round_trip() {
+        let mut canvas = make_canvas_with_box();
+        canvas.add_page_impl(Some("Components".to_string()), 20, 4);
+        canvas.state.grid.set_char(3, 3, 'Z');
+        canvas.add_layer_impl();
+        let id = canvas
+            .group_layers_impl(0, 1, Some("Parts".into()))
+            .unwrap();
+        canvas.set_group_locked(id, true);
+        canvas.set_active_page_impl(0);
+        let json = canvas.serialize_document(None);
+
+        let mut other = AsciiEditor::new(10, 10);
+        assert!(other.try_load_document(&json).unwrap().is_empty());
+        assert_eq!(other.page_count(), 2);
+        assert_eq!(other.page_name(1), "Components");
+        assert_eq!(other.export_for_copy(), canvas.export_for_copy());
+        assert_eq!(other.export_page_ascii(1), canvas.export_page_ascii(1));
+        assert_eq!(other.page_size(1), vec![20, 4]);
+
+        other.set_active_page_impl(1);
+        assert_eq!(other.group_name(id), "Parts");
+        assert_eq!(other.layer_group(1), Some(id));
+        assert!(other.is_active_layer_locked());
+    }
+}
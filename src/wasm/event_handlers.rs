@@ -19,7 +19,7 @@ impl AsciiEditor {
             return self.js_event_result();
         }
 
-        let (x, y) = self.renderer.screen_to_grid(screen_x, screen_y);
+        let (x, y) = self.screen_to_layer(screen_x, screen_y);
         self.last_cursor = Some((x, y));
         let ctx = self.create_tool_context();
         let result = self.active_tool.on_pointer_down(x, y, &ctx);
@@ -60,8 +60,7 @@ impl AsciiEditor {
                 let dx = screen_x - lx;
                 let dy = screen_y - ly;
                 let (px, py) = self.renderer.pan();
-                self.renderer.set_pan(px + dx, py + dy);
-                self.dirty_tracker.request_full_redraw();
+                self.pan_to(px + dx, py + dy);
             }
             self.last_pan_pos = Some((screen_x, screen_y));
             return self.js_event_result();
@@ -70,8 +69,9 @@ impl AsciiEditor {
             return self.js_event_result();
         }
 
-        let (x, y) = self.renderer.screen_to_grid(screen_x, screen_y);
+        let (x, y) = self.screen_to_layer(screen_x, screen_y);
         self.last_cursor = Some((x, y));
+        self.pointer_over_canvas = true;
         let ctx = self.create_tool_context();
         let result = self.active_tool.on_pointer_move(x, y, &ctx);
 
@@ -108,7 +108,7 @@ impl AsciiEditor {
             return self.js_event_result();
         }
 
-        let (x, y) = self.renderer.screen_to_grid(screen_x, screen_y);
+        let (x, y) = self.screen_to_layer(screen_x, screen_y);
         let ctx = self.create_tool_context();
         let result = self.active_tool.on_pointer_up(x, y, &ctx);
 
@@ -130,9 +130,17 @@ impl AsciiEditor {
         self.js_event_result()
     }
 
-    /// Handles keyboard key down events for shortcuts, copy/paste, backspace/delete, etc.
+    /// Handles keyboard key down events: bound shortcuts (see `shortcutAction`),
+    /// backspace/delete and text typing.
     #[wasm_bindgen(js_name = onKeyDown)]
-    pub fn on_key_down(&mut self, key: String, ctrl: bool, shift: bool) -> JsValue {
+    pub fn on_key_down(
+        &mut self,
+        key: String,
+        ctrl: bool,
+        shift: bool,
+        alt: bool,
+        meta: bool,
+    ) -> JsValue {
         let key_char = if key.len() == 1 {
             key.chars().next().unwrap_or('\0')
         } else {
@@ -145,51 +153,15 @@ impl AsciiEditor {
             }
         };
 
-        if key == "Escape" {
-            self.active_tool.reset();
-            self.current_selection = None;
-            self.preview_ops.clear();
-            self.dirty_tracker.request_full_redraw();
-            return self.js_event_result();
-        }
-
-        if key_char == ' ' && !ctrl && !shift {
-            self.space_held = true;
-            return self.js_event_result();
-        }
-
-        if ctrl && !shift && key.to_lowercase() == "z" {
-            self.undo();
-            return self.js_event_result();
-        }
-
-        if ctrl && shift && key.to_lowercase() == "z" {
-            self.redo();
-            return self.js_event_result();
-        }
-
-        if ctrl && !shift && key.to_lowercase() == "y" {
-            self.redo();
-            return self.js_event_result();
-        }
-
-        if ctrl && key.to_lowercase() == "c" {
-            // Fill internal SelectionClipboard, then export selection-aware ASCII for OS clipboard.
-            let _ = self.copy_selection_impl();
-            return self.js_event_result_with_copy(true);
-        }
-
-        if ctrl && key.to_lowercase() == "a" {
-            self.select_all_impl();
-            return self.js_event_result();
-        }
-
-        if ctrl && key.to_lowercase() == "x" && self.cut_selection_impl() {
-            return self.js_event_result();
-        }
-
-        if ctrl && key.to_lowercase() == "v" && self.paste_impl() {
-            return self.js_event_result();
+        if let Some(action) = self.shortcut_action(&key, ctrl, shift, alt, meta) {
+            if self.run_shortcut(&action) {
+                return if action == "copy" {
+                    // The copied selection also goes to the OS clipboard
+                    self.js_event_result_with_copy(true)
+                } else {
+                    self.js_event_result()
+                };
+            }
         }
 
         if !ctrl && (key == "Delete" || key == "Backspace") {
@@ -213,22 +185,11 @@ impl AsciiEditor {
             }
         }
 
-        if !ctrl && !shift && !self.active_tool.is_active() {
-            if let Some(tool_id) = ToolId::from_shortcut(key_char) {
-                self.set_tool_by_id_impl(tool_id);
-                return self.js_event_result();
-            }
-        }
-
         if self.tool_id == ToolId::Text && self.active_tool.is_active() {
             if self.is_active_layer_locked() {
                 return self.js_event_result();
             }
-            let ctx = self.create_tool_context();
-            let result = self.active_tool.on_key(key_char, &ctx);
-            if result.modified {
-                self.commit_ops(&result.ops);
-            }
+            self.type_key(key_char);
             return self.js_event_result();
         }
 
@@ -257,6 +218,7 @@ impl AsciiEditor {
         let new_pan_y = screen_y - (screen_y - py) * zoom_ratio;
 
         self.renderer.set_zoom(new_zoom);
+        self.update_render_scale();
         self.renderer.set_pan(new_pan_x, new_pan_y);
         self.dirty_tracker.request_full_redraw();
 
@@ -265,6 +227,16 @@ impl AsciiEditor {
 }
 
 impl AsciiEditor {
+    /// Feed a key to the text tool; typed characters take the current text style.
+    pub(crate) fn type_key(&mut self, key_char: char) {
+        let ctx = self.create_tool_context();
+        let mut result = self.active_tool.on_key(key_char, &ctx);
+        if result.modified {
+            self.apply_text_style(&mut result.ops);
+            self.commit_ops(&result.ops);
+        }
+    }
+
     fn js_event_result(&self) -> JsValue {
         let er = self.create_event_result();
         serde_wasm_bindgen::to_value(&er).unwrap_or(JsValue::NULL)
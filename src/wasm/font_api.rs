@@ -0,0 +1,301 @@
+//! Font API - bitmap fonts, glyph size and render scale for the pixel buffer.
+//!
+//! The glyph size is in CSS pixels at zoom 1. The pixel buffer is rendered with
+//! glyphs scaled by device scale × zoom, so JS can draw it 1:1 on a HiDPI canvas
+//! instead of stretching a 1× bitmap. Atlases for the last few render sizes are
+//! kept, so zooming back and forth does not rebuild them.
+
+use wasm_bindgen::prelude::*;
+
+use super::bindings::AsciiEditor;
+use crate::render::{BitmapFont, FontAtlas, FontError, MAX_GLYPH_HEIGHT, MAX_GLYPH_WIDTH};
+
+/// Atlases kept for render sizes other than the current one.
+const MAX_CACHED_ATLASES: usize = 4;
+/// Largest pixel buffer, in pixels; beyond it glyphs are rendered smaller (but never
+/// below 1×) and JS scales the rest of the way.
+pub(crate) const MAX_BUFFER_PIXELS: usize = 4096 * 4096;
+/// Accepted range for the device scale.
+const DEVICE_SCALE_RANGE: std::ops::RangeInclusive<f64> = 0.5..=4.0;
+
+#[wasm_bindgen]
+impl AsciiEditor {
+    /// Updates the font atlas glyph data cache for a specific Unicode character.
+    /// `glyph_data` is an alpha mask at the current render size
+    /// (`cellPixelWidth`×`cellPixelHeight`).
+    #[wasm_bindgen(js_name = updateFontAtlasGlyph)]
+    pub fn update_font_atlas_glyph(&mut self, ch_code: u32, glyph_data: Vec<u8>) {
+        if let Some(ch) = char::from_u32(ch_code) {
+            self.font_atlas.update_glyph(ch, &glyph_data);
+            self.dirty_tracker.request_full_redraw();
+        }
+    }
+
+    /// Load a BDF or PSF2 bitmap font for the pixel buffer. The glyph size becomes the
+    /// font's cell size; characters the font lacks keep the built-in glyphs.
+    /// Returns the number of glyphs loaded.
+    #[wasm_bindgen(js_name = loadFont)]
+    pub fn load_font(&mut self, bytes: &[u8]) -> Result<usize, JsValue> {
+        self.load_font_impl(bytes)
+            .map_err(|e| JsError::new(&e.to_string()).into())
+    }
+
+    /// Go back to the built-in font, keeping the current glyph size.
+    #[wasm_bindgen(js_name = resetFont)]
+    pub fn reset_font(&mut self) {
+        self.font = None;
+        self.rebuild_atlas();
+    }
+
+    /// Glyph cell width in CSS pixels at zoom 1.
+    #[wasm_bindgen(getter = glyphWidth)]
+    pub fn glyph_width(&self) -> usize {
+        self.glyph_size.0
+    }
+
+    /// Glyph cell height in CSS pixels at zoom 1.
+    #[wasm_bindgen(getter = glyphHeight)]
+    pub fn glyph_height(&self) -> usize {
+        self.glyph_size.1
+    }
+
+    /// Scale the current font to `width`×`height` CSS pixels per cell and resize the
+    /// pixel buffer. Returns false (and changes nothing) for sizes outside 1×1 to 32×64.
+    #[wasm_bindgen(js_name = setGlyphSize)]
+    pub fn set_glyph_size(&mut self, width: usize, height: usize) -> bool {
+        if !(1..=MAX_GLYPH_WIDTH).contains(&width) || !(1..=MAX_GLYPH_HEIGHT).contains(&height) {
+            return false;
+        }
+        self.glyph_size = (width, height);
+        self.rebuild_atlas();
+        true
+    }
+
+    /// Device pixels per CSS pixel.
+    #[wasm_bindgen(getter = deviceScale)]
+    pub fn device_scale(&self) -> f64 {
+        self.device_scale
+    }
+
+    /// Render the pixel buffer for a display with `scale` device pixels per CSS pixel
+    /// (pass `window.devicePixelRatio`). Values outside 0.5 to 4 are clamped.
+    #[wasm_bindgen(js_name = setDeviceScale)]
+    pub fn set_device_scale(&mut self, scale: f64) {
+        if scale.is_finite() {
+            self.device_scale = scale.clamp(*DEVICE_SCALE_RANGE.start(), *DEVICE_SCALE_RANGE.end());
+            self.update_render_scale();
+        }
+    }
+
+    /// Glyph cell width in pixel-buffer pixels.
+    #[wasm_bindgen(getter = cellPixelWidth)]
+    pub fn cell_pixel_width(&self) -> usize {
+        self.font_atlas.glyph_width
+    }
+
+    /// Glyph cell height in pixel-buffer pixels.
+    #[wasm_bindgen(getter = cellPixelHeight)]
+    pub fn cell_pixel_height(&self) -> usize {
+        self.font_atlas.glyph_height
+    }
+
+    /// Pixel buffer width in pixels.
+    #[wasm_bindgen(getter = bufferWidth)]
+    pub fn buffer_width(&self) -> usize {
+        self.pixel_layout().width
+    }
+
+    /// Pixel buffer height in pixels.
+    #[wasm_bindgen(getter = bufferHeight)]
+    pub fn buffer_height(&self) -> usize {
+        self.pixel_layout().height
+    }
+}
+
+impl AsciiEditor {
+    pub(crate) fn load_font_impl(&mut self, bytes: &[u8]) -> Result<usize, FontError> {
+        let font = BitmapFont::parse(bytes)?;
+        let count = font.len();
+        self.glyph_size = (font.width, font.height);
+        self.font = Some(font);
+        self.rebuild_atlas();
+        Ok(count)
+    }
+
+    /// Glyph size in pixel-buffer pixels for the current device scale and zoom,
+    /// reduced when a whole-canvas buffer would exceed `MAX_BUFFER_PIXELS`.
+    fn render_glyph_size(&self) -> (usize, usize) {
+        let (width, height) = self.glyph_size;
+        let cells = match self.viewport {
+            Some(_) => 0,
+            None => self.state.grid.width() * self.state.grid.height(),
+        };
+        let scale = self.device_scale * self.renderer.zoom();
+        let pixels = (cells * width * height) as f64 * scale * scale;
+        let (scale, round): (f64, fn(f64) -> f64) = if pixels > MAX_BUFFER_PIXELS as f64 {
+            let capped = scale * (MAX_BUFFER_PIXELS as f64 / pixels).sqrt();
+            (capped.max(scale.min(1.0)), f64::floor)
+        } else {
+            (scale, f64::round)
+        };
+        let scaled = |size: usize| (round(size as f64 * scale) as usize).max(1);
+        (scaled(width), scaled(height))
+    }
+
+    /// Switch to the atlas for the current render size when it changed, reusing a
+    /// cached one when possible.
+    pub(crate) fn update_render_scale(&mut self) {
+        let size = self.render_glyph_size();
+        if size == (self.font_atlas.glyph_width, self.font_atlas.glyph_height) {
+            return;
+        }
+        let atlas = match self
+            .atlas_cache
+            .iter()
+            .position(|a| (a.glyph_width, a.glyph_height) == size)
+        {
+            Some(i) => self.atlas_cache.remove(i),
+            None => self.build_atlas(size),
+        };
+        let previous = std::mem::replace(&mut self.font_atlas, atlas);
+        self.atlas_cache.push(previous);
+        if self.atlas_cache.len() > MAX_CACHED_ATLASES {
+            self.atlas_cache.remove(0);
+        }
+        self.reset_pixel_buffer();
+    }
+
+    /// Rebuild the atlas after the font or glyph size changed.
+    fn rebuild_atlas(&mut self) {
+        self.atlas_cache.clear();
+        self.font_atlas = self.build_atlas(self.render_glyph_size());
+        self.reset_pixel_buffer();
+    }
+
+    fn build_atlas(&self, (width, height): (usize, usize)) -> FontAtlas {
+        match &self.font {
+            Some(font) => FontAtlas::from_font(font, width, height),
+            None => FontAtlas::with_size(width, height),
+        }
+    }
+
+    /// Reallocate the pixel buffer for the current canvas (or viewport) and glyph size.
+    pub(crate) fn reset_pixel_buffer(&mut self) {
+        self.pixel_buffer = vec![0u8; self.pixel_layout().byte_len()];
+        self.dirty_tracker.request_full_redraw();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::core::tools::DrawOp;
+    use crate::render::FontError;
+    use crate::wasm::bindings::AsciiEditor;
+
+    /// A 4×4 font whose only glyph, `┼`, is a full cross.
+    const CROSS_BDF: &str = "STARTFONT 2.1
+FONTBOUNDINGBOX 4 4 0 0
+STARTCHAR cross
+ENCODING 9532
+BBX 4 4 0 0
+BITMAP
+20
+F0
+20
+20
+ENDCHAR
+ENDFONT
+";
+
+    #[test]
+    fn test_loaded_font_sets_glyph_size_and_renders() {
+        let mut canvas = AsciiEditor::new(3, 2);
+        assert_eq!(canvas.load_font_impl(CROSS_BDF.as_bytes()), Ok(1));
+        assert_eq!((canvas.glyph_width(), canvas.glyph_height()), (4, 4));
+        assert_eq!(canvas.pixel_buffer.len(), 3 * 4 * 2 * 4 * 4);
+        assert!(canvas.font_atlas.has_glyph('┼'));
+        assert!(
+            canvas.font_atlas.has_glyph('A'),
+            "built-in glyphs fill the gaps"
+        );
+
+        canvas.commit_ops(&[DrawOp::new(1, 1, '┼')]);
+        canvas.render_to_pixel_buffer();
+        let lit = |x: usize, y: usize| {
+            let i = (y * 12 + x) * 4;
+            canvas.pixel_buffer[i..i + 3] != [0x1e, 0x1e, 0x1e]
+        };
+        // Cell (1, 1) starts at pixel (4, 4); its second row is the cross bar.
+        assert!((4..8).all(|x| lit(x, 5)));
+        assert!(!lit(4, 4) && lit(6, 4));
+    }
+
+    #[test]
+    fn test_glyph_size_is_configurable() {
+        let mut canvas = AsciiEditor::new(3, 2);
+        assert!(canvas.set_glyph_size(10, 24));
+        assert_eq!(canvas.pixel_buffer.len(), 3 * 10 * 2 * 24 * 4);
+        assert!(!canvas.set_glyph_size(0, 24));
+        assert!(!canvas.set_glyph_size(10, 65));
+        assert_eq!(canvas.glyph_width(), 10);
+
+        canvas.load_font_impl(CROSS_BDF.as_bytes()).unwrap();
+        assert!(canvas.set_glyph_size(8, 8));
+        canvas.reset_font();
+        assert!(canvas.font.is_none());
+        assert_eq!((canvas.glyph_width(), canvas.glyph_height()), (8, 8));
+        assert_eq!(
+            canvas.load_font_impl(b"not a font"),
+            Err(FontError::UnknownFormat)
+        );
+    }
+
+    #[test]
+    fn test_buffer_renders_at_device_scale_and_zoom() {
+        let mut canvas = AsciiEditor::new(3, 2);
+        canvas.set_device_scale(2.0);
+        assert_eq!(
+            (canvas.cell_pixel_width(), canvas.cell_pixel_height()),
+            (16, 40)
+        );
+        assert_eq!((canvas.glyph_width(), canvas.glyph_height()), (8, 20));
+        assert_eq!(canvas.pixel_buffer.len(), 48 * 80 * 4);
+
+        // A `|` at 2× is two device pixels wide and fully opaque, not a blurred line.
+        canvas.commit_ops(&[DrawOp::new(0, 0, '|')]);
+        canvas.render_to_pixel_buffer();
+        let px = |x: usize, y: usize| {
+            let i = (y * canvas.buffer_width() + x) * 4;
+            canvas.pixel_buffer[i..i + 3].to_vec()
+        };
+        assert_eq!(px(8, 10), px(9, 10));
+        assert_eq!(px(8, 10), vec![0xd4, 0xd4, 0xd4]);
+        assert_eq!(px(7, 10), vec![0x1e, 0x1e, 0x1e]);
+
+        canvas.set_zoom(1.5);
+        assert_eq!(
+            (canvas.cell_pixel_width(), canvas.cell_pixel_height()),
+            (24, 60)
+        );
+        canvas.set_zoom(1.0);
+        assert_eq!(canvas.atlas_cache.len(), 2);
+        canvas.set_zoom(1.5);
+        assert_eq!(canvas.atlas_cache.len(), 2, "cached atlas reused");
+        assert_eq!(canvas.buffer_width(), 72);
+    }
+
+    #[test]
+    fn test_render_size_is_capped() {
+        let mut canvas = AsciiEditor::new(300, 200);
+        canvas.set_device_scale(4.0);
+        canvas.set_zoom(4.0);
+        assert!(canvas.buffer_width() * canvas.buffer_height() <= 4096 * 4096);
+        assert!(canvas.cell_pixel_width() >= 8);
+
+        // Canvases already over the budget at 1× are not shrunk further.
+        canvas.resize(1000, 500);
+        assert_eq!(canvas.cell_pixel_width(), 8);
+        canvas.set_device_scale(f64::NAN);
+        assert_eq!(canvas.device_scale(), 4.0);
+    }
+}
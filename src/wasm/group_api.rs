@@ -0,0 +1,330 @@
+//! Group API - named groups of adjacent layers with cascading visibility and lock.
+//!
+//! Groups are one level deep and always hold a contiguous run of layers; groups do
+//! not nest, so a range that already holds a grouped layer cannot be grouped. A
+//! layer is shown only when both it and its group are visible, and is locked when
+//! either is.
+
+use wasm_bindgen::prelude::*;
+
+use super::bindings::AsciiEditor;
+use crate::core::document::LayerGroup;
+
+#[wasm_bindgen]
+impl AsciiEditor {
+    /// Group the layers `from_index..=to_index` (in either order) under a new group.
+    /// Returns the group id, or `undefined` when the range is invalid or already holds
+    /// a grouped layer (groups do not nest).
+    #[wasm_bindgen(js_name = groupLayers)]
+    pub fn group_layers(
+        &mut self,
+        from_index: usize,
+        to_index: usize,
+        name: Option<String>,
+    ) -> Option<u32> {
+        self.group_layers_impl(from_index, to_index, name)
+    }
+
+    /// Dissolve a group; its layers stay where they are.
+    #[wasm_bindgen(js_name = ungroup)]
+    pub fn ungroup(&mut self, id: u32) -> bool {
+        self.ungroup_impl(id)
+    }
+
+    /// Id of the group a layer belongs to.
+    #[wasm_bindgen(js_name = layerGroup)]
+    pub fn layer_group(&self, index: usize) -> Option<u32> {
+        self.layers.get(index).and_then(|l| l.group)
+    }
+
+    /// Ids of the active page's groups.
+    #[wasm_bindgen(js_name = groupIds)]
+    pub fn group_ids(&self) -> Vec<u32> {
+        self.groups.iter().map(|g| g.id).collect()
+    }
+
+    /// Group name by id.
+    #[wasm_bindgen(js_name = groupName)]
+    pub fn group_name(&self, id: u32) -> String {
+        self.group(id).map(|g| g.name.clone()).unwrap_or_default()
+    }
+
+    /// Rename a group.
+    #[wasm_bindgen(js_name = renameGroup)]
+    pub fn rename_group(&mut self, id: u32, name: String) {
+        if let Some(group) = self.group_mut(id) {
+            group.name = name;
+        }
+    }
+
+    /// Whether a group is visible.
+    #[wasm_bindgen(js_name = groupVisible)]
+    pub fn group_visible(&self, id: u32) -> bool {
+        self.group(id).map(|g| g.visible).unwrap_or(false)
+    }
+
+    /// Show or hide every layer in a group.
+    #[wasm_bindgen(js_name = setGroupVisible)]
+    pub fn set_group_visible(&mut self, id: u32, visible: bool) {
+        if let Some(group) = self.group_mut(id) {
+            group.visible = visible;
+            self.dirty_tracker.request_full_redraw();
+        }
+    }
+
+    /// Whether a group is locked.
+    #[wasm_bindgen(js_name = groupLocked)]
+    pub fn group_locked(&self, id: u32) -> bool {
+        self.group(id).map(|g| g.locked).unwrap_or(false)
+    }
+
+    /// Lock or unlock every layer in a group.
+    #[wasm_bindgen(js_name = setGroupLocked)]
+    pub fn set_group_locked(&mut self, id: u32, locked: bool) {
+        if let Some(group) = self.group_mut(id) {
+            group.locked = locked;
+        }
+    }
+
+    /// Whether a group is folded in the layer panel.
+    #[wasm_bindgen(js_name = groupCollapsed)]
+    pub fn group_collapsed(&self, id: u32) -> bool {
+        self.group(id).map(|g| g.collapsed).unwrap_or(false)
+    }
+
+    /// Fold or unfold a group in the layer panel.
+    #[wasm_bindgen(js_name = setGroupCollapsed)]
+    pub fn set_group_collapsed(&mut self, id: u32, collapsed: bool) {
+        if let Some(group) = self.group_mut(id) {
+            group.collapsed = collapsed;
+        }
+    }
+
+    /// Move a layer into a group (just above its top member), or out of its group
+    /// (`undefined`, just above the group).
+    #[wasm_bindgen(js_name = moveLayerToGroup)]
+    pub fn move_layer_to_group(&mut self, index: usize, group: Option<u32>) -> bool {
+        self.move_layer_to_group_impl(index, group)
+    }
+}
+
+impl AsciiEditor {
+    pub(crate) fn group(&self, id: u32) -> Option<&LayerGroup> {
+        self.groups.iter().find(|g| g.id == id)
+    }
+
+    fn group_mut(&mut self, id: u32) -> Option<&mut LayerGroup> {
+        self.groups.iter_mut().find(|g| g.id == id)
+    }
+
+    /// Whether a layer and its group are both visible.
+    pub(crate) fn layer_shown(&self, index: usize) -> bool {
+        self.layers
+            .get(index)
+            .is_some_and(|l| l.visible && group_visible(&self.groups, l.group))
+    }
+
+    /// Whether a layer or its group is locked.
+    pub(crate) fn layer_effectively_locked(&self, index: usize) -> bool {
+        self.layers.get(index).is_some_and(|l| {
+            l.locked
+                || l.group
+                    .and_then(|id| self.group(id))
+                    .is_some_and(|g| g.locked)
+        })
+    }
+
+    pub(crate) fn group_layers_impl(
+        &mut self,
+        from_index: usize,
+        to_index: usize,
+        name: Option<String>,
+    ) -> Option<u32> {
+        let (lo, hi) = (from_index.min(to_index), from_index.max(to_index));
+        if hi >= self.layers.len() {
+            return None;
+        }
+        // Regrouping members would dissolve or split their group
+        if self.layers[lo..=hi].iter().any(|l| l.group.is_some()) {
+            return None;
+        }
+        let id = self.groups.iter().map(|g| g.id).max().unwrap_or(0) + 1;
+        let name = name
+            .filter(|n| !n.is_empty())
+            .unwrap_or_else(|| format!("Group {id}"));
+        self.groups.push(LayerGroup::new(id, &name));
+        for layer in &mut self.layers[lo..=hi] {
+            layer.group = Some(id);
+        }
+        self.prune_groups();
+        self.dirty_tracker.request_full_redraw();
+        Some(id)
+    }
+
+    pub(crate) fn ungroup_impl(&mut self, id: u32) -> bool {
+        if self.group(id).is_none() {
+            return false;
+        }
+        for layer in self.layers.iter_mut().filter(|l| l.group == Some(id)) {
+            layer.group = None;
+        }
+        self.prune_groups();
+        self.dirty_tracker.request_full_redraw();
+        true
+    }
+
+    pub(crate) fn move_layer_to_group_impl(&mut self, index: usize, group: Option<u32>) -> bool {
+        let Some(current) = self.layers.get(index).map(|l| l.group) else {
+            return false;
+        };
+        if current == group {
+            return true;
+        }
+        // Both moves land just above a group's top member.
+        let Some(top) = self.top_member(group.or(current)) else {
+            return false;
+        };
+        self.layers[index].group = group;
+        let to_index = if index > top { top + 1 } else { top };
+        if to_index == index {
+            self.regroup_moved_layer(index);
+        } else {
+            self.move_layer_impl(index, to_index);
+        }
+        true
+    }
+
+    fn top_member(&self, group: Option<u32>) -> Option<usize> {
+        let id = group?;
+        self.layers.iter().rposition(|l| l.group == Some(id))
+    }
+
+    /// After a layer moved to `index`: it joins the group it landed inside, keeps its
+    /// group while still next to another member, and otherwise leaves it.
+    pub(crate) fn regroup_moved_layer(&mut self, index: usize) {
+        let below = index.checked_sub(1).and_then(|i| self.layers[i].group);
+        let above = self.layers.get(index + 1).and_then(|l| l.group);
+        let layer = &mut self.layers[index];
+        if below.is_some() && below == above {
+            layer.group = below;
+        } else if layer.group.is_some() && layer.group != below && layer.group != above {
+            let id = layer.group;
+            let others = self
+                .layers
+                .iter()
+                .enumerate()
+                .any(|(i, l)| i != index && l.group == id);
+            if others {
+                self.layers[index].group = None;
+            }
+        }
+        self.prune_groups();
+    }
+
+    /// Drop groups that no longer have any members.
+    pub(crate) fn prune_groups(&mut self) {
+        let layers = &self.layers;
+        self.groups
+            .retain(|g| layers.iter().any(|l| l.group == Some(g.id)));
+    }
+}
+
+/// Whether the group `id` (if any) is visible in `groups`.
+pub(crate) fn group_visible(groups: &[LayerGroup], id: Option<u32>) -> bool {
+    id.and_then(|id| groups.iter().find(|g| g.id == id))
+        .map_or(true, |g| g.visible)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::core::tools::DrawOp;
+    use crate::wasm::bindings::AsciiEditor;
+
+    /// Four layers; layers 1 and 2 grouped.
+    fn grouped() -> (AsciiEditor, u32) {
+        let mut canvas = AsciiEditor::new(4, 1);
+        for (i, ch) in "abcd".chars().enumerate() {
+            if i > 0 {
+                canvas.add_layer_impl();
+            }
+            canvas.commit_ops(&[DrawOp::new(i as i32, 0, ch)]);
+        }
+        let id = canvas.group_layers_impl(2, 1, None).unwrap();
+        (canvas, id)
+    }
+
+    fn groups_of(canvas: &AsciiEditor) -> Vec<Option<u32>> {
+        (0..canvas.layer_count())
+            .map(|i| canvas.layer_group(i))
+            .collect()
+    }
+
+    #[test]
+    fn test_group_visibility_and_lock_cascade() {
+        let (mut canvas, id) = grouped();
+        assert_eq!(canvas.group_name(id), "Group 1");
+
+        canvas.set_group_visible(id, false);
+        assert_eq!(canvas.export_ascii(), "a  d");
+        assert!(canvas.layer_visible(1));
+
+        canvas.set_active_layer_impl(2);
+        assert!(!canvas.is_active_layer_locked());
+        canvas.set_group_locked(id, true);
+        assert!(canvas.is_active_layer_locked());
+        assert!(!canvas.layer_locked(2));
+
+        canvas.set_group_collapsed(id, true);
+        assert!(canvas.group_collapsed(id));
+    }
+
+    #[test]
+    fn test_move_layer_in_and_out_of_groups() {
+        let (mut canvas, id) = grouped();
+        assert!(canvas.move_layer_to_group_impl(0, Some(id)));
+        assert_eq!(canvas.layer_name(2), "Layer 1");
+        assert_eq!(groups_of(&canvas), [Some(id), Some(id), Some(id), None]);
+
+        assert!(canvas.move_layer_to_group_impl(0, None));
+        assert_eq!(canvas.layer_name(2), "Layer 2");
+        assert_eq!(groups_of(&canvas), [Some(id), Some(id), None, None]);
+
+        // Dropping a layer between two members joins the group ...
+        canvas.move_layer_impl(3, 1);
+        assert_eq!(groups_of(&canvas), [Some(id), Some(id), Some(id), None]);
+        // ... and layers dragged away from the other members leave it.
+        canvas.move_layer_impl(0, 3);
+        canvas.move_layer_impl(0, 3);
+        assert_eq!(groups_of(&canvas), [Some(id), None, None, None]);
+        assert!(canvas.move_layer_to_group_impl(0, None));
+        assert!(canvas.group_ids().is_empty());
+    }
+
+    #[test]
+    fn test_grouping_cannot_take_grouped_layers() {
+        let mut canvas = AsciiEditor::new(4, 1);
+        for _ in 0..3 {
+            canvas.add_layer_impl();
+        }
+        let outer = canvas.group_layers_impl(0, 3, None).unwrap();
+        assert_eq!(canvas.group_layers_impl(1, 2, None), None);
+        assert_eq!(canvas.group_layers_impl(2, 3, None), None);
+        assert_eq!(canvas.group_ids(), vec![outer]);
+
+        assert!(canvas.ungroup_impl(outer));
+        let lower = canvas.group_layers_impl(0, 1, None).unwrap();
+        let inner = canvas.group_layers_impl(2, 3, Some("Top".into())).unwrap();
+        // Spanning both groups would flatten them into one
+        assert_eq!(canvas.group_layers_impl(1, 2, None), None);
+        assert_eq!(
+            groups_of(&canvas),
+            [Some(lower), Some(lower), Some(inner), Some(inner)]
+        );
+
+        assert!(canvas.ungroup_impl(lower));
+        assert_eq!(canvas.group_ids(), vec![inner]);
+        canvas.delete_layer_impl(3);
+        canvas.delete_layer_impl(2);
+        assert!(canvas.group_ids().is_empty());
+    }
+}
@@ -1,7 +1,7 @@
 //! Private helper methods for AsciiEditor.
 
 use crate::core::ascii_export::export_region;
-use crate::core::commands::{Command, DrawCommand};
+use crate::core::commands::{AddLayerCommand, Command, DrawCommand};
 use crate::core::history::{History, DEFAULT_MAX_DEPTH};
 use crate::core::selection::{Selection, SelectionClipboard};
 use crate::core::tools::{DrawOp, SelectTool, ToolContext, ToolId};
@@ -26,7 +26,8 @@ impl AsciiEditor {
         let composite = self.composite_visible_grid();
         if let Some(ref sel) = self.current_selection {
             let (min_x, min_y, max_x, max_y) = sel.bounds();
-            export_region(&composite, min_x, min_y, max_x, max_y)
+            let (ox, oy) = self.active_offset();
+            export_region(&composite, min_x + ox, min_y + oy, max_x + ox, max_y + oy)
         } else {
             export_ascii(&composite)
         }
@@ -56,8 +57,9 @@ impl AsciiEditor {
         cmd.apply(&mut self.state.grid);
         self.history.push(Box::new(cmd));
 
+        let (ox, oy) = self.active_offset();
         for op in ops {
-            self.dirty_tracker.mark_dirty(op.x, op.y);
+            self.dirty_tracker.mark_dirty(op.x + ox, op.y + oy);
         }
     }
 
@@ -430,13 +432,10 @@ impl AsciiEditor {
             &mut self.layers[self.active_layer].history,
         );
 
-        self.layers.push(super::bindings::LayerData {
+        self.layers.push(super::bindings::LayerData::new(
             name,
-            visible: true,
-            locked: false,
-            grid: crate::core::Grid::new(w, h),
-            history: History::new(DEFAULT_MAX_DEPTH),
-        });
+            crate::core::Grid::new(w, h),
+        ));
         let index = self.layers.len() - 1;
         self.active_layer = index;
         self.state.grid = crate::core::Grid::new(w, h);
@@ -447,308 +446,36 @@ impl AsciiEditor {
         index
     }
 
-    pub(crate) fn move_layer_impl(&mut self, from_index: usize, to_index: usize) {
-        if from_index >= self.layers.len()
-            || to_index >= self.layers.len()
-            || from_index == to_index
-        {
-            return;
-        }
-        self.sync_active_layer();
-
-        // Temporarily swap active history back to active layer for moving
-        let mut temp_history = std::mem::take(&mut self.history);
-        std::mem::swap(
-            &mut temp_history,
-            &mut self.layers[self.active_layer].history,
-        );
-
-        let layer = self.layers.remove(from_index);
-        self.layers.insert(to_index, layer);
-
-        if self.active_layer == from_index {
-            self.active_layer = to_index;
-        } else if from_index < to_index
-            && self.active_layer > from_index
-            && self.active_layer <= to_index
-        {
-            self.active_layer -= 1;
-        } else if from_index > to_index
-            && self.active_layer >= to_index
-            && self.active_layer < from_index
-        {
-            self.active_layer += 1;
-        }
-
-        // Swap history back from the new active layer
-        let mut temp_history = std::mem::take(&mut self.layers[self.active_layer].history);
-        std::mem::swap(&mut temp_history, &mut self.history);
-
-        self.state.grid = self.layers[self.active_layer].grid.clone();
-        self.dirty_tracker.request_full_redraw();
-    }
-
-    pub(crate) fn delete_layer_impl(&mut self, index: usize) -> bool {
-        if self.layers.len() <= 1 || index >= self.layers.len() {
-            return false;
-        }
-        self.sync_active_layer();
-
-        // Temporarily swap active history back to active layer before structural changes
-        let mut temp_history = std::mem::take(&mut self.history);
-        std::mem::swap(
-            &mut temp_history,
-            &mut self.layers[self.active_layer].history,
-        );
-
-        self.layers.remove(index);
-
-        if self.active_layer == index {
-            if self.active_layer >= self.layers.len() {
-                self.active_layer = self.layers.len() - 1;
-            }
-        } else if self.active_layer > index {
-            self.active_layer -= 1;
-        }
-
-        // Restore active grid and history
-        self.state.grid = self.layers[self.active_layer].grid.clone();
-        self.history = std::mem::take(&mut self.layers[self.active_layer].history);
-
-        self.current_selection = None;
-        self.preview_ops.clear();
-        self.dirty_tracker.request_full_redraw();
-        true
-    }
-
-    pub(crate) fn merge_down_impl(&mut self, index: usize) -> bool {
-        if index == 0 || index >= self.layers.len() {
-            return false;
-        }
-        self.sync_active_layer();
-
-        // Temporarily swap active history back to active layer before structural changes
-        let mut temp_history = std::mem::take(&mut self.history);
-        std::mem::swap(
-            &mut temp_history,
-            &mut self.layers[self.active_layer].history,
-        );
-
-        // Clone upper layer's grid
-        let upper_grid = self.layers[index].grid.clone();
-
-        // Merge into lower layer
-        {
-            let lower_layer = &mut self.layers[index - 1];
-            for (x, y, cell) in upper_grid.iter_with_coords() {
-                if cell.is_visible() {
-                    lower_layer.grid.set(x, y, *cell);
-                }
-            }
-        }
-
-        self.layers.remove(index);
-
-        if self.active_layer == index {
-            self.active_layer = index - 1;
-        } else if self.active_layer > index {
-            self.active_layer -= 1;
-        }
-
-        self.state.grid = self.layers[self.active_layer].grid.clone();
-        self.history = std::mem::take(&mut self.layers[self.active_layer].history);
-
-        self.current_selection = None;
-        self.preview_ops.clear();
+    /// Fill the just-created active layer with `ops`; undoing this removes the layer.
+    pub(crate) fn push_new_layer_ops(&mut self, ops: Vec<DrawOp>, description: &str) {
+        debug_assert!(!self.history.can_undo() && !self.is_active_layer_locked());
+        let mut cmd = AddLayerCommand::new(ops, description);
+        cmd.apply(&mut self.state.grid);
+        self.history.push(Box::new(cmd));
         self.dirty_tracker.request_full_redraw();
-        true
     }
 
-    /// Composite all visible layers (bottom → top) into a single grid.
+    /// Composite all visible non-guide layers (bottom → top) into a single grid.
     pub(crate) fn composite_visible_grid(&self) -> crate::core::Grid {
-        let w = self.state.grid.width();
-        let h = self.state.grid.height();
-        let mut out = crate::core::Grid::new(w, h);
-
-        for (i, layer) in self.layers.iter().enumerate() {
-            if !layer.visible {
-                continue;
-            }
-            let src = if i == self.active_layer {
-                &self.state.grid
-            } else {
-                &layer.grid
-            };
-            for (x, y, cell) in src.iter_with_coords() {
-                if cell.is_visible() {
-                    let _ = out.set(x, y, *cell);
-                }
-            }
-        }
-        out
-    }
-
-    pub(crate) fn serialize_document_impl(&self) -> String {
-        // Snapshot active layer content for serialization without requiring &mut.
-        #[derive(serde::Serialize)]
-        struct DocCell {
-            x: i32,
-            y: i32,
-            ch: String,
-        }
-        #[derive(serde::Serialize)]
-        struct DocLayer {
-            name: String,
-            visible: bool,
-            locked: bool,
-            cells: Vec<DocCell>,
-        }
-        #[derive(serde::Serialize)]
-        struct Document {
-            format: &'static str,
-            version: u32,
-            canvas: CanvasSize,
-            active_layer: usize,
-            layers: Vec<DocLayer>,
-        }
-        #[derive(serde::Serialize)]
-        struct CanvasSize {
-            width: usize,
-            height: usize,
-        }
-
-        let mut layers = Vec::with_capacity(self.layers.len());
-        for (i, layer) in self.layers.iter().enumerate() {
-            let src = if i == self.active_layer {
-                &self.state.grid
-            } else {
-                &layer.grid
-            };
-            let mut cells = Vec::new();
-            for (x, y, cell) in src.iter_with_coords() {
-                if cell.is_visible() {
-                    cells.push(DocCell {
-                        x,
-                        y,
-                        ch: cell.ch.to_string(),
-                    });
-                }
-            }
-            layers.push(DocLayer {
-                name: layer.name.clone(),
-                visible: layer.visible,
-                locked: layer.locked,
-                cells,
-            });
-        }
-
-        let doc = Document {
-            format: "ascii-canvas",
-            version: 1,
-            canvas: CanvasSize {
-                width: self.state.grid.width(),
-                height: self.state.grid.height(),
-            },
-            active_layer: self.active_layer,
-            layers,
-        };
-
-        serde_json::to_string(&doc).unwrap_or_else(|_| "{}".to_string())
+        let views = self.layer_views();
+        let composite = self.synced_composite(&views);
+        composite.export_grid().clone()
     }
 
     #[cfg(test)]
     pub(crate) fn set_selection_for_test(&mut self, x1: i32, y1: i32, x2: i32, y2: i32) {
         self.current_selection = Some(Selection::new(x1, y1, x2, y2));
     }
+}
 
-    pub(crate) fn load_document_impl(&mut self, json: &str) -> bool {
-        #[derive(serde::Deserialize)]
-        struct DocCell {
-            x: i32,
-            y: i32,
-            ch: String,
-        }
-        #[derive(serde::Deserialize)]
-        struct DocLayer {
-            name: String,
-            #[serde(default = "default_true")]
-            visible: bool,
-            #[serde(default = "default_false")]
-            locked: bool,
-            cells: Vec<DocCell>,
-        }
-        fn default_true() -> bool {
-            true
-        }
-        fn default_false() -> bool {
-            false
-        }
-        #[derive(serde::Deserialize)]
-        struct CanvasSize {
-            width: usize,
-            height: usize,
-        }
-        #[derive(serde::Deserialize)]
-        struct Document {
-            format: String,
-            version: u32,
-            canvas: CanvasSize,
-            #[serde(default)]
-            active_layer: usize,
-            layers: Vec<DocLayer>,
-        }
-
-        let doc: Document = match serde_json::from_str(json) {
-            Ok(d) => d,
-            Err(_) => return false,
-        };
-        if doc.format != "ascii-canvas" || doc.version == 0 || doc.layers.is_empty() {
-            return false;
-        }
-        // Match UI grid Apply caps (400×200) and keep layer count bounded to avoid OOM.
-        const MAX_CANVAS_WIDTH: usize = 400;
-        const MAX_CANVAS_HEIGHT: usize = 200;
-        const MAX_LAYERS: usize = 32;
-        if doc.canvas.width == 0
-            || doc.canvas.height == 0
-            || doc.canvas.width > MAX_CANVAS_WIDTH
-            || doc.canvas.height > MAX_CANVAS_HEIGHT
-            || doc.layers.len() > MAX_LAYERS
-        {
-            return false;
-        }
-
-        let w = doc.canvas.width;
-        let h = doc.canvas.height;
-        let mut layers = Vec::new();
-        for layer in doc.layers {
-            let mut grid = crate::core::Grid::new(w, h);
-            for cell in layer.cells {
-                if let Some(ch) = cell.ch.chars().next() {
-                    let _ = grid.set_char(cell.x, cell.y, ch);
-                }
-            }
-            layers.push(super::bindings::LayerData {
-                name: layer.name,
-                visible: layer.visible,
-                locked: layer.locked,
-                grid,
-                history: History::new(DEFAULT_MAX_DEPTH),
-            });
-        }
-
-        let active = doc.active_layer.min(layers.len() - 1);
-        self.layers = layers;
-        self.active_layer = active;
-        self.state.grid = self.layers[active].grid.clone();
-        self.history.clear();
-        self.clipboard.clear();
-        self.current_selection = None;
-        self.preview_ops.clear();
-        self.pixel_buffer = vec![0u8; w * 8 * h * 20 * 4];
-        self.dirty_tracker.request_full_redraw();
-        true
-    }
+/// Draw ops recreating the non-blank cells of a grid.
+pub(crate) fn content_ops<'a>(
+    cells: impl Iterator<Item = (i32, i32, &'a crate::core::Cell)>,
+) -> Vec<DrawOp> {
+    cells
+        .filter(|(_, _, cell)| cell.is_visible())
+        .map(|(x, y, cell)| DrawOp { x, y, cell: *cell })
+        .collect()
 }
 
 #[cfg(test)]
@@ -828,15 +555,6 @@ mod clipboard_tests {
         assert_eq!(canvas.state.grid.get(5, 5).map(|c| c.ch), Some('A'));
     }
 
-    #[test]
-    fn test_serialize_load_round_trip() {
-        let canvas = make_canvas_with_box();
-        let json = canvas.serialize_document_impl();
-        let mut other = AsciiEditor::new(10, 10);
-        assert!(other.load_document_impl(&json));
-        assert_eq!(other.export_for_copy(), canvas.export_for_copy());
-    }
-
     #[test]
     fn test_add_layer() {
         let mut canvas = AsciiEditor::new(8, 8);
@@ -845,35 +563,6 @@ mod clipboard_tests {
         assert_eq!(canvas.layers.len(), 2);
     }
 
-    #[test]
-    fn test_load_document_rejects_oversized_canvas() {
-        let mut canvas = AsciiEditor::new(10, 10);
-        let json = r#"{
-            "format":"ascii-canvas",
-            "version":1,
-            "canvas":{"width":50000,"height":50000},
-            "active_layer":0,
-            "layers":[{"name":"Layer 1","visible":true,"cells":[]}]
-        }"#;
-        assert!(!canvas.load_document_impl(json));
-        // Original canvas unchanged
-        assert_eq!(canvas.state.grid.width(), 10);
-        assert_eq!(canvas.state.grid.height(), 10);
-    }
-
-    #[test]
-    fn test_load_document_rejects_too_many_layers() {
-        let mut canvas = AsciiEditor::new(10, 10);
-        let layers: String = (0..40)
-            .map(|i| format!(r#"{{"name":"L{i}","visible":true,"cells":[]}}"#))
-            .collect::<Vec<_>>()
-            .join(",");
-        let json = format!(
-            r#"{{"format":"ascii-canvas","version":1,"canvas":{{"width":10,"height":10}},"active_layer":0,"layers":[{layers}]}}"#
-        );
-        assert!(!canvas.load_document_impl(&json));
-    }
-
     #[test]
     fn test_export_and_copy_use_composite_layers() {
         let mut canvas = AsciiEditor::new(10, 10);
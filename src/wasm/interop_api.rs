@@ -0,0 +1,186 @@
+//! Import/interop API - bringing external content onto the canvas as new layers.
+
+use wasm_bindgen::prelude::*;
+
+use super::bindings::AsciiEditor;
+use super::helpers::content_ops;
+use crate::core::document::MAX_LAYERS;
+use crate::core::image_ascii::{image_to_grid, DensityRamp, ImageMode, ImageToAsciiOptions};
+use crate::core::interop::{
+    export_asciiflow, export_asciiflow_json, export_diagram, import_asciiflow, DiagramFlavor,
+};
+use crate::core::Grid;
+
+#[wasm_bindgen]
+impl AsciiEditor {
+    /// Converts an RGBA image (`width * height * 4` bytes) into ASCII art on a new layer.
+    ///
+    /// `ramp` is `standard` (` .:-=+*#%@`), `blocks` (` ░▒▓█`) or a custom
+    /// lightest-to-darkest string; `mode` is `luminance`, `dithered` or `edges`.
+    /// Row count follows the image and font cell aspect ratios.
+    /// Returns the new layer index, or `undefined` if the buffer or options are invalid
+    /// or the page is full.
+    #[wasm_bindgen(js_name = importImage)]
+    pub fn import_image(
+        &mut self,
+        rgba: &[u8],
+        width: usize,
+        height: usize,
+        target_width: usize,
+        ramp: String,
+        mode: String,
+    ) -> Option<usize> {
+        self.import_image_impl(rgba, width, height, target_width, &ramp, &mode)
+    }
+
+    /// Imports Asciiflow/Monodraw text or Asciiflow JSON onto a new layer, re-drawing
+    /// `+ - |` boxes and arrows in the current border style.
+    /// Returns the new layer index, or `undefined` if the input has no content or the
+    /// page is full.
+    #[wasm_bindgen(js_name = importAsciiflow)]
+    pub fn import_asciiflow(&mut self, input: String) -> Option<usize> {
+        self.import_asciiflow_impl(&input)
+    }
+
+    /// Exports visible layers as plain text using only the Asciiflow character set.
+    #[wasm_bindgen(js_name = exportAsciiflow)]
+    pub fn export_asciiflow(&self) -> String {
+        export_asciiflow(&self.composite_visible_grid())
+    }
+
+    /// Exports visible layers in Asciiflow's JSON save format.
+    #[wasm_bindgen(js_name = exportAsciiflowJson)]
+    pub fn export_asciiflow_json(&self) -> String {
+        export_asciiflow_json(&self.composite_visible_grid())
+    }
+
+    /// Exports visible layers as plain ASCII for diagram renderers (`svgbob` or `ditaa`).
+    /// Returns `undefined` for an unknown flavor.
+    #[wasm_bindgen(js_name = exportDiagram)]
+    pub fn export_diagram(&self, flavor: String) -> Option<String> {
+        let flavor = DiagramFlavor::from_name(&flavor)?;
+        Some(export_diagram(&self.composite_visible_grid(), flavor))
+    }
+}
+
+impl AsciiEditor {
+    pub(crate) fn import_image_impl(
+        &mut self,
+        rgba: &[u8],
+        width: usize,
+        height: usize,
+        target_width: usize,
+        ramp: &str,
+        mode: &str,
+    ) -> Option<usize> {
+        let (char_width, line_height) = self.renderer.metrics().cell_size();
+        let options = ImageToAsciiOptions {
+            target_width,
+            ramp: DensityRamp::from_name(ramp)?,
+            mode: ImageMode::from_name(mode)?,
+            cell_aspect: char_width / line_height,
+            ..Default::default()
+        };
+        let grid = image_to_grid(rgba, width, height, &options)?;
+        self.add_layer_from_grid("Image", &grid)
+    }
+
+    pub(crate) fn import_asciiflow_impl(&mut self, input: &str) -> Option<usize> {
+        let grid = import_asciiflow(input, self.state.border_style)?;
+        self.add_layer_from_grid("Asciiflow", &grid)
+    }
+
+    /// Adds a new layer holding the visible cells of `grid` (clipped to the canvas) as
+    /// a single undoable step, so undo removes the layer again. Returns `None` when the
+    /// page already holds `MAX_LAYERS` layers.
+    pub(crate) fn add_layer_from_grid(&mut self, name: &str, grid: &Grid) -> Option<usize> {
+        if self.layers.len() >= MAX_LAYERS {
+            return None;
+        }
+        let canvas = &self.state.grid;
+        let ops = content_ops(
+            grid.iter_with_coords()
+                .filter(|(x, y, _)| canvas.in_bounds(*x, *y)),
+        );
+        let index = self.add_layer_impl();
+        self.layers[index].name = name.to_string();
+        self.push_new_layer_ops(ops, &format!("Import {name}"));
+        Some(index)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::core::document::MAX_LAYERS;
+    use crate::wasm::bindings::AsciiEditor;
+
+    #[test]
+    fn test_import_image_creates_undoable_layer() {
+        let mut editor = AsciiEditor::new(20, 10);
+        // 4×4 opaque black image.
+        let rgba: Vec<u8> = [0, 0, 0, 255].repeat(16);
+
+        let index = editor
+            .import_image_impl(&rgba, 4, 4, 4, "standard", "luminance")
+            .unwrap();
+        assert_eq!(index, 1);
+        assert_eq!(editor.layer_name(1), "Image");
+        assert_eq!(editor.state.grid.get(0, 0).unwrap().ch, '@');
+
+        assert!(editor.undo());
+        assert_eq!(editor.layer_count(), 1);
+        assert!(!editor.state.grid.get(0, 0).unwrap().is_visible());
+    }
+
+    #[test]
+    fn test_import_stops_at_the_layer_cap() {
+        let mut editor = AsciiEditor::new(20, 10);
+        while editor.layer_count() < MAX_LAYERS {
+            editor.add_layer_impl();
+        }
+        let rgba: Vec<u8> = [0, 0, 0, 255].repeat(16);
+        assert_eq!(
+            editor.import_image_impl(&rgba, 4, 4, 4, "standard", "luminance"),
+            None
+        );
+        assert_eq!(editor.layer_count(), MAX_LAYERS);
+    }
+
+    #[test]
+    fn test_import_image_rejects_bad_input() {
+        let mut editor = AsciiEditor::new(20, 10);
+        assert!(editor
+            .import_image_impl(&[0; 12], 2, 2, 4, "standard", "luminance")
+            .is_none());
+        assert!(editor
+            .import_image_impl(&[0; 16], 2, 2, 4, "standard", "sepia")
+            .is_none());
+        assert_eq!(editor.layer_count(), 1);
+    }
+
+    #[test]
+    fn test_asciiflow_import_export() {
+        let mut editor = AsciiEditor::new(20, 10);
+        editor.set_border_style("double".to_string());
+
+        assert_eq!(editor.import_asciiflow_impl("+-+\n+-+"), Some(1));
+        assert_eq!(editor.state.grid.get(0, 0).unwrap().ch, '╔');
+        assert_eq!(editor.export_asciiflow(), "+-+\n+-+");
+
+        assert_eq!(editor.import_asciiflow_impl("  "), None);
+        assert_eq!(editor.layer_count(), 2);
+    }
+
+    #[test]
+    fn test_export_diagram() {
+        let mut editor = AsciiEditor::new(20, 10);
+        editor.set_border_style("rounded".to_string());
+        editor.import_asciiflow_impl("+-+\n+-+");
+
+        assert_eq!(
+            editor.export_diagram("svgbob".to_string()).unwrap(),
+            ".-.\n'-'"
+        );
+        assert!(editor.export_diagram("mermaid".to_string()).is_none());
+    }
+}
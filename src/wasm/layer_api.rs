@@ -0,0 +1,494 @@
+//! Layer API - layer positioning and merging.
+
+use wasm_bindgen::prelude::*;
+
+use super::bindings::{AsciiEditor, LayerData};
+use crate::core::commands::{shift_offset, LayerOffsetCommand, LayerTarget};
+use crate::core::compositing::{BlendMode, LayerView};
+use crate::core::document::MAX_LAYER_OFFSET;
+use crate::core::history::History;
+use crate::core::{CompositeCache, Grid};
+use std::cell::Ref;
+
+#[wasm_bindgen]
+impl AsciiEditor {
+    /// Layer offset as `[x, y]` in cells (empty for an unknown index).
+    #[wasm_bindgen(js_name = layerOffset)]
+    pub fn layer_offset(&self, index: usize) -> Vec<i32> {
+        self.layers
+            .get(index)
+            .map(|l| vec![l.offset.0, l.offset.1])
+            .unwrap_or_default()
+    }
+
+    /// Move the active layer by `(dx, dy)` cells as one undoable step. The layer
+    /// stops at `MAX_LAYER_OFFSET` from the canvas origin. Returns false when the
+    /// layer is locked or the step is longer than that.
+    #[wasm_bindgen(js_name = nudgeLayer)]
+    pub fn nudge_layer(&mut self, dx: i32, dy: i32) -> bool {
+        let (max_x, max_y) = MAX_LAYER_OFFSET;
+        let in_range = (-max_x..=max_x).contains(&dx) && (-max_y..=max_y).contains(&dy);
+        if self.is_active_layer_locked() || !in_range {
+            return false;
+        }
+        let (sx, sy) = self.active_offset();
+        self.shift_active_layer(dx, dy);
+        let (ox, oy) = self.active_offset();
+        if (ox, oy) != (sx, sy) {
+            self.history
+                .push(Box::new(LayerOffsetCommand::new(ox - sx, oy - sy)));
+        }
+        true
+    }
+
+    /// Start dragging the active layer. Returns false when the layer is locked.
+    #[wasm_bindgen(js_name = beginLayerDrag)]
+    pub fn begin_layer_drag(&mut self) -> bool {
+        if self.is_active_layer_locked() {
+            return false;
+        }
+        self.layer_drag_start = Some(self.active_offset());
+        true
+    }
+
+    /// Place the dragged layer `(dx, dy)` cells from where the drag started.
+    #[wasm_bindgen(js_name = dragLayer)]
+    pub fn drag_layer(&mut self, dx: i32, dy: i32) {
+        if let Some(start) = self.layer_drag_start {
+            let (tx, ty) = shift_offset(start, dx, dy);
+            let (ox, oy) = self.active_offset();
+            self.shift_active_layer(tx - ox, ty - oy);
+        }
+    }
+
+    /// Finish a layer drag, recording the whole move as one undo step.
+    #[wasm_bindgen(js_name = endLayerDrag)]
+    pub fn end_layer_drag(&mut self) -> bool {
+        let Some((sx, sy)) = self.layer_drag_start.take() else {
+            return false;
+        };
+        let (ox, oy) = self.active_offset();
+        if (ox, oy) != (sx, sy) {
+            self.history
+                .push(Box::new(LayerOffsetCommand::new(ox - sx, oy - sy)));
+        }
+        true
+    }
+
+    /// Layer opacity (0.0 to 1.0); only affects on-screen rendering.
+    #[wasm_bindgen(js_name = layerOpacity)]
+    pub fn layer_opacity(&self, index: usize) -> f32 {
+        self.layers.get(index).map(|l| l.opacity).unwrap_or(1.0)
+    }
+
+    /// Set layer opacity, clamped to 0.0..=1.0.
+    #[wasm_bindgen(js_name = setLayerOpacity)]
+    pub fn set_layer_opacity(&mut self, index: usize, opacity: f32) {
+        if let Some(layer) = self.layers.get_mut(index) {
+            layer.opacity = if opacity.is_nan() {
+                1.0
+            } else {
+                opacity.clamp(0.0, 1.0)
+            };
+            self.dirty_tracker.request_full_redraw();
+        }
+    }
+
+    /// Layer blend mode name (`normal` or `under`).
+    #[wasm_bindgen(js_name = layerBlendMode)]
+    pub fn layer_blend_mode(&self, index: usize) -> String {
+        self.layers
+            .get(index)
+            .map(|l| l.blend.name().to_string())
+            .unwrap_or_default()
+    }
+
+    /// Set layer blend mode by name. Returns false for an unknown layer or mode.
+    #[wasm_bindgen(js_name = setLayerBlendMode)]
+    pub fn set_layer_blend_mode(&mut self, index: usize, mode: String) -> bool {
+        let (Some(layer), Some(blend)) = (self.layers.get_mut(index), BlendMode::from_name(&mode))
+        else {
+            return false;
+        };
+        layer.blend = blend;
+        self.dirty_tracker.request_full_redraw();
+        true
+    }
+
+    /// The layer's see-through character.
+    #[wasm_bindgen(js_name = layerTransparentChar)]
+    pub fn layer_transparent_char(&self, index: usize) -> String {
+        self.layers
+            .get(index)
+            .map(|l| l.transparent.to_string())
+            .unwrap_or_default()
+    }
+
+    /// Set the layer's see-through character. Any other character, including space,
+    /// then covers the layers below. Must be a single non-whitespace character, or a
+    /// space to restore the default.
+    #[wasm_bindgen(js_name = setLayerTransparentChar)]
+    pub fn set_layer_transparent_char(&mut self, index: usize, ch: String) -> bool {
+        let mut chars = ch.chars();
+        let (Some(c), None) = (chars.next(), chars.next()) else {
+            return false;
+        };
+        if c.is_whitespace() && c != ' ' {
+            return false;
+        }
+        let Some(layer) = self.layers.get_mut(index) else {
+            return false;
+        };
+        layer.transparent = c;
+        self.dirty_tracker.request_full_redraw();
+        true
+    }
+
+    /// Whether a layer is a guide.
+    #[wasm_bindgen(js_name = layerGuide)]
+    pub fn layer_guide(&self, index: usize) -> bool {
+        self.layers.get(index).is_some_and(|l| l.guide)
+    }
+
+    /// Mark a layer as a guide (reference sketch, margin notes): it is drawn dimmed
+    /// and left out of every export and copy.
+    #[wasm_bindgen(js_name = setLayerGuide)]
+    pub fn set_layer_guide(&mut self, index: usize, guide: bool) {
+        if let Some(layer) = self.layers.get_mut(index) {
+            layer.guide = guide;
+            self.dirty_tracker.request_full_redraw();
+        }
+    }
+}
+
+impl AsciiEditor {
+    /// Offset of the active layer.
+    pub(crate) fn active_offset(&self) -> (i32, i32) {
+        self.layers
+            .get(self.active_layer)
+            .map(|l| l.offset)
+            .unwrap_or((0, 0))
+    }
+
+    /// Live content of layer `index` (the active layer is edited in `state.grid`).
+    pub(crate) fn layer_grid(&self, index: usize) -> &Grid {
+        if index == self.active_layer {
+            &self.state.grid
+        } else {
+            &self.layers[index].grid
+        }
+    }
+
+    /// Visible layers, bottom to top, as the compositor sees them.
+    pub(crate) fn layer_views(&self) -> Vec<LayerView<'_>> {
+        (0..self.layers.len())
+            .filter(|&i| self.layer_shown(i))
+            .map(|i| layer_view(&self.layers[i], self.layer_grid(i)))
+            .collect()
+    }
+
+    /// The composite of `views` (from [`layer_views`](Self::layer_views)), updated
+    /// where layer cells or properties changed since the last call.
+    pub(crate) fn synced_composite(&self, views: &[LayerView]) -> Ref<'_, CompositeCache> {
+        let (width, height) = (self.state.grid.width(), self.state.grid.height());
+        self.composite_cache.borrow_mut().sync(views, width, height);
+        self.composite_cache.borrow()
+    }
+
+    /// Grid cell of the active layer under a screen position.
+    pub(crate) fn screen_to_layer(&self, screen_x: f64, screen_y: f64) -> (i32, i32) {
+        let (x, y) = self.renderer.screen_to_grid(screen_x, screen_y);
+        let (ox, oy) = self.active_offset();
+        (x - ox, y - oy)
+    }
+
+    /// Undo or redo (`step`) on the active layer, so commands restore its offset
+    /// along with its cells. Undoing the layer's creation removes it and activates
+    /// the layer below.
+    pub(crate) fn step_history(
+        &mut self,
+        step: fn(&mut History, &mut LayerTarget) -> bool,
+    ) -> bool {
+        let Some(layer) = self.layers.get_mut(self.active_layer) else {
+            return false;
+        };
+        let mut target = LayerTarget::new(&mut self.state.grid, layer.offset);
+        let result = step(&mut self.history, &mut target);
+        layer.offset = target.offset;
+        if !target.exists {
+            let index = self.active_layer;
+            self.delete_layer_impl(index);
+            self.set_active_layer_impl(index.saturating_sub(1));
+        }
+        result
+    }
+
+    pub(crate) fn shift_active_layer(&mut self, dx: i32, dy: i32) {
+        if let Some(layer) = self.layers.get_mut(self.active_layer) {
+            layer.offset = shift_offset(layer.offset, dx, dy);
+            self.dirty_tracker.request_full_redraw();
+        }
+    }
+
+    pub(crate) fn move_layer_impl(&mut self, from_index: usize, to_index: usize) {
+        if from_index >= self.layers.len()
+            || to_index >= self.layers.len()
+            || from_index == to_index
+        {
+            return;
+        }
+        self.sync_active_layer();
+
+        // Temporarily swap active history back to active layer for moving
+        let mut temp_history = std::mem::take(&mut self.history);
+        std::mem::swap(
+            &mut temp_history,
+            &mut self.layers[self.active_layer].history,
+        );
+
+        let layer = self.layers.remove(from_index);
+        self.layers.insert(to_index, layer);
+
+        if self.active_layer == from_index {
+            self.active_layer = to_index;
+        } else if from_index < to_index
+            && self.active_layer > from_index
+            && self.active_layer <= to_index
+        {
+            self.active_layer -= 1;
+        } else if from_index > to_index
+            && self.active_layer >= to_index
+            && self.active_layer < from_index
+        {
+            self.active_layer += 1;
+        }
+
+        // Swap history back from the new active layer
+        let mut temp_history = std::mem::take(&mut self.layers[self.active_layer].history);
+        std::mem::swap(&mut temp_history, &mut self.history);
+
+        self.state.grid = self.layers[self.active_layer].grid.clone();
+        self.regroup_moved_layer(to_index);
+        self.dirty_tracker.request_full_redraw();
+    }
+
+    pub(crate) fn delete_layer_impl(&mut self, index: usize) -> bool {
+        if self.layers.len() <= 1 || index >= self.layers.len() {
+            return false;
+        }
+        self.sync_active_layer();
+
+        // Temporarily swap active history back to active layer before structural changes
+        let mut temp_history = std::mem::take(&mut self.history);
+        std::mem::swap(
+            &mut temp_history,
+            &mut self.layers[self.active_layer].history,
+        );
+
+        self.layers.remove(index);
+        self.prune_groups();
+
+        if self.active_layer == index {
+            if self.active_layer >= self.layers.len() {
+                self.active_layer = self.layers.len() - 1;
+            }
+        } else if self.active_layer > index {
+            self.active_layer -= 1;
+        }
+
+        // Restore active grid and history
+        self.state.grid = self.layers[self.active_layer].grid.clone();
+        self.history = std::mem::take(&mut self.layers[self.active_layer].history);
+
+        self.current_selection = None;
+        self.preview_ops.clear();
+        self.dirty_tracker.request_full_redraw();
+        true
+    }
+
+    pub(crate) fn merge_down_impl(&mut self, index: usize) -> bool {
+        if index == 0 || index >= self.layers.len() {
+            return false;
+        }
+        self.sync_active_layer();
+
+        // Temporarily swap active history back to active layer before structural changes
+        let mut temp_history = std::mem::take(&mut self.history);
+        std::mem::swap(
+            &mut temp_history,
+            &mut self.layers[self.active_layer].history,
+        );
+
+        // Clone upper layer's grid
+        let upper_grid = self.layers[index].grid.clone();
+        let (ux, uy) = self.layers[index].offset;
+
+        // Merge into lower layer, converting to its coordinates
+        {
+            let lower_layer = &mut self.layers[index - 1];
+            let (lx, ly) = lower_layer.offset;
+            for (x, y, cell) in upper_grid.iter_with_coords() {
+                if cell.is_visible() {
+                    lower_layer.grid.set(x + ux - lx, y + uy - ly, *cell);
+                }
+            }
+        }
+
+        self.layers.remove(index);
+        self.prune_groups();
+
+        if self.active_layer == index {
+            self.active_layer = index - 1;
+        } else if self.active_layer > index {
+            self.active_layer -= 1;
+        }
+
+        self.state.grid = self.layers[self.active_layer].grid.clone();
+        self.history = std::mem::take(&mut self.layers[self.active_layer].history);
+
+        self.current_selection = None;
+        self.preview_ops.clear();
+        self.dirty_tracker.request_full_redraw();
+        true
+    }
+}
+
+/// Compositor view of `layer` with `grid` as its content.
+pub(crate) fn layer_view<'a>(layer: &LayerData, grid: &'a Grid) -> LayerView<'a> {
+    LayerView {
+        grid,
+        offset: layer.offset,
+        blend: layer.blend,
+        transparent: layer.transparent,
+        opacity: layer.opacity,
+        guide: layer.guide,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::core::document::MAX_LAYER_OFFSET;
+    use crate::core::tools::DrawOp;
+    use crate::wasm::bindings::AsciiEditor;
+
+    #[test]
+    fn test_offset_moves_layer_in_export() {
+        let mut canvas = AsciiEditor::new(6, 3);
+        canvas.commit_ops(&[DrawOp::new(0, 0, 'A')]);
+        assert!(canvas.nudge_layer(2, 1));
+        assert_eq!(canvas.layer_offset(0), vec![2, 1]);
+        let composite = canvas.composite_visible_grid();
+        assert_eq!(composite.get(2, 1).unwrap().ch, 'A');
+        assert!(!composite.get(0, 0).unwrap().is_visible());
+
+        // Drawing goes through the offset: screen cell (2, 1) is layer cell (0, 0).
+        assert_eq!(canvas.screen_to_layer(2.0 * 8.0 + 1.0, 20.0 + 1.0), (0, 0));
+    }
+
+    #[test]
+    fn test_nudge_and_drag_are_undoable() {
+        let mut canvas = AsciiEditor::new(6, 3);
+        canvas.nudge_layer(1, 0);
+        assert!(canvas.begin_layer_drag());
+        canvas.drag_layer(1, 1);
+        canvas.drag_layer(3, 1);
+        assert!(canvas.end_layer_drag());
+        assert_eq!(canvas.layer_offset(0), vec![4, 1]);
+        assert_eq!(canvas.history.undo_count(), 2);
+
+        assert!(canvas.undo());
+        assert_eq!(canvas.layer_offset(0), vec![1, 0]);
+        assert!(canvas.undo());
+        assert_eq!(canvas.layer_offset(0), vec![0, 0]);
+        assert!(canvas.redo());
+        assert_eq!(canvas.layer_offset(0), vec![1, 0]);
+    }
+
+    #[test]
+    fn test_layer_moves_stop_at_the_offset_limit() {
+        let (max_x, max_y) = MAX_LAYER_OFFSET;
+        let mut canvas = AsciiEditor::new(6, 3);
+        assert!(!canvas.nudge_layer(i32::MIN, 0));
+        assert!(canvas.nudge_layer(max_x, 0));
+        assert!(canvas.nudge_layer(max_x, 0));
+        assert_eq!(canvas.layer_offset(0), vec![max_x, 0]);
+        assert_eq!(canvas.history.undo_count(), 1);
+
+        assert!(canvas.begin_layer_drag());
+        canvas.drag_layer(i32::MIN, i32::MAX);
+        assert!(canvas.end_layer_drag());
+        assert_eq!(canvas.layer_offset(0), vec![-max_x, max_y]);
+        assert!(canvas.undo());
+        assert!(canvas.undo());
+        assert_eq!(canvas.layer_offset(0), vec![0, 0]);
+    }
+
+    #[test]
+    fn test_locked_layer_cannot_move() {
+        let mut canvas = AsciiEditor::new(6, 3);
+        canvas.set_layer_locked(0, true);
+        assert!(!canvas.nudge_layer(1, 1));
+        assert!(!canvas.begin_layer_drag());
+        assert_eq!(canvas.layer_offset(0), vec![0, 0]);
+    }
+
+    #[test]
+    fn test_merge_down_respects_offsets() {
+        let mut canvas = AsciiEditor::new(6, 3);
+        canvas.add_layer_impl();
+        canvas.commit_ops(&[DrawOp::new(0, 0, 'B')]);
+        canvas.nudge_layer(3, 2);
+        assert!(canvas.merge_down_impl(1));
+        assert_eq!(canvas.state.grid.get(3, 2).unwrap().ch, 'B');
+    }
+
+    #[test]
+    fn test_blend_and_transparency_settings() {
+        let mut canvas = AsciiEditor::new(4, 1);
+        canvas.commit_ops(&[DrawOp::new(0, 0, 'a'), DrawOp::new(1, 0, 'b')]);
+        canvas.add_layer_impl();
+        canvas.commit_ops(&[DrawOp::new(1, 0, 'X'), DrawOp::new(2, 0, 'Y')]);
+
+        assert!(canvas.set_layer_blend_mode(1, "under".to_string()));
+        assert!(!canvas.set_layer_blend_mode(1, "multiply".to_string()));
+        assert_eq!(canvas.export_ascii(), "abY");
+
+        assert!(canvas.set_layer_blend_mode(1, "normal".to_string()));
+        assert!(canvas.set_layer_transparent_char(1, "Y".to_string()));
+        assert!(!canvas.set_layer_transparent_char(1, "\t".to_string()));
+        // Spaces on the upper layer now blank out the lower one.
+        let row: String = canvas
+            .composite_visible_grid()
+            .cells()
+            .iter()
+            .map(|c| c.ch)
+            .collect();
+        assert_eq!(row, " X  ");
+
+        canvas.set_layer_opacity(1, 7.0);
+        assert_eq!(canvas.layer_opacity(1), 1.0);
+    }
+
+    #[test]
+    fn test_guide_layers_are_shown_but_not_exported() {
+        let mut canvas = AsciiEditor::new(4, 1);
+        canvas.commit_ops(&[DrawOp::new(0, 0, 'a')]);
+        canvas.add_layer_impl();
+        canvas.commit_ops(&[DrawOp::new(2, 0, 'G')]);
+        canvas.set_layer_guide(1, true);
+        assert!(canvas.layer_guide(1));
+
+        assert_eq!(canvas.export_ascii(), "a");
+        assert!(!canvas.export_svg().contains(">G<"));
+        canvas.set_selection_for_test(0, 0, 3, 0);
+        assert_eq!(canvas.export_for_copy(), "a   ");
+        assert!(canvas.copy_selection_impl());
+        let copied: Vec<_> = canvas.clipboard.cells.iter().map(|c| c.2.ch).collect();
+        assert_eq!(copied, ['a']);
+
+        // Still drawn on screen, in the theme's muted colour.
+        let views = canvas.layer_views();
+        assert_eq!(views.len(), 2);
+        assert!(views[1].guide);
+    }
+}
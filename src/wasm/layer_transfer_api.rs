@@ -0,0 +1,271 @@
+//! Layer transfer API - duplicate layers and send selected content to other layers.
+//!
+//! Content lands on its layer through a command pushed onto that layer's own
+//! history, so each transfer is undone from the layer it changed. A layer created
+//! here starts its history with an `AddLayerCommand`, so undoing it removes the
+//! layer. Nothing is created once the page holds `MAX_LAYERS` layers.
+
+use wasm_bindgen::prelude::*;
+
+use super::bindings::AsciiEditor;
+use super::helpers::content_ops;
+use crate::core::commands::{Command, DrawCommand};
+use crate::core::document::MAX_LAYERS;
+use crate::core::tools::DrawOp;
+
+#[wasm_bindgen]
+impl AsciiEditor {
+    /// Copy a layer (content and properties, except its lock) to a new layer just
+    /// above it and switch to the copy. Returns the copy's index, or `undefined` when
+    /// the page is full or the layer's group is locked.
+    #[wasm_bindgen(js_name = duplicateLayer)]
+    pub fn duplicate_layer(&mut self, index: usize) -> Option<usize> {
+        self.duplicate_layer_impl(index)
+    }
+
+    /// Move the selected cells of the active layer to layer `target`, keeping their
+    /// place on the canvas. Both layers must be unlocked, and every cell must land
+    /// inside the target layer.
+    #[wasm_bindgen(js_name = moveSelectionToLayer)]
+    pub fn move_selection_to_layer(&mut self, target: usize) -> bool {
+        self.move_selection_to_layer_impl(target)
+    }
+
+    /// Copy the selected cells of the active layer to a new layer on top and switch
+    /// to it. Returns the new layer's index, or `undefined` when the page is full.
+    #[wasm_bindgen(js_name = copySelectionToNewLayer)]
+    pub fn copy_selection_to_new_layer(&mut self) -> Option<usize> {
+        self.copy_selection_to_new_layer_impl()
+    }
+}
+
+impl AsciiEditor {
+    pub(crate) fn duplicate_layer_impl(&mut self, index: usize) -> Option<usize> {
+        if index >= self.layers.len() || self.layers.len() >= MAX_LAYERS {
+            return None;
+        }
+        // The copy joins the layer's group, so a locked group would lock it too.
+        let group = self.layers[index].group;
+        if group
+            .and_then(|id| self.group(id))
+            .is_some_and(|g| g.locked)
+        {
+            return None;
+        }
+        self.sync_active_layer();
+        let source = self.layers[index].clone();
+        let ops = content_ops(source.grid.iter_with_coords());
+
+        let copy = self.add_layer_impl();
+        let layer = &mut self.layers[copy];
+        layer.name = format!("{} copy", source.name);
+        layer.visible = source.visible;
+        layer.guide = source.guide;
+        layer.group = source.group;
+        layer.offset = source.offset;
+        layer.opacity = source.opacity;
+        layer.blend = source.blend;
+        layer.transparent = source.transparent;
+        self.push_new_layer_ops(ops, "Duplicate layer");
+
+        if copy != index + 1 {
+            self.move_layer_impl(copy, index + 1);
+        }
+        Some(index + 1)
+    }
+
+    pub(crate) fn move_selection_to_layer_impl(&mut self, target: usize) -> bool {
+        if target >= self.layers.len()
+            || target == self.active_layer
+            || self.is_active_layer_locked()
+            || self.layer_effectively_locked(target)
+        {
+            return false;
+        }
+        let ops = self.selected_ops();
+        if ops.is_empty() {
+            return false;
+        }
+
+        // Selected cells keep their canvas position on the target layer.
+        let (ax, ay) = self.active_offset();
+        let (tx, ty) = self.layers[target].offset;
+        let moved = ops
+            .iter()
+            .map(|op| DrawOp {
+                x: op.x + ax - tx,
+                y: op.y + ay - ty,
+                cell: op.cell,
+            })
+            .collect::<Vec<_>>();
+        // Cells past the target layer's edge would be cleared here and lost there
+        let grid = &self.layers[target].grid;
+        if moved.iter().any(|op| !grid.in_bounds(op.x, op.y)) {
+            return false;
+        }
+        let cleared = ops.iter().map(|op| DrawOp::new(op.x, op.y, ' ')).collect();
+        self.push_active_ops(cleared, "Move to layer");
+
+        let layer = &mut self.layers[target];
+        let mut cmd = DrawCommand::with_description(moved, "Move from layer");
+        cmd.apply(&mut layer.grid);
+        layer.history.push(Box::new(cmd));
+
+        self.current_selection = None;
+        self.dirty_tracker.request_full_redraw();
+        true
+    }
+
+    pub(crate) fn copy_selection_to_new_layer_impl(&mut self) -> Option<usize> {
+        let ops = self.selected_ops();
+        if ops.is_empty() || self.layers.len() >= MAX_LAYERS {
+            return None;
+        }
+        let offset = self.active_offset();
+        let index = self.add_layer_impl();
+        self.layers[index].offset = offset;
+        self.push_new_layer_ops(ops, "Copy to new layer");
+        Some(index)
+    }
+
+    /// Non-blank cells of the active layer inside the selection.
+    pub(crate) fn selected_ops(&self) -> Vec<DrawOp> {
+        let Some(sel) = self.current_selection.as_ref() else {
+            return Vec::new();
+        };
+        content_ops(
+            self.state
+                .grid
+                .iter_with_coords()
+                .filter(|(x, y, _)| sel.contains(*x, *y)),
+        )
+    }
+
+    /// Apply `ops` to the active layer as one undoable step.
+    fn push_active_ops(&mut self, ops: Vec<DrawOp>, description: &str) {
+        if ops.is_empty() {
+            return;
+        }
+        let mut cmd = DrawCommand::with_description(ops, description);
+        cmd.apply(&mut self.state.grid);
+        self.history.push(Box::new(cmd));
+        self.dirty_tracker.request_full_redraw();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::core::document::MAX_LAYERS;
+    use crate::core::tools::DrawOp;
+    use crate::wasm::bindings::AsciiEditor;
+
+    fn row(canvas: &AsciiEditor, index: usize) -> String {
+        let grid = canvas.layer_grid(index);
+        (0..grid.width() as i32)
+            .map(|x| grid.get(x, 0).unwrap().ch)
+            .collect()
+    }
+
+    #[test]
+    fn test_duplicate_layer_copies_content_and_properties() {
+        let mut canvas = AsciiEditor::new(4, 1);
+        canvas.commit_ops(&[DrawOp::new(1, 0, 'A')]);
+        canvas.add_layer_impl();
+        canvas.set_layer_opacity(0, 0.5);
+        canvas.set_layer_locked(0, true);
+
+        assert_eq!(canvas.duplicate_layer_impl(0), Some(1));
+        assert_eq!(canvas.active_layer, 1);
+        assert_eq!(canvas.layer_count(), 3);
+        assert_eq!(canvas.layer_name(1), "Layer 1 copy");
+        assert_eq!(canvas.layer_opacity(1), 0.5);
+        assert!(!canvas.layer_locked(1));
+        assert_eq!(row(&canvas, 1), " A  ");
+
+        // One undo removes the copy and goes back to the original.
+        assert!(canvas.undo());
+        assert_eq!(canvas.layer_count(), 2);
+        assert_eq!(canvas.active_layer, 0);
+        assert_eq!(canvas.layer_name(1), "Layer 2");
+        assert_eq!(row(&canvas, 0), " A  ");
+    }
+
+    #[test]
+    fn test_duplicate_layer_refuses_locked_groups_and_full_pages() {
+        let mut canvas = AsciiEditor::new(4, 1);
+        canvas.add_layer_impl();
+        let id = canvas.group_layers_impl(0, 1, None).unwrap();
+        canvas.set_group_locked(id, true);
+        assert_eq!(canvas.duplicate_layer_impl(0), None);
+        canvas.set_group_locked(id, false);
+        assert_eq!(canvas.duplicate_layer_impl(0), Some(1));
+        assert_eq!(canvas.layer_group(1), Some(id));
+
+        while canvas.layer_count() < MAX_LAYERS {
+            canvas.add_layer_impl();
+        }
+        assert_eq!(canvas.duplicate_layer_impl(0), None);
+        canvas.set_selection_for_test(0, 0, 3, 0);
+        canvas.commit_ops(&[DrawOp::new(0, 0, 'x')]);
+        assert_eq!(canvas.copy_selection_to_new_layer_impl(), None);
+        assert_eq!(canvas.layer_count(), MAX_LAYERS);
+    }
+
+    #[test]
+    fn test_move_selection_to_layer_is_undoable_per_layer() {
+        let mut canvas = AsciiEditor::new(4, 1);
+        canvas.add_layer_impl();
+        canvas.shift_active_layer(1, 0);
+        canvas.commit_ops(&[DrawOp::new(0, 0, 'M'), DrawOp::new(2, 0, 'K')]);
+        canvas.set_selection_for_test(0, 0, 1, 0);
+
+        canvas.set_layer_locked(0, true);
+        assert!(!canvas.move_selection_to_layer_impl(0));
+        canvas.set_layer_locked(0, false);
+
+        assert!(canvas.move_selection_to_layer_impl(0));
+        assert_eq!(row(&canvas, 1), "  K ");
+        assert_eq!(row(&canvas, 0), " M  ");
+
+        assert!(canvas.undo());
+        assert_eq!(row(&canvas, 1), "M K ");
+        canvas.set_active_layer_impl(0);
+        assert!(canvas.undo());
+        assert_eq!(row(&canvas, 0), "    ");
+    }
+
+    #[test]
+    fn test_move_selection_refuses_cells_past_the_target_edge() {
+        let mut canvas = AsciiEditor::new(4, 1);
+        canvas.add_layer_impl();
+        canvas.shift_active_layer(-2, 0);
+        canvas.commit_ops(&[DrawOp::new(0, 0, 'L'), DrawOp::new(3, 0, 'R')]);
+
+        // 'L' sits at canvas column -2, left of layer 0
+        canvas.set_selection_for_test(0, 0, 3, 0);
+        assert!(!canvas.move_selection_to_layer_impl(0));
+        assert_eq!(row(&canvas, 1), "L  R");
+        assert_eq!(row(&canvas, 0), "    ");
+
+        canvas.set_selection_for_test(3, 0, 3, 0);
+        assert!(canvas.move_selection_to_layer_impl(0));
+        assert_eq!(row(&canvas, 1), "L   ");
+        assert_eq!(row(&canvas, 0), " R  ");
+    }
+
+    #[test]
+    fn test_copy_selection_to_new_layer() {
+        let mut canvas = AsciiEditor::new(4, 1);
+        canvas.commit_ops(&[DrawOp::new(0, 0, 'a'), DrawOp::new(3, 0, 'b')]);
+        assert_eq!(canvas.copy_selection_to_new_layer_impl(), None);
+
+        canvas.set_selection_for_test(2, 0, 3, 0);
+        canvas.set_layer_locked(0, true);
+        assert_eq!(canvas.copy_selection_to_new_layer_impl(), Some(1));
+        assert_eq!(row(&canvas, 1), "   b");
+        assert_eq!(row(&canvas, 0), "a  b");
+        assert!(canvas.undo());
+        assert_eq!(canvas.layer_count(), 1);
+        assert_eq!(canvas.active_layer, 0);
+    }
+}
@@ -0,0 +1,145 @@
+//! Minimap API - a small overview of the composite canvas with the viewport on it.
+//!
+//! The minimap has its own RGBA buffer, read by JS the same way as the pixel buffer
+//! (pointer and length into wasm memory). Clicking it pans the view there.
+
+use wasm_bindgen::prelude::*;
+
+use super::bindings::AsciiEditor;
+use crate::core::CellStyle;
+use crate::render::{Minimap, PixelPalette};
+
+#[wasm_bindgen]
+impl AsciiEditor {
+    /// Render the overview into the minimap buffer, at most `max_width`×`max_height`
+    /// pixels, with the current viewport outlined.
+    #[wasm_bindgen(js_name = renderMinimap)]
+    pub fn render_minimap(&mut self, max_width: usize, max_height: usize) {
+        let (cols, rows) = (self.state.grid.width(), self.state.grid.height());
+        self.minimap = Minimap::fit(cols, rows, max_width, max_height);
+
+        // The theme's palette, not `pixel_palette`: the eraser's error color is for
+        // its footprint, not the viewport outline
+        let palette = PixelPalette::from_theme(|name| self.theme.variable(name));
+        let (bg, fg) = (palette.background, palette.foreground);
+        let (guide, highlight) = (palette.guide, palette.highlight);
+        let accent = palette.overlay.hover;
+
+        let mut buffer = std::mem::take(&mut self.minimap_buffer);
+        let views = self.layer_views();
+        let composite = self.synced_composite(&views);
+        self.minimap.render(&mut buffer, bg, |x, y| {
+            let &(cell, i) = composite.stack(x, y).last()?;
+            Some(if views[i].guide {
+                guide
+            } else if cell.style.contains(CellStyle::HIGHLIGHT) {
+                highlight
+            } else if cell.is_visible() {
+                fg
+            } else {
+                return None;
+            })
+        });
+        drop(composite);
+        drop(views);
+        self.minimap
+            .draw_rect(&mut buffer, self.renderer.visible_grid_rect(), accent);
+        self.minimap_buffer = buffer;
+    }
+
+    /// Minimap width in pixels (after `renderMinimap`).
+    #[wasm_bindgen(getter = minimapWidth)]
+    pub fn minimap_width(&self) -> usize {
+        self.minimap.width
+    }
+
+    /// Minimap height in pixels (after `renderMinimap`).
+    #[wasm_bindgen(getter = minimapHeight)]
+    pub fn minimap_height(&self) -> usize {
+        self.minimap.height
+    }
+
+    /// Pointer to the minimap RGBA buffer.
+    #[wasm_bindgen(js_name = getMinimapPtr)]
+    pub fn get_minimap_ptr(&self) -> *const u8 {
+        self.minimap_buffer.as_ptr()
+    }
+
+    /// Length of the minimap buffer in bytes.
+    #[wasm_bindgen(js_name = getMinimapLen)]
+    pub fn get_minimap_len(&self) -> usize {
+        self.minimap_buffer.len()
+    }
+
+    /// The viewport on the minimap as `[x, y, width, height]` in minimap pixels
+    /// (may reach past the minimap when the view shows area outside the canvas).
+    #[wasm_bindgen(js_name = minimapViewport)]
+    pub fn minimap_viewport(&self) -> Vec<f64> {
+        let (x0, y0, x1, y1) = self.renderer.visible_grid_rect();
+        let (left, top) = self.minimap.cell_to_pixel(x0, y0);
+        let (right, bottom) = self.minimap.cell_to_pixel(x1, y1);
+        vec![left, top, right - left, bottom - top]
+    }
+
+    /// Center the view on the canvas point under minimap pixel (`x`, `y`).
+    #[wasm_bindgen(js_name = minimapClick)]
+    pub fn minimap_click(&mut self, x: f64, y: f64) {
+        if self.minimap.width == 0 || self.minimap.height == 0 {
+            return;
+        }
+        let (gx, gy) = self.minimap.pixel_to_cell(x, y);
+        let (pan_x, pan_y) = self.renderer.pan_centering(gx, gy);
+        self.pan_to(pan_x, pan_y);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::core::tools::{DrawOp, ToolId};
+    use crate::wasm::bindings::AsciiEditor;
+
+    #[test]
+    fn test_minimap_shows_content_and_viewport() {
+        let mut canvas = AsciiEditor::new(200, 100);
+        canvas.set_font_metrics(8.0, 20.0, 14.0);
+        canvas.set_canvas_size(400.0, 200.0);
+        let ops: Vec<_> = (0..10)
+            .flat_map(|x| (0..10).map(move |y| DrawOp::new(190 + x, 90 + y, '#')))
+            .collect();
+        canvas.commit_ops(&ops);
+        canvas.render_minimap(100, 100);
+        assert_eq!((canvas.minimap_width(), canvas.minimap_height()), (100, 50));
+        assert_eq!(canvas.get_minimap_len(), 100 * 50 * 4);
+
+        let px = |canvas: &AsciiEditor, x: usize, y: usize| {
+            canvas.minimap_buffer[(y * 100 + x) * 4..][..3].to_vec()
+        };
+        assert_eq!(px(&canvas, 98, 48), vec![0xd4, 0xd4, 0xd4], "filled corner");
+        assert_eq!(px(&canvas, 60, 30), vec![0x1e, 0x1e, 0x1e], "empty canvas");
+        // 8×20 cells on a 400×200 canvas: 50×10 cells in view, a quarter of the width.
+        assert_eq!(canvas.minimap_viewport(), vec![0.0, 0.0, 25.0, 5.0]);
+        assert_eq!(
+            px(&canvas, 0, 0),
+            vec![0x0d, 0x99, 0xff],
+            "viewport outline"
+        );
+
+        // The eraser's footprint color stays out of the minimap
+        canvas.set_tool_by_id_impl(ToolId::Eraser);
+        canvas.render_minimap(100, 100);
+        assert_eq!(px(&canvas, 0, 0), vec![0x0d, 0x99, 0xff]);
+    }
+
+    #[test]
+    fn test_minimap_click_centers_the_view() {
+        let mut canvas = AsciiEditor::new(200, 100);
+        canvas.set_canvas_size(400.0, 200.0);
+        canvas.render_minimap(100, 100);
+        canvas.minimap_click(50.0, 25.0);
+
+        let (x0, y0, x1, y1) = canvas.renderer.visible_grid_rect();
+        assert!(((x0 + x1) / 2.0 - 100.0).abs() < 1e-9);
+        assert!(((y0 + y1) / 2.0 - 50.0).abs() < 1e-9);
+        assert!(canvas.dirty_tracker.panned());
+    }
+}
@@ -2,12 +2,25 @@
 
 mod bindings;
 mod clipboard;
+mod document_api;
 mod event_handlers;
+mod font_api;
+mod group_api;
 mod helpers;
+mod interop_api;
+mod layer_api;
+mod layer_transfer_api;
+mod minimap_api;
+mod overlay_api;
+mod page_api;
+mod pixel_api;
 mod render_api;
 mod render_bridge;
 mod selection;
+mod shortcut_api;
+mod style_api;
 mod tool_manager;
+mod viewport_api;
 
 pub use bindings::AsciiEditor;
 pub use clipboard::copy_to_clipboard;
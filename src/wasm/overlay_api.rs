@@ -0,0 +1,156 @@
+//! Overlay API - text caret, marching ants and hover outline in the pixel buffer.
+//!
+//! The overlay is rebuilt from editor state whenever a frame time comes in or the
+//! buffer is rendered; only the cells that look different from the last frame are
+//! marked dirty.
+
+use wasm_bindgen::prelude::*;
+
+use super::bindings::AsciiEditor;
+use crate::core::tools::ToolId;
+use crate::render::{CursorOverlay, DirtyRect};
+
+#[wasm_bindgen]
+impl AsciiEditor {
+    /// Advance the caret blink and marching ants to `time_ms` (a
+    /// `requestAnimationFrame` timestamp). Marks only the changed cells dirty.
+    #[wasm_bindgen(js_name = setFrameTime)]
+    pub fn set_frame_time(&mut self, time_ms: f64) {
+        self.frame_time = time_ms;
+        self.sync_cursor_overlay();
+    }
+
+    /// Whether the overlay animates on its own (a caret or selection is shown), so
+    /// frames should keep coming.
+    #[wasm_bindgen(getter)]
+    pub fn animating(&self) -> bool {
+        self.cursor_overlay.is_animated()
+    }
+
+    /// The pointer left the canvas: drop the hover outline.
+    #[wasm_bindgen(js_name = onPointerLeave)]
+    pub fn on_pointer_leave(&mut self) {
+        self.pointer_over_canvas = false;
+        self.sync_cursor_overlay();
+    }
+}
+
+impl AsciiEditor {
+    /// Bring the overlay up to date and mark the cells it changed.
+    pub(crate) fn sync_cursor_overlay(&mut self) {
+        let previous = self.cursor_overlay;
+        let mut next = self.current_cursor_overlay();
+        next.caret_since_ms = if next.caret == previous.caret {
+            previous.caret_since_ms
+        } else {
+            self.frame_time
+        };
+        for rect in next.changed_cells(&previous) {
+            self.dirty_tracker
+                .mark_region_dirty(rect.x1, rect.y1, rect.x2, rect.y2);
+        }
+        self.cursor_overlay = next;
+    }
+
+    /// The overlay for the current editor state, in canvas cells.
+    fn current_cursor_overlay(&mut self) -> CursorOverlay {
+        let (ox, oy) = self.active_offset();
+        let caret = self
+            .text_cursor_position()
+            .map(|pos| (pos[0] + ox, pos[1] + oy));
+        let selection = self.current_selection.as_ref().map(|sel| {
+            let (min_x, min_y, max_x, max_y) = sel.bounds();
+            DirtyRect::from_points(min_x + ox, min_y + oy, max_x + ox, max_y + oy)
+        });
+        let hover = self
+            .last_cursor
+            .filter(|_| self.pointer_over_canvas && !self.is_panning)
+            .map(|(x, y)| {
+                let r = if self.tool_id == ToolId::Eraser {
+                    (self.eraser_size - 1).max(0)
+                } else {
+                    0
+                };
+                DirtyRect::from_points(x - r + ox, y - r + oy, x + r + ox, y + r + oy)
+            });
+        CursorOverlay {
+            caret,
+            selection,
+            hover,
+            time_ms: self.frame_time,
+            caret_since_ms: 0.0,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::core::tools::ToolId;
+    use crate::render::{ANTS_STEP_MS, CARET_BLINK_MS};
+    use crate::wasm::bindings::AsciiEditor;
+
+    const BG: [u8; 3] = [0x1e, 0x1e, 0x1e];
+
+    fn px(canvas: &AsciiEditor, x: usize, y: usize) -> [u8; 3] {
+        let i = (y * canvas.buffer_width() + x) * 4;
+        [
+            canvas.pixel_buffer[i],
+            canvas.pixel_buffer[i + 1],
+            canvas.pixel_buffer[i + 2],
+        ]
+    }
+
+    fn frame(canvas: &mut AsciiEditor, time_ms: f64) {
+        canvas.set_frame_time(time_ms);
+        canvas.render_to_pixel_buffer();
+        canvas.clear_dirty_state();
+    }
+
+    #[test]
+    fn test_caret_blinks_by_redrawing_its_cell() {
+        let mut canvas = AsciiEditor::new(20, 10);
+        canvas.set_tool_by_id_impl(ToolId::Text);
+        let ctx = canvas.create_tool_context();
+        canvas.active_tool.on_pointer_down(3, 2, &ctx);
+        frame(&mut canvas, 0.0);
+        assert!(canvas.animating());
+        assert_ne!(px(&canvas, 3 * 8, 2 * 20), BG);
+
+        let dirty_renders = canvas.dirty_render_count;
+        frame(&mut canvas, CARET_BLINK_MS);
+        assert_eq!(canvas.dirty_render_count, dirty_renders + 1);
+        assert_eq!(canvas.get_rendered_regions(), vec![24, 40, 8, 20]);
+        assert_eq!(px(&canvas, 3 * 8, 2 * 20), BG);
+
+        // Typing moves the caret, which restarts the blink visible.
+        canvas.type_key('x');
+        frame(&mut canvas, CARET_BLINK_MS + 1.0);
+        assert_ne!(px(&canvas, 4 * 8, 2 * 20), BG);
+    }
+
+    #[test]
+    fn test_ants_march_and_hover_follows_the_eraser() {
+        let mut canvas = AsciiEditor::new(20, 10);
+        canvas.set_selection_for_test(2, 2, 5, 4);
+        frame(&mut canvas, 0.0);
+        let before = canvas.pixel_buffer.clone();
+
+        frame(&mut canvas, ANTS_STEP_MS);
+        assert_eq!(canvas.get_rendered_regions(), vec![16, 40, 32, 60]);
+        assert_ne!(canvas.pixel_buffer, before);
+
+        canvas.current_selection = None;
+        canvas.set_tool_by_id_impl(ToolId::Eraser);
+        canvas.set_eraser_size(2);
+        canvas.last_cursor = Some((10, 5));
+        canvas.pointer_over_canvas = true;
+        frame(&mut canvas, ANTS_STEP_MS);
+        let error = [0xf2, 0x48, 0x22];
+        assert_eq!(px(&canvas, 9 * 8, 4 * 20 + 5), error);
+        assert_eq!(px(&canvas, 12 * 8 - 1, 6 * 20 + 5), error);
+
+        canvas.on_pointer_leave();
+        frame(&mut canvas, ANTS_STEP_MS);
+        assert_eq!(px(&canvas, 9 * 8, 4 * 20 + 5), BG);
+    }
+}
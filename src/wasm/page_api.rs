@@ -0,0 +1,315 @@
+//! Page API - multiple named pages (artboards), each with its own size and layers.
+
+use wasm_bindgen::prelude::*;
+
+use super::bindings::{AsciiEditor, LayerData, PageData};
+use crate::core::compositing::composite_grid;
+use crate::core::document::{MAX_CANVAS_HEIGHT, MAX_CANVAS_WIDTH, MAX_PAGES};
+use crate::core::Grid;
+use crate::wasm::group_api::group_visible;
+use crate::wasm::layer_api::layer_view;
+use crate::wasm::render_bridge::export_ascii;
+
+#[wasm_bindgen]
+impl AsciiEditor {
+    /// Number of pages.
+    #[wasm_bindgen(getter = pageCount)]
+    pub fn page_count(&self) -> usize {
+        self.pages.len()
+    }
+
+    /// Active page index.
+    #[wasm_bindgen(getter = activePage)]
+    pub fn active_page_index(&self) -> usize {
+        self.active_page
+    }
+
+    /// Page name by index.
+    #[wasm_bindgen(js_name = pageName)]
+    pub fn page_name(&self, index: usize) -> String {
+        self.pages
+            .get(index)
+            .map(|p| p.name.clone())
+            .unwrap_or_default()
+    }
+
+    /// Page size as `[width, height]` in cells (empty for an unknown index).
+    #[wasm_bindgen(js_name = pageSize)]
+    pub fn page_size(&self, index: usize) -> Vec<usize> {
+        self.page_dimensions(index)
+            .map(|(w, h)| vec![w, h])
+            .unwrap_or_default()
+    }
+
+    /// Add a new page with one empty layer and switch to it.
+    /// A zero width or height uses the current page's size.
+    /// Returns the new page index, or `undefined` when the page limit is reached.
+    #[wasm_bindgen(js_name = addPage)]
+    pub fn add_page(&mut self, name: Option<String>, width: usize, height: usize) -> Option<usize> {
+        self.add_page_impl(name, width, height)
+    }
+
+    /// Rename a page.
+    #[wasm_bindgen(js_name = renamePage)]
+    pub fn rename_page(&mut self, index: usize, name: String) {
+        if let Some(page) = self.pages.get_mut(index) {
+            page.name = name;
+        }
+    }
+
+    /// Move a page to a new position.
+    #[wasm_bindgen(js_name = movePage)]
+    pub fn move_page(&mut self, from_index: usize, to_index: usize) {
+        self.move_page_impl(from_index, to_index);
+    }
+
+    /// Delete a page (the last remaining page cannot be deleted).
+    #[wasm_bindgen(js_name = deletePage)]
+    pub fn delete_page(&mut self, index: usize) -> bool {
+        self.delete_page_impl(index)
+    }
+
+    /// Switch the active page. Each page keeps its own layers, active layer and undo history.
+    #[wasm_bindgen(js_name = setActivePage)]
+    pub fn set_active_page(&mut self, index: usize) -> bool {
+        self.set_active_page_impl(index)
+    }
+
+    /// Export one page's visible layers as plain ASCII.
+    #[wasm_bindgen(js_name = exportPageAscii)]
+    pub fn export_page_ascii(&self, index: usize) -> Option<String> {
+        self.composite_page_grid(index)
+            .map(|grid| export_ascii(&grid))
+    }
+
+    /// Export every page as plain ASCII, each preceded by a `=== Name ===` heading.
+    #[wasm_bindgen(js_name = exportAllPagesAscii)]
+    pub fn export_all_pages_ascii(&self) -> String {
+        let mut out = String::new();
+        for (i, page) in self.pages.iter().enumerate() {
+            if i > 0 {
+                out.push('\n');
+            }
+            out.push_str(&format!("=== {} ===\n", page.name));
+            if let Some(text) = self.export_page_ascii(i) {
+                out.push_str(&text);
+                if !text.ends_with('\n') {
+                    out.push('\n');
+                }
+            }
+        }
+        out
+    }
+}
+
+impl AsciiEditor {
+    pub(crate) fn add_page_impl(
+        &mut self,
+        name: Option<String>,
+        width: usize,
+        height: usize,
+    ) -> Option<usize> {
+        if self.pages.len() >= MAX_PAGES {
+            return None;
+        }
+        let (width, height) = if width == 0 || height == 0 {
+            (self.state.grid.width(), self.state.grid.height())
+        } else {
+            (width.min(MAX_CANVAS_WIDTH), height.min(MAX_CANVAS_HEIGHT))
+        };
+        let name = name
+            .filter(|n| !n.is_empty())
+            .unwrap_or_else(|| format!("Page {}", self.pages.len() + 1));
+        self.pages.push(PageData {
+            name,
+            width,
+            height,
+            layers: vec![LayerData::new("Layer 1", Grid::new(width, height))],
+            active_layer: 0,
+            groups: Vec::new(),
+        });
+        let index = self.pages.len() - 1;
+        self.set_active_page_impl(index);
+        Some(index)
+    }
+
+    pub(crate) fn set_active_page_impl(&mut self, index: usize) -> bool {
+        if index >= self.pages.len() || index == self.active_page {
+            return index < self.pages.len();
+        }
+        self.stash_active_page();
+        self.restore_page(index);
+        true
+    }
+
+    pub(crate) fn move_page_impl(&mut self, from_index: usize, to_index: usize) {
+        let len = self.pages.len();
+        if from_index >= len || to_index >= len || from_index == to_index {
+            return;
+        }
+        // The active page's content lives in the editor, so only its index moves.
+        let page = self.pages.remove(from_index);
+        self.pages.insert(to_index, page);
+
+        if self.active_page == from_index {
+            self.active_page = to_index;
+        } else if from_index < to_index
+            && self.active_page > from_index
+            && self.active_page <= to_index
+        {
+            self.active_page -= 1;
+        } else if from_index > to_index
+            && self.active_page >= to_index
+            && self.active_page < from_index
+        {
+            self.active_page += 1;
+        }
+    }
+
+    pub(crate) fn delete_page_impl(&mut self, index: usize) -> bool {
+        if self.pages.len() <= 1 || index >= self.pages.len() {
+            return false;
+        }
+        self.pages.remove(index);
+        if index == self.active_page {
+            let next = index.min(self.pages.len() - 1);
+            self.restore_page(next);
+        } else if index < self.active_page {
+            self.active_page -= 1;
+        }
+        true
+    }
+
+    /// Width and height of a page in cells.
+    pub(crate) fn page_dimensions(&self, index: usize) -> Option<(usize, usize)> {
+        if index == self.active_page {
+            return Some((self.state.grid.width(), self.state.grid.height()));
+        }
+        self.pages.get(index).map(|p| (p.width, p.height))
+    }
+
+    /// Composite of a page's visible layers.
+    pub(crate) fn composite_page_grid(&self, index: usize) -> Option<Grid> {
+        if index == self.active_page {
+            return Some(self.composite_visible_grid());
+        }
+        let page = self.pages.get(index)?;
+        let views: Vec<_> = page
+            .layers
+            .iter()
+            .filter(|l| l.visible && !l.guide && group_visible(&page.groups, l.group))
+            .map(|l| layer_view(l, &l.grid))
+            .collect();
+        Some(composite_grid(&views, page.width, page.height))
+    }
+
+    /// Move the live layers, active layer and undo history back into the active page.
+    pub(crate) fn stash_active_page(&mut self) {
+        self.sync_active_layer();
+        if let Some(layer) = self.layers.get_mut(self.active_layer) {
+            layer.history = std::mem::take(&mut self.history);
+        }
+        let width = self.state.grid.width();
+        let height = self.state.grid.height();
+        let page = &mut self.pages[self.active_page];
+        page.layers = std::mem::take(&mut self.layers);
+        page.active_layer = self.active_layer;
+        page.groups = std::mem::take(&mut self.groups);
+        page.width = width;
+        page.height = height;
+    }
+
+    /// Load page `index` into the editor. The current page must already be stashed
+    /// (or removed).
+    pub(crate) fn restore_page(&mut self, index: usize) {
+        let page = &mut self.pages[index];
+        self.layers = std::mem::take(&mut page.layers);
+        self.groups = std::mem::take(&mut page.groups);
+        self.active_layer = page.active_layer.min(self.layers.len().saturating_sub(1));
+        self.active_page = index;
+
+        let layer = &mut self.layers[self.active_layer];
+        self.state.grid = layer.grid.clone();
+        self.history = std::mem::take(&mut layer.history);
+
+        self.update_render_scale();
+        self.reset_pixel_buffer();
+        self.current_selection = None;
+        self.preview_ops.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::wasm::bindings::AsciiEditor;
+
+    #[test]
+    fn test_pages_keep_their_own_size_layers_and_history() {
+        let mut canvas = AsciiEditor::new(10, 5);
+        canvas.state.grid.set_char(0, 0, 'A');
+        canvas.add_layer_impl();
+
+        assert_eq!(
+            canvas.add_page_impl(Some("Containers".into()), 20, 8),
+            Some(1)
+        );
+        assert_eq!(canvas.width(), 20);
+        assert_eq!(canvas.layer_count(), 1);
+        assert_eq!(canvas.pixel_buffer.len(), 20 * 8 * 8 * 20 * 4);
+        canvas.state.grid.set_char(1, 1, 'B');
+
+        assert!(canvas.set_active_page_impl(0));
+        assert_eq!(canvas.width(), 10);
+        assert_eq!(canvas.layer_count(), 2);
+        assert_eq!(canvas.active_layer, 1);
+        assert_eq!(canvas.export_page_ascii(0).unwrap().trim(), "A");
+        assert_eq!(canvas.export_page_ascii(1).unwrap().trim(), "B");
+        assert_eq!(canvas.page_size(1), vec![20, 8]);
+    }
+
+    #[test]
+    fn test_undo_history_stays_with_its_page() {
+        let mut canvas = AsciiEditor::new(10, 5);
+        canvas.commit_ops(&[crate::core::tools::DrawOp::new(0, 0, 'X')]);
+        canvas.add_page_impl(None, 0, 0);
+        assert!(!canvas.history.can_undo());
+
+        canvas.set_active_page_impl(0);
+        assert!(canvas.history.can_undo());
+        assert_eq!(canvas.page_name(1), "Page 2");
+    }
+
+    #[test]
+    fn test_rename_move_and_delete_pages() {
+        let mut canvas = AsciiEditor::new(10, 5);
+        canvas.add_page_impl(Some("B".into()), 0, 0);
+        canvas.add_page_impl(Some("C".into()), 0, 0);
+        canvas.rename_page(0, "A".into());
+        assert_eq!(canvas.active_page, 2);
+
+        canvas.move_page_impl(2, 0);
+        assert_eq!(canvas.active_page, 0);
+        assert_eq!(canvas.page_name(0), "C");
+        assert_eq!(canvas.page_name(1), "A");
+
+        assert!(canvas.delete_page_impl(0));
+        assert_eq!(canvas.page_count(), 2);
+        assert_eq!(canvas.active_page, 0);
+        assert_eq!(canvas.page_name(0), "A");
+        assert_eq!(canvas.layer_count(), 1);
+        assert!(canvas.delete_page_impl(1));
+        assert!(!canvas.delete_page_impl(0));
+    }
+
+    #[test]
+    fn test_export_all_pages() {
+        let mut canvas = AsciiEditor::new(4, 2);
+        canvas.rename_page(0, "Context".into());
+        canvas.state.grid.set_char(0, 0, 'C');
+        canvas.add_page_impl(Some("Container".into()), 4, 2);
+        canvas.state.grid.set_char(0, 0, 'K');
+        let text = canvas.export_all_pages_ascii();
+        assert!(text.starts_with("=== Context ===\nC"));
+        assert!(text.contains("\n=== Container ===\nK"));
+    }
+}
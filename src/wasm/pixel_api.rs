@@ -0,0 +1,341 @@
+//! Pixel buffer API - software rendering of the canvas into an RGBA buffer.
+
+use wasm_bindgen::prelude::*;
+
+use super::bindings::AsciiEditor;
+use crate::core::tools::ToolId;
+use crate::render::{
+    parse_hex_color, DirtyRect, PixelLayout, PixelPalette, PixelScene, PreviewMode,
+};
+
+#[wasm_bindgen]
+impl AsciiEditor {
+    /// Whether the pixel buffer shows a line along every cell edge.
+    #[wasm_bindgen(getter = gridLines)]
+    pub fn grid_lines(&self) -> bool {
+        self.grid_overlay.lines
+    }
+
+    /// Show or hide cell grid lines (also used by `getRenderCommands`).
+    #[wasm_bindgen(setter = gridLines)]
+    pub fn set_grid_lines(&mut self, show: bool) {
+        self.grid_overlay.lines = show;
+        self.renderer.set_show_grid(show);
+        self.dirty_tracker.request_full_redraw();
+    }
+
+    /// Interval, in cells, of the stronger major grid lines (0 when off).
+    #[wasm_bindgen(getter = majorGridEvery)]
+    pub fn major_grid_every(&self) -> usize {
+        self.grid_overlay.major_every
+    }
+
+    /// Draw every Nth grid line at full strength; 0 turns major lines off.
+    #[wasm_bindgen(setter = majorGridEvery)]
+    pub fn set_major_grid_every(&mut self, every: usize) {
+        self.grid_overlay.major_every = every;
+        self.dirty_tracker.request_full_redraw();
+    }
+
+    /// Whether row and column rulers are drawn along the top and left edges of the view.
+    #[wasm_bindgen(getter)]
+    pub fn rulers(&self) -> bool {
+        self.grid_overlay.rulers
+    }
+
+    /// Show or hide row and column rulers with cell coordinates.
+    #[wasm_bindgen(setter)]
+    pub fn set_rulers(&mut self, show: bool) {
+        self.grid_overlay.rulers = show;
+        self.dirty_tracker.request_full_redraw();
+    }
+
+    /// How tool previews are drawn: `tinted`, `dashed-outline`, `ghost` or `diff`.
+    #[wasm_bindgen(getter = previewMode)]
+    pub fn preview_mode(&self) -> String {
+        let mode = PreviewMode::from_name(&self.theme.preview.mode).unwrap_or_default();
+        mode.name().to_string()
+    }
+
+    /// Change how tool previews are drawn. Returns false for an unknown mode.
+    #[wasm_bindgen(js_name = setPreviewMode)]
+    pub fn set_preview_mode(&mut self, mode: &str) -> bool {
+        let Some(mode) = PreviewMode::from_name(mode) else {
+            return false;
+        };
+        self.theme.preview.mode = mode.name().to_string();
+        self.dirty_tracker.request_full_redraw();
+        true
+    }
+
+    /// Returns the pointer to the underlying raw pixel buffer (RGBA format).
+    #[wasm_bindgen(js_name = getPixelBufferPtr)]
+    pub fn get_pixel_buffer_ptr(&self) -> *const u8 {
+        self.pixel_buffer.as_ptr()
+    }
+
+    /// Returns the length of the raw pixel buffer in bytes.
+    #[wasm_bindgen(js_name = getPixelBufferLen)]
+    pub fn get_pixel_buffer_len(&self) -> usize {
+        self.pixel_buffer.len()
+    }
+
+    /// Pixel rectangles changed by the last `renderToPixelBuffer`, as a flat
+    /// `[x, y, width, height, ...]` list for `putImageData`.
+    #[wasm_bindgen(js_name = getRenderedRegions)]
+    pub fn get_rendered_regions(&self) -> Vec<u32> {
+        self.rendered_regions
+            .iter()
+            .flat_map(|&(x0, y0, x1, y1)| [x0, y0, x1 - x0, y1 - y0])
+            .map(|v| v as u32)
+            .collect()
+    }
+
+    /// Renders the canvas layers, selection highlights and cursor overlay into the pixel buffer, using dirty-rect optimization when possible.
+    /// In viewport mode a pan scrolls the buffer and renders only the uncovered strips.
+    #[wasm_bindgen(js_name = renderToPixelBuffer)]
+    pub fn render_to_pixel_buffer(&mut self) {
+        self.sync_cursor_overlay();
+        let (grid_width, grid_height) = (self.state.grid.width(), self.state.grid.height());
+        let layout = self.pixel_layout();
+        let visible = layout.visible_cells(grid_width, grid_height);
+
+        let mut is_resized = false;
+        if self.pixel_buffer.len() != layout.byte_len() {
+            self.pixel_buffer.resize(layout.byte_len(), 0);
+            is_resized = true;
+        }
+
+        // A pan alone leaves the content alone, so an empty dirty rect is not a full redraw.
+        let tracker = &self.dirty_tracker;
+        let mut needs_full = is_resized
+            || tracker.full_redraw_requested()
+            || (!tracker.panned() && tracker.needs_full_redraw())
+            || tracker.covers_most_of(grid_width, grid_height);
+
+        if is_resized || tracker.full_redraw_requested() {
+            // Font glyphs may have changed, which tile signatures do not cover
+            self.tile_cache.clear();
+        }
+        let palette = self.pixel_palette();
+        let bg_color = palette.background;
+        let mut regions = Vec::new();
+        let mut scrolled = false;
+        if !needs_full {
+            let (dx, dy) = (
+                layout.origin.0 - self.buffer_origin.0,
+                layout.origin.1 - self.buffer_origin.1,
+            );
+            if dx != 0 || dy != 0 {
+                match layout.scroll(&mut self.pixel_buffer, dx, dy) {
+                    Some(mut strips) => {
+                        scrolled = true;
+                        // The ruler bands moved with the pixels; what they now cover
+                        // is stale too
+                        for (x0, y0, x1, y1) in self.grid_overlay.ruler_bands(&layout, grid_height)
+                        {
+                            let (x0, y0, x1, y1) = (x0 as i32, y0 as i32, x1 as i32, y1 as i32);
+                            strips.push(layout.clip(x0 + dx, y0 + dy, x1 + dx, y1 + dy));
+                        }
+                        for strip in strips {
+                            layout.fill(&mut self.pixel_buffer, strip, bg_color);
+                            regions.push(layout.cells_in(strip).intersection(&visible));
+                        }
+                    }
+                    None => needs_full = true,
+                }
+            }
+            for dirty in self.dirty_tracker.regions() {
+                regions.push(dirty.intersection(&visible));
+            }
+        }
+        if needs_full {
+            // Pixels outside the canvas (viewport mode) are background too.
+            let all = (0, 0, layout.width, layout.height);
+            layout.fill(&mut self.pixel_buffer, all, bg_color);
+            regions = vec![visible];
+        }
+        self.buffer_origin = layout.origin;
+
+        regions.retain(|r| !r.is_empty());
+        self.rendered_regions.clear();
+        if regions.is_empty() && !scrolled {
+            return;
+        }
+        for dirty in &regions {
+            self.render_cells(&layout, dirty, &palette);
+        }
+        // Rulers stay on the buffer edges, over whatever the cells drew there
+        let size = (grid_width, grid_height);
+        let bands = self.grid_overlay.ruler_bands(&layout, grid_height);
+        let (grid, buffer) = (self.grid_overlay, &mut self.pixel_buffer);
+        grid.draw_rulers(buffer, &layout, size, palette.grid, bg_color);
+        self.rendered_regions = if needs_full || scrolled {
+            vec![(0, 0, layout.width, layout.height)]
+        } else {
+            let cells = regions.iter().map(|r| layout.cell_pixels(r));
+            cells
+                .chain(bands)
+                .filter(|r| r.0 < r.2 && r.1 < r.3)
+                .collect()
+        };
+
+        // Update metric counters to track if we did a full or dirty rect render
+        if needs_full {
+            self.full_render_count += 1;
+        } else {
+            self.dirty_render_count += 1;
+        }
+    }
+}
+
+impl AsciiEditor {
+    /// Draw the cells in `dirty` (within the canvas), reusing cached tiles.
+    fn render_cells(&mut self, layout: &PixelLayout, dirty: &DirtyRect, palette: &PixelPalette) {
+        // The buffers are taken out so the layer views can borrow `self`
+        let mut pixels = std::mem::take(&mut self.pixel_buffer);
+        let mut tiles = std::mem::take(&mut self.tile_cache);
+        let views = self.layer_views();
+        let composite = self.synced_composite(&views);
+        let (ox, oy) = self.active_offset();
+        let scene = PixelScene {
+            layers: &views,
+            composite: &composite,
+            size: (self.state.grid.width(), self.state.grid.height()),
+            selection: self.current_selection.as_ref().map(|sel| {
+                let (min_x, min_y, max_x, max_y) = sel.bounds();
+                DirtyRect::from_points(min_x + ox, min_y + oy, max_x + ox, max_y + oy)
+            }),
+            preview: (&self.preview_ops, (ox, oy)),
+            preview_target: Some(&self.state.grid),
+            grid: self.grid_overlay,
+            cursor: self.cursor_overlay,
+        };
+        tiles.render(
+            &self.font_atlas,
+            &mut pixels,
+            layout,
+            dirty,
+            &scene,
+            palette,
+        );
+        drop(composite);
+        drop(views);
+        self.pixel_buffer = pixels;
+        self.tile_cache = tiles;
+    }
+
+    /// The RGBA buffer written by `renderToPixelBuffer`, for native callers.
+    pub fn pixel_buffer(&self) -> &[u8] {
+        &self.pixel_buffer
+    }
+
+    /// Theme colors for the pixel renderer; the eraser footprint uses the error color.
+    pub(crate) fn pixel_palette(&self) -> PixelPalette {
+        let mut palette = PixelPalette::from_theme(|name| self.theme.variable(name));
+        if self.tool_id == ToolId::Eraser {
+            if let Some(error) = parse_hex_color(&self.theme.error) {
+                palette.overlay.hover = error;
+            }
+        }
+        palette
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::core::tools::DrawOp;
+    use crate::wasm::bindings::AsciiEditor;
+
+    #[test]
+    fn test_grid_overlay_survives_dirty_redraws() {
+        let mut canvas = AsciiEditor::new(4, 2);
+        canvas.set_grid_lines(true);
+        canvas.set_rulers(true);
+        canvas.render_to_pixel_buffer();
+        canvas.clear_dirty_state();
+        let before = canvas.pixel_buffer.clone();
+        let width = 4 * 8;
+        // Left edge of cell (2, 1) carries a grid line.
+        let edge = ((20 + 10) * width + 16) * 4;
+        assert_ne!(before[edge..edge + 3], [0x1e, 0x1e, 0x1e]);
+
+        canvas.commit_ops(&[DrawOp::new(2, 1, '#')]);
+        canvas.render_to_pixel_buffer();
+        assert_eq!(canvas.dirty_render_count, 1);
+        let after = &canvas.pixel_buffer;
+        assert_eq!(after[edge..edge + 4], before[edge..edge + 4]);
+        for (i, (a, b)) in after.chunks(4).zip(before.chunks(4)).enumerate() {
+            let (x, y) = (i % width / 8, i / width / 20);
+            assert!(
+                a == b || (x, y) == (2, 1),
+                "pixel outside the dirty cell changed"
+            );
+        }
+        assert_ne!(after, &before);
+    }
+
+    #[test]
+    fn test_opposite_corners_render_two_small_regions() {
+        let mut canvas = AsciiEditor::new(40, 20);
+        canvas.render_to_pixel_buffer();
+        canvas.clear_dirty_state();
+
+        canvas.commit_ops(&[DrawOp::new(0, 0, 'a'), DrawOp::new(39, 19, 'z')]);
+        canvas.render_to_pixel_buffer();
+        assert_eq!(canvas.dirty_render_count, 1);
+        assert_eq!(
+            canvas.get_rendered_regions(),
+            vec![0, 0, 8, 20, 39 * 8, 19 * 20, 8, 20]
+        );
+
+        // Changing most of the canvas falls back to one full redraw.
+        canvas.clear_dirty_state();
+        let ops: Vec<_> = (0..30)
+            .flat_map(|x| (0..20).map(move |y| DrawOp::new(x, y, '.')))
+            .collect();
+        canvas.commit_ops(&ops);
+        canvas.render_to_pixel_buffer();
+        assert_eq!(canvas.full_render_count, 2);
+        assert_eq!(canvas.get_rendered_regions(), vec![0, 0, 320, 400]);
+    }
+
+    #[test]
+    fn test_unchanged_tiles_are_reused_across_frames() {
+        let mut canvas = AsciiEditor::new(40, 20);
+        canvas.commit_ops(&[DrawOp::new(1, 1, '#')]);
+        canvas.add_layer_impl();
+        canvas.commit_ops(&[DrawOp::new(30, 15, '@')]);
+        canvas.render_to_pixel_buffer();
+        canvas.clear_dirty_state();
+        let reused = canvas.tile_cache.reused;
+
+        canvas.render_to_pixel_buffer();
+        assert_eq!(canvas.tile_cache.reused, reused + 6);
+
+        canvas.commit_ops(&[DrawOp::new(2, 1, '+')]);
+        canvas.render_to_pixel_buffer();
+        // Same pixels as a full render of the same document
+        let mut fresh = AsciiEditor::new(40, 20);
+        fresh
+            .try_load_document(&canvas.serialize_document_text())
+            .unwrap();
+        fresh.render_to_pixel_buffer();
+        assert!(fresh.pixel_buffer == canvas.pixel_buffer);
+        let text = canvas.export_ascii();
+        assert!(text.contains("#+") && text.contains('@'), "{text}");
+    }
+
+    #[test]
+    fn test_preview_mode_survives_theme_switches() {
+        let mut canvas = AsciiEditor::new(4, 2);
+        assert_eq!(canvas.preview_mode(), "tinted");
+        assert!(!canvas.set_preview_mode("sparkle"));
+        assert!(canvas.set_preview_mode("Diff"));
+        assert!(canvas.set_theme("High Contrast".to_string()));
+        assert_eq!(canvas.preview_mode(), "diff");
+        let preview = canvas.pixel_palette().preview;
+        assert_eq!(preview.mode, crate::render::PreviewMode::Diff);
+        assert_eq!(preview.removed, [255, 0, 0, 255]);
+    }
+}
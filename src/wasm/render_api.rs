@@ -3,6 +3,7 @@
 use wasm_bindgen::prelude::*;
 
 use super::bindings::AsciiEditor;
+use crate::core::{Cell, CellStyle};
 use crate::wasm::render_bridge::{
     export_ascii, get_dirty_render_commands, get_render_commands, get_render_commands_full,
     needs_redraw, request_full_redraw,
@@ -58,11 +59,22 @@ impl AsciiEditor {
                     if cell.is_visible() {
                         let px = x as f64 * char_width;
                         let py = y as f64 * line_height;
+                        if cell.style.contains(CellStyle::HIGHLIGHT) {
+                            svg.push_str(&format!(
+                                r##"<rect x="{x}" y="{y}" width="{w}" height="{h}" fill="{fill}" />"##,
+                                x = px,
+                                y = py,
+                                w = char_width,
+                                h = line_height,
+                                fill = self.theme.highlight
+                            ));
+                        }
                         let escaped = escape_xml_char(cell.ch);
                         svg.push_str(&format!(
-                            r##"<text x="{x}" y="{y}" dominant-baseline="hanging">{char}</text>"##,
+                            r##"<text x="{x}" y="{y}" dominant-baseline="hanging"{attrs}>{char}</text>"##,
                             x = px,
                             y = py,
+                            attrs = svg_style_attributes(cell.style),
                             char = escaped
                         ));
                     }
@@ -74,24 +86,56 @@ impl AsciiEditor {
         svg
     }
 
+    /// Exports the composited visible canvas layers as an HTML `<pre>` block, with
+    /// styled runs wrapped in `<span style="...">`.
+    #[wasm_bindgen(js_name = exportHtml)]
+    pub fn export_html(&self) -> String {
+        let composite = self.composite_visible_grid();
+        let mut html = format!(
+            r#"<pre style="background:{bg};color:{fg};font-family:{font}">"#,
+            bg = self.theme.background,
+            fg = self.theme.foreground,
+            font = escape_xml(&self.theme.font_family)
+        );
+        for y in 0..composite.height() as i32 {
+            let row: Vec<Cell> = (0..composite.width() as i32)
+                .filter_map(|x| composite.get(x, y).copied())
+                .collect();
+            let end = row.iter().rposition(Cell::is_visible).map_or(0, |i| i + 1);
+            let mut x = 0;
+            while x < end {
+                // Blanks are never styled, so they join the surrounding plain text.
+                let style = visible_style(&row[x]);
+                let run = row[x..end]
+                    .iter()
+                    .take_while(|c| visible_style(c) == style)
+                    .count();
+                let text: String = row[x..x + run]
+                    .iter()
+                    .map(|c| escape_xml_char(c.ch))
+                    .collect();
+                if style.is_empty() {
+                    html.push_str(&text);
+                } else {
+                    html.push_str(&format!(
+                        r#"<span style="{}">{text}</span>"#,
+                        css_style(style, &self.theme.highlight)
+                    ));
+                }
+                x += run;
+            }
+            html.push('\n');
+        }
+        html.push_str("</pre>");
+        html
+    }
+
     /// Selection-aware export for the OS clipboard (selection region or trimmed full grid).
     #[wasm_bindgen(js_name = exportForCopy)]
     pub fn export_for_copy_public(&self) -> String {
         self.export_for_copy()
     }
 
-    /// Serialize diagram to JSON (`.asc` format).
-    #[wasm_bindgen(js_name = serializeDocument)]
-    pub fn serialize_document(&self) -> String {
-        self.serialize_document_impl()
-    }
-
-    /// Load diagram from JSON (`.asc` format). Returns false on parse/schema errors.
-    #[wasm_bindgen(js_name = loadDocument)]
-    pub fn load_document(&mut self, json: String) -> bool {
-        self.load_document_impl(&json)
-    }
-
     /// Number of layers.
     #[wasm_bindgen(getter = layerCount)]
     pub fn layer_count(&self) -> usize {
@@ -181,12 +225,9 @@ impl AsciiEditor {
         self.merge_down_impl(index)
     }
 
-    /// Returns whether the active layer is locked.
+    /// Returns whether the active layer is locked, directly or through its group.
     pub(crate) fn is_active_layer_locked(&self) -> bool {
-        self.layers
-            .get(self.active_layer)
-            .map(|l| l.locked)
-            .unwrap_or(false)
+        self.layer_effectively_locked(self.active_layer)
     }
 
     /// Returns the full list of drawing instructions/commands to render the entire canvas in JS.
@@ -211,9 +252,13 @@ impl AsciiEditor {
     /// Returns only the drawing instructions/commands for regions of the canvas that have changed.
     #[wasm_bindgen(js_name = getDirtyRenderCommands)]
     pub fn get_dirty_render_commands(&mut self) -> JsValue {
-        if self.dirty_tracker.needs_full_redraw() {
+        let (width, height) = (self.state.grid.width(), self.state.grid.height());
+        if self.dirty_tracker.needs_full_redraw()
+            || self.dirty_tracker.panned()
+            || self.dirty_tracker.covers_most_of(width, height)
+        {
             self.full_render_count += 1;
-        } else if !self.dirty_tracker.dirty_rect().is_empty() {
+        } else if !self.dirty_tracker.regions().is_empty() {
             self.dirty_render_count += 1;
         }
 
@@ -260,181 +305,53 @@ impl AsciiEditor {
     pub fn clear_dirty_state(&mut self) {
         self.dirty_tracker.clear();
     }
+}
 
-    /// Updates the font atlas glyph data cache for a specific Unicode character.
-    #[wasm_bindgen(js_name = updateFontAtlasGlyph)]
-    pub fn update_font_atlas_glyph(&mut self, ch_code: u32, glyph_data: Vec<u8>) {
-        if let Some(ch) = char::from_u32(ch_code) {
-            self.font_atlas.update_glyph(ch, &glyph_data);
-            self.dirty_tracker.request_full_redraw();
-        }
+/// Style of a cell as exported; blank cells count as plain.
+fn visible_style(cell: &Cell) -> CellStyle {
+    if cell.is_visible() {
+        cell.style
+    } else {
+        CellStyle::NONE
     }
+}
 
-    /// Returns the pointer to the underlying raw pixel buffer (RGBA format).
-    #[wasm_bindgen(js_name = getPixelBufferPtr)]
-    pub fn get_pixel_buffer_ptr(&self) -> *const u8 {
-        self.pixel_buffer.as_ptr()
+/// SVG presentation attributes for the text parts of a cell style.
+fn svg_style_attributes(style: CellStyle) -> String {
+    let mut attrs = String::new();
+    if style.contains(CellStyle::BOLD) {
+        attrs.push_str(r#" font-weight="bold""#);
     }
-
-    /// Returns the length of the raw pixel buffer in bytes.
-    #[wasm_bindgen(js_name = getPixelBufferLen)]
-    pub fn get_pixel_buffer_len(&self) -> usize {
-        self.pixel_buffer.len()
+    if style.contains(CellStyle::ITALIC) {
+        attrs.push_str(r#" font-style="italic""#);
     }
+    if style.contains(CellStyle::UNDERLINE) {
+        attrs.push_str(r#" text-decoration="underline""#);
+    }
+    attrs
+}
 
-    /// Renders the canvas layers and selection highlights into the pixel buffer, using dirty-rect optimization when possible.
-    #[wasm_bindgen(js_name = renderToPixelBuffer)]
-    pub fn render_to_pixel_buffer(&mut self) {
-        let grid_width = self.state.grid.width();
-        let grid_height = self.state.grid.height();
-        let glyph_w = 8;
-        let glyph_h = 20;
-        let buffer_width = grid_width * glyph_w;
-        let buffer_height = grid_height * glyph_h;
-
-        let required_len = buffer_width * buffer_height * 4;
-        let mut is_resized = false;
-        if self.pixel_buffer.len() != required_len {
-            self.pixel_buffer.resize(required_len, 0);
-            is_resized = true;
-        }
-
-        let needs_full = self.dirty_tracker.needs_full_redraw() || is_resized;
-
-        let mut dirty = if needs_full {
-            crate::render::DirtyRect::full(grid_width, grid_height)
-        } else {
-            *self.dirty_tracker.dirty_rect()
-        };
-
-        dirty.clamp(grid_width, grid_height);
-
-        if dirty.is_empty() {
-            return;
-        }
-
-        let bg_color = parse_hex_color(&self.theme.background).unwrap_or([30, 30, 30, 255]);
-        let fg_color = parse_hex_color(&self.theme.foreground).unwrap_or([212, 212, 212, 255]);
-
-        // 1. Clear only the dirty pixel region to bg_color
-        let py_start = dirty.y1 as usize * glyph_h;
-        let py_end = (dirty.y2 as usize + 1) * glyph_h;
-        let px_start = dirty.x1 as usize * glyph_w;
-        let px_end = (dirty.x2 as usize + 1) * glyph_w;
-
-        for py in py_start..py_end {
-            let row_start_idx = (py * buffer_width + px_start) * 4;
-            let row_end_idx = (py * buffer_width + px_end) * 4;
-            for idx in (row_start_idx..row_end_idx).step_by(4) {
-                debug_assert!(
-                    idx + 3 < self.pixel_buffer.len(),
-                    "Pixel index out of bounds in render_to_pixel_buffer clear"
-                );
-                if idx + 3 < self.pixel_buffer.len() {
-                    self.pixel_buffer[idx] = bg_color[0];
-                    self.pixel_buffer[idx + 1] = bg_color[1];
-                    self.pixel_buffer[idx + 2] = bg_color[2];
-                    self.pixel_buffer[idx + 3] = bg_color[3];
-                }
-            }
-        }
-
-        // 2. Render Selection Highlights if there is an active selection that intersects the dirty rect
-        if let Some(ref sel) = self.current_selection {
-            let (min_x, min_y, max_x, max_y) = sel.bounds();
-            let highlight_color =
-                parse_hex_color(&self.theme.selection).unwrap_or([38, 79, 120, 255]);
-
-            let start_y = min_y.max(dirty.y1);
-            let end_y = max_y.min(dirty.y2);
-            let start_x = min_x.max(dirty.x1);
-            let end_x = max_x.min(dirty.x2);
-
-            for gy in start_y..=end_y {
-                for gx in start_x..=end_x {
-                    if self.state.grid.in_bounds(gx, gy) {
-                        let sx = gx as usize * glyph_w;
-                        let sy = gy as usize * glyph_h;
-
-                        for y in 0..glyph_h {
-                            let buffer_y = sy + y;
-                            let buffer_row_start = (buffer_y * buffer_width + sx) * 4;
-                            for x in 0..glyph_w {
-                                let pixel_idx = buffer_row_start + x * 4;
-                                debug_assert!(
-                                    pixel_idx + 3 < self.pixel_buffer.len(),
-                                    "Pixel index out of bounds in render_to_pixel_buffer highlight"
-                                );
-                                if pixel_idx + 3 < self.pixel_buffer.len() {
-                                    self.pixel_buffer[pixel_idx] = highlight_color[0];
-                                    self.pixel_buffer[pixel_idx + 1] = highlight_color[1];
-                                    self.pixel_buffer[pixel_idx + 2] = highlight_color[2];
-                                    self.pixel_buffer[pixel_idx + 3] = highlight_color[3];
-                                }
-                            }
-                        }
-                    }
-                }
-            }
-        }
-
-        // 3. Render grid composite glyphs that fall inside the dirty rect using sparse lookup
-        for gy in dirty.y1..=dirty.y2 {
-            for gx in dirty.x1..=dirty.x2 {
-                let mut composite_cell = None;
-                for i in (0..self.layers.len()).rev() {
-                    let layer = &self.layers[i];
-                    if !layer.visible {
-                        continue;
-                    }
-                    let grid = if i == self.active_layer {
-                        &self.state.grid
-                    } else {
-                        &layer.grid
-                    };
-                    if let Some(cell) = grid.get(gx, gy) {
-                        if cell.is_visible() {
-                            composite_cell = Some(*cell);
-                            break;
-                        }
-                    }
-                }
-
-                if let Some(cell) = composite_cell {
-                    self.font_atlas.render_glyph(
-                        &mut self.pixel_buffer,
-                        buffer_width,
-                        gx as usize * glyph_w,
-                        gy as usize * glyph_h,
-                        cell.ch,
-                        fg_color,
-                    );
-                }
-            }
-        }
-
-        // 4. Render preview ops that fall inside the dirty rect
-        let preview_color = [86, 156, 214, 179]; // rgba(86, 156, 214, 0.7)
-        for op in &self.preview_ops {
-            if op.cell.is_visible() && dirty.contains(op.x, op.y) {
-                self.font_atlas.render_glyph(
-                    &mut self.pixel_buffer,
-                    buffer_width,
-                    op.x as usize * glyph_w,
-                    op.y as usize * glyph_h,
-                    op.cell.ch,
-                    preview_color,
-                );
-            }
-        }
-
-        // Update metric counters to track if we did a full or dirty rect render
-        if needs_full {
-            self.full_render_count += 1;
-        } else {
-            self.dirty_render_count += 1;
-        }
+/// Inline CSS for a cell style, highlighting with `highlight`.
+fn css_style(style: CellStyle, highlight: &str) -> String {
+    let mut rules = Vec::new();
+    if style.contains(CellStyle::BOLD) {
+        rules.push("font-weight:bold".to_string());
+    }
+    if style.contains(CellStyle::ITALIC) {
+        rules.push("font-style:italic".to_string());
     }
+    if style.contains(CellStyle::UNDERLINE) {
+        rules.push("text-decoration:underline".to_string());
+    }
+    if style.contains(CellStyle::HIGHLIGHT) {
+        rules.push(format!("background:{highlight}"));
+    }
+    rules.join(";")
+}
+
+/// Escapes special XML characters in attribute values.
+fn escape_xml(text: &str) -> String {
+    text.chars().map(escape_xml_char).collect()
 }
 
 /// Escapes special XML/SVG characters in text elements.
@@ -449,25 +366,6 @@ fn escape_xml_char(c: char) -> String {
     }
 }
 
-/// Helper function to parse hex color string into [r, g, b, a] bytes.
-fn parse_hex_color(hex: &str) -> Option<[u8; 4]> {
-    let hex = hex.trim_start_matches('#');
-    if hex.len() == 6 {
-        let r = u8::from_str_radix(&hex[0..2], 16).ok()?;
-        let g = u8::from_str_radix(&hex[2..4], 16).ok()?;
-        let b = u8::from_str_radix(&hex[4..6], 16).ok()?;
-        Some([r, g, b, 255])
-    } else if hex.len() == 8 {
-        let r = u8::from_str_radix(&hex[0..2], 16).ok()?;
-        let g = u8::from_str_radix(&hex[2..4], 16).ok()?;
-        let b = u8::from_str_radix(&hex[4..6], 16).ok()?;
-        let a = u8::from_str_radix(&hex[6..8], 16).ok()?;
-        Some([r, g, b, a])
-    } else {
-        None
-    }
-}
-
 impl AsciiEditor {
     /// Marks a cell as dirty for benchmarking purposes.
     pub fn mark_cell_dirty_for_bench(&mut self, x: i32, y: i32) {
@@ -80,20 +80,29 @@ pub(crate) fn get_dirty_render_commands(
     grid: &crate::core::Grid,
     dirty_tracker: &mut DirtyTracker,
 ) -> JsValue {
-    if dirty_tracker.needs_full_redraw() {
+    // Render commands carry the pan, so panning redraws everything.
+    if dirty_tracker.needs_full_redraw()
+        || dirty_tracker.panned()
+        || dirty_tracker.covers_most_of(grid.width(), grid.height())
+    {
         dirty_tracker.clear();
         return get_render_commands(renderer, grid);
     }
 
-    let dirty = *dirty_tracker.dirty_rect();
+    let commands: Vec<_> = dirty_tracker
+        .regions()
+        .iter()
+        .flat_map(|dirty| renderer.build_render_commands(grid, dirty))
+        .collect();
     dirty_tracker.clear();
 
-    let commands = renderer.build_render_commands(grid, &dirty);
     serde_wasm_bindgen::to_value(&commands).unwrap_or(JsValue::NULL)
 }
 
 pub(crate) fn needs_redraw(dirty_tracker: &DirtyTracker) -> bool {
-    dirty_tracker.needs_full_redraw() || !dirty_tracker.dirty_rect().is_empty()
+    dirty_tracker.needs_full_redraw()
+        || dirty_tracker.panned()
+        || !dirty_tracker.dirty_rect().is_empty()
 }
 
 pub(crate) fn request_full_redraw(dirty_tracker: &mut DirtyTracker) {
@@ -0,0 +1,209 @@
+//! Shortcut API - key bindings, rebinding and the editor actions they trigger.
+//!
+//! Key events are matched against the editor's `ShortcutManager`. Editor actions
+//! (undo, tools, styles, ...) run here; page actions such as `zoom_fit` or `help`
+//! are returned by `shortcutAction` for the page to run.
+
+use serde::Serialize;
+use wasm_bindgen::prelude::*;
+
+use super::bindings::AsciiEditor;
+use crate::core::CellStyle;
+use crate::ui::{ShortcutError, ShortcutManager};
+
+/// An action and its current chords, as listed by `getShortcuts`.
+#[derive(Serialize)]
+struct ShortcutInfo {
+    action: String,
+    description: String,
+    chords: Vec<String>,
+}
+
+#[wasm_bindgen]
+impl AsciiEditor {
+    /// Action bound to a key event (`undo`, `tool_rectangle`, `zoom_fit`, ...), if any.
+    #[wasm_bindgen(js_name = shortcutAction)]
+    pub fn shortcut_action(
+        &self,
+        key: &str,
+        ctrl: bool,
+        shift: bool,
+        alt: bool,
+        meta: bool,
+    ) -> Option<String> {
+        self.shortcuts
+            .find_match(key, ctrl, shift, alt, meta)
+            .map(|s| s.action.clone())
+    }
+
+    /// Every action with its description and chords, as `[{ action, description, chords }]`.
+    #[wasm_bindgen(js_name = getShortcuts)]
+    pub fn get_shortcuts(&self) -> JsValue {
+        serde_wasm_bindgen::to_value(&self.shortcut_list()).unwrap_or(JsValue::NULL)
+    }
+
+    /// Action other than `action` already bound to `chord` (e.g. `Ctrl+Shift+K`).
+    #[wasm_bindgen(js_name = shortcutConflict)]
+    pub fn shortcut_conflict(&self, action: &str, chord: &str) -> Option<String> {
+        let conflict = self.shortcuts.conflict(action, chord).ok().flatten();
+        conflict.map(str::to_string)
+    }
+
+    /// Bind `action` to `chord` alone. Throws when the chord is malformed or bound to
+    /// another action; the bindings are unchanged then.
+    #[wasm_bindgen(js_name = bindShortcut)]
+    pub fn bind_shortcut(&mut self, action: &str, chord: &str) -> Result<(), JsValue> {
+        self.shortcuts
+            .rebind(action, &[chord.to_string()])
+            .map_err(|e| JsError::new(&e.to_string()).into())
+    }
+
+    /// Remove every chord of `action`.
+    #[wasm_bindgen(js_name = unbindShortcut)]
+    pub fn unbind_shortcut(&mut self, action: &str) -> Result<(), JsValue> {
+        self.shortcuts
+            .rebind(action, &[])
+            .map_err(|e| JsError::new(&e.to_string()).into())
+    }
+
+    /// Go back to the default bindings.
+    #[wasm_bindgen(js_name = resetShortcuts)]
+    pub fn reset_shortcuts(&mut self) {
+        self.shortcuts = ShortcutManager::new();
+    }
+
+    /// Bindings changed from the defaults, as JSON to save in user settings.
+    #[wasm_bindgen(getter = shortcutBindings)]
+    pub fn shortcut_bindings(&self) -> String {
+        self.shortcuts.to_json()
+    }
+
+    /// Apply bindings saved from `shortcutBindings` on top of the defaults. Throws on
+    /// invalid JSON, unknown actions or conflicts, keeping the current bindings.
+    #[wasm_bindgen(js_name = loadShortcutBindings)]
+    pub fn load_shortcut_bindings(&mut self, json: &str) -> Result<(), JsValue> {
+        self.load_shortcut_bindings_impl(json)
+            .map_err(|e| JsError::new(&e.to_string()).into())
+    }
+}
+
+impl AsciiEditor {
+    pub(crate) fn load_shortcut_bindings_impl(&mut self, json: &str) -> Result<(), ShortcutError> {
+        self.shortcuts = ShortcutManager::from_json(json)?;
+        Ok(())
+    }
+
+    /// Actions in registration order, unbound ones included.
+    fn shortcut_list(&self) -> Vec<ShortcutInfo> {
+        let mut list: Vec<ShortcutInfo> = Vec::new();
+        for shortcut in ShortcutManager::new().all() {
+            if list.iter().all(|info| info.action != shortcut.action) {
+                list.push(ShortcutInfo {
+                    action: shortcut.action.clone(),
+                    description: shortcut.description.clone(),
+                    chords: self.shortcuts.chords(&shortcut.action),
+                });
+            }
+        }
+        list
+    }
+
+    /// Run an editor action. Returns false for page actions and for actions that
+    /// did nothing (a tool key while a tool is busy, cut without a selection), so
+    /// the key can still be typed.
+    pub(crate) fn run_shortcut(&mut self, action: &str) -> bool {
+        if let Some(tool_id) = ShortcutManager::tool_from_action(action) {
+            if self.active_tool.is_active() {
+                return false;
+            }
+            self.set_tool_by_id_impl(tool_id);
+            return true;
+        }
+        match action {
+            "cancel" => {
+                self.active_tool.reset();
+                self.current_selection = None;
+                self.preview_ops.clear();
+                self.dirty_tracker.request_full_redraw();
+            }
+            "pan_mode" => self.space_held = true,
+            "undo" => {
+                self.undo();
+            }
+            "redo" => {
+                self.redo();
+            }
+            "copy" => {
+                // Fill the internal clipboard; the caller exports the ASCII for the OS
+                let _ = self.copy_selection_impl();
+            }
+            "cut" => return self.cut_selection_impl(),
+            "paste" => return self.paste_impl(),
+            "select_all" => self.select_all_impl(),
+            "bold" => self.toggle_style(CellStyle::BOLD),
+            "italic" => self.toggle_style(CellStyle::ITALIC),
+            "underline" => self.toggle_style(CellStyle::UNDERLINE),
+            _ => return false,
+        }
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::core::tools::ToolId;
+    use crate::wasm::bindings::AsciiEditor;
+
+    #[test]
+    fn test_rebound_keys_reach_the_editor() {
+        let mut canvas = AsciiEditor::new(10, 4);
+        let action =
+            |canvas: &AsciiEditor, key, alt| canvas.shortcut_action(key, false, false, alt, false);
+        assert_eq!(
+            action(&canvas, "r", false).as_deref(),
+            Some("tool_rectangle")
+        );
+
+        canvas
+            .load_shortcut_bindings_impl(r#"{"tool_rectangle": ["Alt+R"]}"#)
+            .unwrap();
+        assert_eq!(action(&canvas, "r", false), None);
+        let rebound = action(&canvas, "r", true).unwrap();
+        assert!(canvas.run_shortcut(&rebound));
+        assert_eq!(canvas.tool_id, ToolId::Rectangle);
+        assert_eq!(
+            canvas.shortcut_bindings(),
+            r#"{"tool_rectangle":["Alt+R"]}"#
+        );
+
+        // Conflicting bindings are rejected and leave the current ones in place.
+        assert!(canvas
+            .load_shortcut_bindings_impl(r#"{"undo": ["Ctrl+A"]}"#)
+            .is_err());
+        assert_eq!(
+            canvas.shortcut_conflict("undo", "Ctrl+A").as_deref(),
+            Some("select_all")
+        );
+        assert_eq!(
+            action(&canvas, "r", true).as_deref(),
+            Some("tool_rectangle")
+        );
+
+        // Page actions leave the key to the caller.
+        assert!(!canvas.run_shortcut("zoom_fit"));
+        canvas.reset_shortcuts();
+        assert_eq!(canvas.shortcut_bindings(), "{}");
+    }
+
+    #[test]
+    fn test_shortcut_list_includes_unbound_actions() {
+        let mut canvas = AsciiEditor::new(10, 4);
+        canvas.shortcuts.rebind("help", &[]).unwrap();
+        let list = canvas.shortcut_list();
+        let redo = list.iter().find(|info| info.action == "redo").unwrap();
+        assert_eq!(redo.chords, ["Ctrl+Shift+Z", "Ctrl+Y"]);
+        let help = list.iter().find(|info| info.action == "help").unwrap();
+        assert!(help.chords.is_empty());
+        assert_eq!(list.iter().filter(|info| info.action == "redo").count(), 1);
+    }
+}
@@ -0,0 +1,174 @@
+//! Style API - bold, italic, underline and highlight on typed text and selections.
+//!
+//! Styles are `CellStyle` bits passed to JS as a `u8`. Text typed with the text tool
+//! takes the current text style; restyling a selection is one undoable step on the
+//! active layer.
+
+use wasm_bindgen::prelude::*;
+
+use super::bindings::AsciiEditor;
+use crate::core::commands::{Command, DrawCommand};
+use crate::core::tools::DrawOp;
+use crate::core::CellStyle;
+
+#[wasm_bindgen]
+impl AsciiEditor {
+    /// Style bits applied to text typed with the text tool.
+    #[wasm_bindgen(getter = textStyle)]
+    pub fn text_style(&self) -> u8 {
+        self.text_style.bits()
+    }
+
+    /// Set the style bits for typed text (unknown bits are dropped).
+    #[wasm_bindgen(setter = textStyle)]
+    pub fn set_text_style(&mut self, bits: u8) {
+        self.text_style = CellStyle::from_bits_truncate(bits);
+    }
+
+    /// Style bits shared by every non-blank selected cell of the active layer.
+    #[wasm_bindgen(js_name = selectionStyle)]
+    pub fn selection_style(&self) -> u8 {
+        let cells = self.selected_ops();
+        if cells.is_empty() {
+            return 0;
+        }
+        cells
+            .iter()
+            .fold(CellStyle::all(), |style, op| style & op.cell.style)
+            .bits()
+    }
+
+    /// Turn style bits on or off for the non-blank selected cells of the active layer.
+    /// Returns false when nothing changed.
+    #[wasm_bindgen(js_name = setSelectionStyle)]
+    pub fn set_selection_style(&mut self, bits: u8, enabled: bool) -> bool {
+        self.set_selection_style_impl(CellStyle::from_bits_truncate(bits), enabled)
+    }
+}
+
+impl AsciiEditor {
+    pub(crate) fn set_selection_style_impl(&mut self, style: CellStyle, enabled: bool) -> bool {
+        if style.is_empty() || self.is_active_layer_locked() {
+            return false;
+        }
+        let ops: Vec<DrawOp> = self
+            .selected_ops()
+            .into_iter()
+            .filter(|op| op.cell.style.contains(style) != enabled)
+            .map(|mut op| {
+                op.cell.style.set(style, enabled);
+                op
+            })
+            .collect();
+        if ops.is_empty() {
+            return false;
+        }
+        let (ox, oy) = self.active_offset();
+        for op in &ops {
+            self.dirty_tracker.mark_dirty(op.x + ox, op.y + oy);
+        }
+        let mut cmd = DrawCommand::with_description(ops, "Change style");
+        cmd.apply(&mut self.state.grid);
+        self.history.push(Box::new(cmd));
+        true
+    }
+
+    /// Keyboard toggle (Ctrl+B / I / U): restyle the selection when there is one,
+    /// otherwise switch the style for typed text.
+    pub(crate) fn toggle_style(&mut self, style: CellStyle) {
+        if self.current_selection.is_some() {
+            let on = CellStyle::from_bits_truncate(self.selection_style()).contains(style);
+            self.set_selection_style_impl(style, !on);
+        } else {
+            self.text_style.toggle(style);
+        }
+    }
+
+    /// Give typed characters the current text style.
+    pub(crate) fn apply_text_style(&self, ops: &mut [DrawOp]) {
+        for op in ops.iter_mut().filter(|op| op.cell.is_visible()) {
+            op.cell.style = self.text_style;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::core::tools::{DrawOp, ToolId};
+    use crate::core::CellStyle;
+    use crate::wasm::bindings::AsciiEditor;
+
+    fn style_at(canvas: &AsciiEditor, x: i32) -> CellStyle {
+        canvas.state.grid.get(x, 0).unwrap().style
+    }
+
+    #[test]
+    fn test_selection_style_is_undoable() {
+        let mut canvas = AsciiEditor::new(6, 1);
+        canvas.commit_ops(&[DrawOp::new(0, 0, 'a'), DrawOp::new(2, 0, 'b')]);
+        canvas.set_selection_for_test(0, 0, 3, 0);
+
+        assert!(canvas.set_selection_style(CellStyle::BOLD.bits(), true));
+        assert_eq!(style_at(&canvas, 0), CellStyle::BOLD);
+        assert_eq!(style_at(&canvas, 1), CellStyle::NONE, "blanks stay plain");
+        assert_eq!(canvas.selection_style(), CellStyle::BOLD.bits());
+        assert!(!canvas.set_selection_style(CellStyle::BOLD.bits(), true));
+
+        canvas.toggle_style(CellStyle::UNDERLINE);
+        assert_eq!(style_at(&canvas, 2), CellStyle::BOLD | CellStyle::UNDERLINE);
+        canvas.toggle_style(CellStyle::BOLD);
+        assert_eq!(style_at(&canvas, 2), CellStyle::UNDERLINE);
+
+        assert!(canvas.undo());
+        assert!(canvas.undo());
+        assert!(canvas.undo());
+        assert_eq!(style_at(&canvas, 0), CellStyle::NONE);
+    }
+
+    #[test]
+    fn test_typed_text_takes_the_text_style() {
+        let mut canvas = AsciiEditor::new(8, 2);
+        canvas.set_tool_by_id_impl(ToolId::Text);
+        canvas.toggle_style(CellStyle::ITALIC);
+        assert_eq!(canvas.text_style(), CellStyle::ITALIC.bits());
+        let ctx = canvas.create_tool_context();
+        canvas.active_tool.on_pointer_down(1, 0, &ctx);
+        canvas.type_key('x');
+        assert_eq!(style_at(&canvas, 1), CellStyle::ITALIC);
+    }
+
+    #[test]
+    fn test_styles_reach_svg_and_html() {
+        let mut canvas = AsciiEditor::new(6, 1);
+        canvas.commit_ops(&[DrawOp::new(0, 0, 'a'), DrawOp::new(1, 0, 'b')]);
+        canvas.set_selection_for_test(1, 0, 1, 0);
+        canvas.set_selection_style(
+            (CellStyle::BOLD | CellStyle::ITALIC | CellStyle::HIGHLIGHT).bits(),
+            true,
+        );
+
+        let svg = canvas.export_svg();
+        assert!(svg.contains(r#"font-weight="bold" font-style="italic">b</text>"#));
+        assert!(svg.contains(r##"fill="#4d4215""##));
+
+        let html = canvas.export_html();
+        assert!(html.contains(
+            r#"a<span style="font-weight:bold;font-style:italic;background:#4d4215">b</span>"#
+        ));
+    }
+
+    #[test]
+    fn test_styles_render_into_the_pixel_buffer() {
+        let mut canvas = AsciiEditor::new(2, 1);
+        canvas.commit_ops(&[DrawOp::new(0, 0, '|'), DrawOp::new(1, 0, '|')]);
+        canvas.set_selection_for_test(1, 0, 1, 0);
+        canvas.set_selection_style((CellStyle::BOLD | CellStyle::HIGHLIGHT).bits(), true);
+        canvas.current_selection = None;
+        canvas.render_to_pixel_buffer();
+
+        let px = |x: usize, y: usize| canvas.pixel_buffer[(y * 16 + x) * 4..][..3].to_vec();
+        assert_eq!(px(5, 5), vec![0x1e, 0x1e, 0x1e], "plain cell: one column");
+        assert_eq!(px(13, 5), px(12, 5), "bold cell: two columns");
+        assert_eq!(px(8, 5), vec![0x4d, 0x42, 0x15], "highlight background");
+    }
+}
@@ -0,0 +1,145 @@
+//! Viewport API - a pixel buffer covering only the on-screen canvas.
+//!
+//! In viewport mode the buffer is the size of the canvas element and only the cells
+//! visible under the current zoom and pan are rasterized. Panning scrolls the pixels
+//! already in the buffer and draws just the strips it uncovers.
+
+use wasm_bindgen::prelude::*;
+
+use super::bindings::AsciiEditor;
+use super::font_api::MAX_BUFFER_PIXELS;
+use crate::render::PixelLayout;
+
+#[wasm_bindgen]
+impl AsciiEditor {
+    /// Render only what is on screen: the pixel buffer becomes `width`×`height`
+    /// device pixels (the canvas element's size) and JS draws it at 0, 0 without
+    /// scaling. Pass 0×0 to go back to a buffer covering the whole canvas. Returns
+    /// false, leaving the buffer as it was, for a size above `MAX_BUFFER_PIXELS`.
+    #[wasm_bindgen(js_name = setViewportSize)]
+    pub fn set_viewport_size(&mut self, width: usize, height: usize) -> bool {
+        let fits = width
+            .checked_mul(height)
+            .is_some_and(|pixels| pixels <= MAX_BUFFER_PIXELS);
+        if !fits {
+            return false;
+        }
+        let viewport = (width > 0 && height > 0).then_some((width, height));
+        if viewport != self.viewport {
+            self.viewport = viewport;
+            self.update_render_scale();
+            self.reset_pixel_buffer();
+        }
+        true
+    }
+
+    /// Size of the on-screen canvas in CSS pixels, which decides how much of the
+    /// grid is in view (for the minimap's viewport rectangle).
+    #[wasm_bindgen(js_name = setCanvasSize)]
+    pub fn set_canvas_size(&mut self, width: f64, height: f64) {
+        self.renderer.set_canvas_size(width, height);
+    }
+
+    /// Whether the pixel buffer covers only the viewport.
+    #[wasm_bindgen(getter = viewportMode)]
+    pub fn viewport_mode(&self) -> bool {
+        self.viewport.is_some()
+    }
+}
+
+impl AsciiEditor {
+    /// Where cells land in the pixel buffer for the current mode, zoom and pan.
+    pub(crate) fn pixel_layout(&self) -> PixelLayout {
+        let cell = (self.font_atlas.glyph_width, self.font_atlas.glyph_height);
+        match self.viewport {
+            Some((width, height)) => {
+                let (pan_x, pan_y) = self.renderer.pan();
+                PixelLayout {
+                    width,
+                    height,
+                    cell,
+                    origin: (
+                        (pan_x * self.device_scale).round() as i32,
+                        (pan_y * self.device_scale).round() as i32,
+                    ),
+                }
+            }
+            None => PixelLayout::canvas(self.state.grid.width(), self.state.grid.height(), cell),
+        }
+    }
+
+    /// Move the view. Only a viewport buffer has to be redrawn, and there the
+    /// existing pixels are scrolled rather than rendered again.
+    pub(crate) fn pan_to(&mut self, x: f64, y: f64) {
+        self.renderer.set_pan(x, y);
+        self.dirty_tracker.mark_panned();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::core::tools::DrawOp;
+    use crate::wasm::bindings::AsciiEditor;
+
+    const BG: [u8; 4] = [0x1e, 0x1e, 0x1e, 0xff];
+
+    fn frame(canvas: &mut AsciiEditor) -> Vec<u8> {
+        canvas.render_to_pixel_buffer();
+        canvas.clear_dirty_state();
+        canvas.pixel_buffer.clone()
+    }
+
+    #[test]
+    fn test_viewport_buffer_matches_the_screen() {
+        let mut canvas = AsciiEditor::new(400, 200);
+        canvas.set_viewport_size(64, 40);
+        assert_eq!(canvas.pixel_buffer.len(), 64 * 40 * 4);
+        assert_eq!((canvas.buffer_width(), canvas.buffer_height()), (64, 40));
+
+        // Cell (3, 1) shows up at the pan offset.
+        canvas.commit_ops(&[DrawOp::new(3, 1, '#')]);
+        canvas.set_pan(-16.0, -10.0);
+        let pixels = frame(&mut canvas);
+        let lit = |x: usize, y: usize| pixels[(y * 64 + x) * 4..][..4] != BG;
+        assert!((8..16).any(|x| (10..30).any(|y| lit(x, y))));
+        assert!(!lit(0, 0));
+
+        // Oversized viewports are refused rather than allocated.
+        assert!(!canvas.set_viewport_size(usize::MAX, 2));
+        assert!(!canvas.set_viewport_size(8192, 8192));
+        assert_eq!(canvas.pixel_buffer.len(), 64 * 40 * 4);
+
+        canvas.set_viewport_size(0, 0);
+        assert!(!canvas.viewport_mode());
+        assert_eq!(canvas.pixel_buffer.len(), 400 * 8 * 200 * 20 * 4);
+    }
+
+    #[test]
+    fn test_pan_scrolls_and_draws_exposed_strips() {
+        let mut canvas = AsciiEditor::new(20, 4);
+        canvas.set_grid_lines(true);
+        canvas.set_rulers(true);
+        for x in 0..20 {
+            canvas.commit_ops(&[DrawOp::new(x, x % 4, char::from(b'a' + x as u8))]);
+        }
+        canvas.set_viewport_size(48, 60);
+        frame(&mut canvas);
+        let full_renders = canvas.full_render_count;
+
+        canvas.set_pan(-21.0, 7.0);
+        let scrolled = frame(&mut canvas);
+        assert_eq!(canvas.full_render_count, full_renders);
+        assert_eq!(canvas.dirty_render_count, 1);
+
+        canvas.request_redraw();
+        let rendered = frame(&mut canvas);
+        assert_eq!(scrolled, rendered);
+
+        // The rulers stay on the viewport edge: column 10 is labelled at its new place
+        canvas.set_pan(-40.0, 0.0);
+        let pixels = frame(&mut canvas);
+        assert_eq!(canvas.get_rendered_regions(), vec![0, 0, 48, 60]);
+        let lit = |x: usize, y: usize| pixels[(y * 48 + x) * 4..][..3] != BG[..3];
+        assert!(lit(40, 5) && lit(43, 1) && !lit(41, 5));
+    }
+}
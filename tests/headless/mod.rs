@@ -0,0 +1,200 @@
+//! Golden-image tests: tool output rendered headlessly and compared with the PNGs
+//! in `tests/headless/golden/`.
+//!
+//! Run with `UPDATE_GOLDEN=1` to (re)write the golden images after an intended
+//! rendering change, then review the new PNGs before committing them.
+
+#![cfg(not(target_arch = "wasm32"))]
+
+use std::path::PathBuf;
+
+use ascii_canvas::core::grid::Grid;
+use ascii_canvas::core::tools::{
+    ArrowTool, BorderStyle, DiamondTool, RectangleTool, TextTool, Tool, ToolContext,
+};
+use ascii_canvas::core::{Cell, CellStyle, Document};
+use ascii_canvas::headless::{render_document, render_grid, Snapshot};
+use ascii_canvas::ui::Theme;
+use ascii_canvas::AsciiEditor;
+
+fn context(grid: &Grid) -> ToolContext {
+    ToolContext {
+        grid_width: grid.width(),
+        grid_height: grid.height(),
+        border_style: BorderStyle::Single,
+    }
+}
+
+/// Drag `tool` from `from` to `to` and commit the result to `grid`.
+fn drag(grid: &mut Grid, tool: &mut dyn Tool, from: (i32, i32), to: (i32, i32)) {
+    let ctx = context(grid);
+    tool.on_pointer_down(from.0, from.1, &ctx);
+    tool.on_pointer_move(to.0, to.1, &ctx);
+    for op in tool.on_pointer_up(to.0, to.1, &ctx).ops {
+        grid.set(op.x, op.y, op.cell);
+    }
+}
+
+/// Decode a PNG written by `Snapshot::to_png` (8-bit RGBA, unfiltered rows).
+fn decode_png(bytes: &[u8]) -> Snapshot {
+    let be = |at: usize| u32::from_be_bytes(bytes[at..at + 4].try_into().unwrap()) as usize;
+    let (mut pos, mut width, mut height, mut data) = (8, 0, 0, Vec::new());
+    while pos + 8 <= bytes.len() {
+        let len = be(pos);
+        let body = &bytes[pos + 8..pos + 8 + len];
+        match &bytes[pos + 4..pos + 8] {
+            b"IHDR" => (width, height) = (be(pos + 8), be(pos + 12)),
+            b"IDAT" => data.extend_from_slice(body),
+            _ => {}
+        }
+        pos += 12 + len;
+    }
+    let raw = miniz_oxide::inflate::decompress_to_vec_zlib(&data).expect("bad IDAT");
+    let pixels = raw
+        .chunks_exact(width * 4 + 1)
+        .flat_map(|row| {
+            assert_eq!(row[0], 0, "only unfiltered rows are supported");
+            row[1..].to_vec()
+        })
+        .collect();
+    Snapshot {
+        width,
+        height,
+        pixels,
+    }
+}
+
+/// Compare `snapshot` with `golden/<name>.png`, or write it with `UPDATE_GOLDEN=1`.
+fn assert_golden(name: &str, snapshot: &Snapshot) {
+    let path: PathBuf = [env!("CARGO_MANIFEST_DIR"), "tests/headless/golden"]
+        .iter()
+        .collect::<PathBuf>()
+        .join(format!("{name}.png"));
+    if std::env::var_os("UPDATE_GOLDEN").is_some() {
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(&path, snapshot.to_png()).unwrap();
+        return;
+    }
+    let bytes = std::fs::read(&path)
+        .unwrap_or_else(|_| panic!("missing {}; run with UPDATE_GOLDEN=1", path.display()));
+    let golden = decode_png(&bytes);
+    assert_eq!(
+        (snapshot.width, snapshot.height),
+        (golden.width, golden.height),
+        "{name}: size differs from the golden image"
+    );
+    let differing = snapshot
+        .pixels
+        .chunks_exact(4)
+        .zip(golden.pixels.chunks_exact(4))
+        .position(|(a, b)| a != b);
+    if let Some(i) = differing {
+        let (x, y) = (i % snapshot.width, i / snapshot.width);
+        panic!(
+            "{name}: pixel ({x}, {y}) is {:?}, golden has {:?}",
+            snapshot.pixel(x, y),
+            golden.pixel(x, y)
+        );
+    }
+}
+
+#[test]
+fn test_rectangle_and_arrow() {
+    let mut grid = Grid::new(16, 6);
+    drag(&mut grid, &mut RectangleTool::new(), (1, 1), (6, 4));
+    drag(&mut grid, &mut ArrowTool::new(), (7, 2), (14, 2));
+    assert_golden(
+        "rectangle_and_arrow",
+        &render_grid(&grid, &Theme::figma_dark()),
+    );
+}
+
+#[test]
+fn test_diamond_light_theme() {
+    let mut grid = Grid::new(12, 7);
+    drag(&mut grid, &mut DiamondTool::new(), (1, 0), (10, 6));
+    assert_golden("diamond_light", &render_grid(&grid, &Theme::light()));
+}
+
+#[test]
+fn test_styled_text() {
+    let mut grid = Grid::new(10, 2);
+    let mut tool = TextTool::new();
+    let ctx = context(&grid);
+    tool.on_pointer_down(1, 0, &ctx);
+    for ch in "Hello".chars() {
+        for op in tool.on_key(ch, &ctx).ops {
+            grid.set(op.x, op.y, op.cell);
+        }
+    }
+    grid.set(1, 1, Cell::with_style('B', CellStyle::BOLD));
+    grid.set(2, 1, Cell::with_style('I', CellStyle::ITALIC));
+    grid.set(3, 1, Cell::with_style('U', CellStyle::UNDERLINE));
+    grid.set(4, 1, Cell::with_style('H', CellStyle::HIGHLIGHT));
+    assert_golden("styled_text", &render_grid(&grid, &Theme::figma_dark()));
+}
+
+#[test]
+fn test_document_with_layers() {
+    let doc = "\
+ascii-canvas 3
+
+=== page \"Main\" 12x3 ===
+=== layer \"Base\" visible ===
++--------+
+|  box   |
++--------+
+=== end ===
+=== layer \"Notes\" visible at 2,1 guide ===
+note
+=== end ===
+";
+    let parsed = Document::parse(doc).unwrap();
+    let page = &parsed.pages[0];
+    assert_eq!(page.layers.len(), 2);
+    let notes = &page.layers[1];
+    assert_eq!((notes.guide, notes.x, notes.y), (true, 2, 1));
+    for layer in &page.layers {
+        let (_, warnings) = layer.to_grid(12, 3);
+        assert!(warnings.is_empty(), "{}: {warnings:?}", layer.name);
+    }
+    let snapshot = render_document(doc, &Theme::figma_dark()).unwrap();
+    assert_eq!((snapshot.width, snapshot.height), (96, 60));
+    assert_golden("document_with_layers", &snapshot);
+}
+
+#[test]
+fn test_headless_render_matches_the_editor() {
+    let doc = "\
+ascii-canvas 3
+
+=== page \"Main\" 12x4 ===
+=== layer \"Base\" visible ===
+
+ # b
+=== styles ===
+
+...9
+=== end ===
+=== layer \"Top\" visible guide at 8,2 opacity 0.5 ===
+g
+=== end ===
+";
+    let mut editor = AsciiEditor::new(12, 4);
+    assert!(editor.try_load_document(doc).unwrap().is_empty());
+    editor.render_to_pixel_buffer();
+
+    // The editor's own serialization renders to the same pixels
+    let saved = editor.serialize_document_text();
+    let snapshot = render_document(&saved, &Theme::figma_dark()).unwrap();
+    assert_eq!((snapshot.width, snapshot.height), (96, 80));
+    assert!(snapshot.pixels == editor.pixel_buffer());
+}
+
+#[test]
+fn test_png_round_trip() {
+    let mut grid = Grid::new(3, 1);
+    grid.set_char(1, 0, '@');
+    let snapshot = render_grid(&grid, &Theme::figma_dark());
+    assert_eq!(decode_png(&snapshot.to_png()), snapshot);
+}
@@ -6,8 +6,20 @@ export const FONT_SIZE = 14;
 export const MIN_COLS = 40;
 export const MIN_ROWS = 20;
 export const USE_PIXEL_BUFFER = true;
+/** Size the pixel buffer to the on-screen canvas instead of the whole grid. */
+export const USE_VIEWPORT_BUFFER = true;
 export const GLYPH_WIDTH = 8;
 export const GLYPH_HEIGHT = 20;
+/** Largest minimap size in pixels, and how often it is refreshed. */
+export const MINIMAP_MAX_WIDTH = 160;
+export const MINIMAP_MAX_HEIGHT = 120;
+export const MINIMAP_INTERVAL_MS = 250;
+
+/** CellStyle bits, as carried by render commands and the style API. */
+export const STYLE_BOLD = 1;
+export const STYLE_ITALIC = 2;
+export const STYLE_UNDERLINE = 4;
+export const STYLE_HIGHLIGHT = 8;
 
 export const TOOL_INFO: Record<string, { instruction: string; cursor: string; shortcut: string }> = {
     select: { instruction: 'Click to select, drag selection to move, or Del to erase', cursor: 'default', shortcut: 'V' },
@@ -28,3 +40,7 @@ export const LINE_DIRECTIONS = ['auto', 'horizontal', 'vertical'];
 export const AUTOSAVE_KEY = 'ascii-canvas-autosave';
 /** localStorage key for theme preference */
 export const THEME_KEY = 'ascii-canvas-theme';
+/** localStorage key for the preview style (tinted, dashed-outline, ghost, diff) */
+export const PREVIEW_MODE_KEY = 'ascii-canvas-preview-mode';
+/** localStorage key for key bindings changed from the defaults (JSON) */
+export const SHORTCUTS_KEY = 'ascii-canvas-shortcuts';
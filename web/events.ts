@@ -5,14 +5,15 @@
 import { state } from './state.js';
 import {
     BORDER_STYLES,
-    TOOL_INFO,
     MIN_COLS,
     MIN_ROWS,
+    PREVIEW_MODE_KEY,
 } from './constants.js';
 import { copyAsciiToClipboard, copyToClipboard as copySelectionAware } from './clipboard.js';
 import { createAutoSaveScheduler, downloadDocument, openDocumentPicker } from './persistence.js';
 import { exportPng } from './exportPng.js';
 import { exportSvg } from './exportSvg.js';
+import { matchShortcut, resetShortcuts } from './shortcuts.js';
 import {
     requestRender,
     debouncedResizeCanvas,
@@ -185,13 +186,13 @@ export function handleMobileInput(e: Event): void {
         const value = e.target.value;
 
         if (value.length === 0) {
-            const result = state.editor.onKeyDown('Backspace', false, false);
+            const result = state.editor.onKeyDown('Backspace', false, false, false, false);
             handleEventResult(result);
             e.target.value = ' ';
         } else if (value.length > 1) {
             const newChars = value.substring(1);
             for (const char of newChars) {
-                const result = state.editor.onKeyDown(char, false, false);
+                const result = state.editor.onKeyDown(char, false, false, false, false);
                 handleEventResult(result);
             }
             e.target.value = ' ';
@@ -199,7 +200,24 @@ export function handleMobileInput(e: Event): void {
     }
 }
 
+export function handleMinimapPointerDown(e: PointerEvent): void {
+    if (!state.editor || !state.minimapCanvas) return;
+    e.preventDefault();
+    e.stopPropagation();
+    const rect = state.minimapCanvas.getBoundingClientRect();
+    // The minimap may be shown at a different size than its pixel buffer.
+    const x = (e.clientX - rect.left) * state.minimapCanvas.width / rect.width;
+    const y = (e.clientY - rect.top) * state.minimapCanvas.height / rect.height;
+    state.editor.minimapClick(x, y);
+    state.lastMinimapRender = 0;
+    requestRender();
+}
+
 export function handlePointerLeave(): void {
+    if (state.editor) {
+        state.editor.onPointerLeave();
+        requestRender();
+    }
     const hasTextCursor = state.editor && state.editor.tool.toLowerCase() === 'text' && typeof state.editor.textCursorPosition === 'function' && state.editor.textCursorPosition() !== null;
     if (!hasTextCursor && state.cursorIndicator) {
         state.cursorIndicator.classList.add('hidden');
@@ -243,58 +261,41 @@ export function handlePasteEvent(e: ClipboardEvent): void {
 
 export function handleKeyDown(e: KeyboardEvent): void {
     if (!state.editor) return;
-    const key = e.key;
-    const ctrl = e.ctrlKey || e.metaKey;
-    const shift = e.shiftKey;
+    const { ctrlKey: ctrl, shiftKey: shift, altKey: alt, metaKey: meta } = e;
+    const { key, action } = matchShortcut(state.editor, e);
 
-    if (['r', 'l', 'a', 'd', 't', 'f', 'v', 'e', 'b', ' ', 'escape', '?'].includes(key.toLowerCase()) && !ctrl) {
-        e.preventDefault();
+    // Paste arrives as a paste event, which carries the OS clipboard
+    if (action === 'paste') {
+        return;
     }
-    if (ctrl && ['z', 'y', 'c', 'a', 'x'].includes(key.toLowerCase())) {
+    if (action) {
         e.preventDefault();
     }
 
-    if (ctrl && key.toLowerCase() === 'v') {
-        return;
-    }
-
-    const result = state.editor.onKeyDown(key, ctrl, shift);
+    const result = state.editor.onKeyDown(key, ctrl, shift, alt, meta);
 
-    if (key === ' ' && !ctrl && !shift && state.canvasContainer) {
+    if (action === 'pan_mode' && state.canvasContainer) {
         state.canvasContainer.classList.add('panning');
     }
 
     handleEventResult(result);
 
-    const isTextTool = state.editor.tool.toLowerCase() === 'text';
-
-    if (!isTextTool) {
-        if (key.toLowerCase() === 'b' && !ctrl && !shift) {
-            cycleBorderStyle();
-        }
-
-        if ((key === '0' || key === ')') && !ctrl) {
-            if (shift || key === ')') {
-                fitZoom();
-            } else {
-                resetZoom();
-            }
-        }
-
-        if (key === '?' || (key === '/' && shift)) {
-            showShortcutsModal();
-        }
-
-        const lowerKey = key.toLowerCase();
-        for (const [toolName, info] of Object.entries(TOOL_INFO)) {
-            if (info.shortcut.toLowerCase() === lowerKey && !ctrl && !shift) {
-                setTool(toolName);
-                break;
-            }
-        }
-    } else if (key === '?' || (key === '/' && shift)) {
+    if (action === 'help') {
         showShortcutsModal();
     }
+    // Other page shortcuts would interrupt typing
+    if (state.editor.tool.toLowerCase() === 'text') {
+        return;
+    }
+    if (action === 'cycle_border') {
+        cycleBorderStyle();
+    } else if (action === 'zoom_reset') {
+        resetZoom();
+    } else if (action === 'zoom_fit') {
+        fitZoom();
+    } else if (action?.startsWith('tool_')) {
+        setTool(action.slice('tool_'.length));
+    }
 }
 
 export function handleKeyUp(e: KeyboardEvent): void {
@@ -404,6 +405,7 @@ export function setupEventListeners(): void {
     state.canvas.addEventListener('pointermove', handlePointerMove);
     state.canvas.addEventListener('pointerup', handlePointerUp);
     state.canvas.addEventListener('pointerleave', handlePointerLeave);
+    state.minimapCanvas?.addEventListener('pointerdown', handleMinimapPointerDown);
 
     state.canvas.addEventListener('touchstart', handleTouchStart, { passive: false });
     state.canvas.addEventListener('touchmove', handleTouchMove, { passive: false });
@@ -510,6 +512,20 @@ export function setupEventListeners(): void {
         });
     }
 
+    wireOptionalButton('reset-shortcuts-btn', resetShortcuts);
+
+    if (state.previewModeSelect) {
+        state.previewModeSelect.addEventListener('change', () => {
+            if (state.editor && state.previewModeSelect) {
+                const mode = state.previewModeSelect.value;
+                if (state.editor.setPreviewMode(mode)) {
+                    localStorage.setItem(PREVIEW_MODE_KEY, mode);
+                    requestRender();
+                }
+            }
+        });
+    }
+
     if (state.directionBtns) {
         state.directionBtns.forEach(btn => {
             btn.addEventListener('mousedown', (e) => { e.preventDefault(); });
@@ -602,7 +618,11 @@ export function setupEventListeners(): void {
             state.editor.requestRedraw();
             requestRender();
             requestAnimationFrame(() => {
-                exportPng(state.offscreenCanvas, state.canvas, showToast);
+                exportPng(
+                    state.editor?.viewportMode ? null : state.offscreenCanvas,
+                    state.canvas,
+                    showToast
+                );
             });
         }
         if (state.canvas) state.canvas.focus();
@@ -790,7 +810,11 @@ export function setupEventListeners(): void {
             state.editor.requestRedraw();
             requestRender();
             requestAnimationFrame(() => {
-                exportPng(state.offscreenCanvas, state.canvas, showToast);
+                exportPng(
+                    state.editor?.viewportMode ? null : state.offscreenCanvas,
+                    state.canvas,
+                    showToast
+                );
             });
         }
         closeDrawer();
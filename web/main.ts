@@ -7,7 +7,7 @@
 import init, { AsciiEditor } from './pkg/ascii_canvas.js';
 import { logger } from './logger.js';
 import type { AsciiEditor as AsciiEditorType } from './types.js';
-import { FONT_SIZE, TOOL_INFO, THEME_KEY } from './constants.js';
+import { FONT_SIZE, TOOL_INFO, THEME_KEY, PREVIEW_MODE_KEY } from './constants.js';
 import { getElement } from './utils.js';
 import { tryRestoreAutoSave } from './persistence.js';
 import { state } from './state.js';
@@ -25,6 +25,7 @@ import {
     syncGridInputs,
 } from './ui.js';
 import { setupEventListeners } from './events.js';
+import { renderShortcutList, restoreShortcuts } from './shortcuts.js';
 
 // Expose editor for testing
 declare global {
@@ -66,6 +67,7 @@ async function initialize() {
         state.loadingOverlay = getElement('loading');
         state.canvasContainer = getElement('canvas-container');
         state.cursorIndicator = getElement('cursor-indicator');
+        state.minimapCanvas = getElement<HTMLCanvasElement>('minimap');
         state.gridSizeEl = getElement('grid-size');
         state.cursorPosEl = getElement('cursor-pos');
         state.zoomLevelEl = getElement('zoom-level');
@@ -82,6 +84,7 @@ async function initialize() {
         state.clearBtn = getElement<HTMLButtonElement>('clear-btn');
         state.helpBtn = getElement<HTMLButtonElement>('help-btn');
         state.borderStyleSelect = getElement<HTMLSelectElement>('border-style');
+        state.previewModeSelect = document.getElementById('preview-mode') as HTMLSelectElement | null;
         state.toolButtons = document.querySelectorAll('.tool-btn');
         state.zoomFitBtn = getElement<HTMLButtonElement>('zoom-fit');
         state.zoomResetBtn = getElement<HTMLButtonElement>('zoom-reset');
@@ -157,6 +160,18 @@ async function initialize() {
             }
         }
 
+        // Preview style survives theme switches, so restore it after the theme
+        const savedPreviewMode = localStorage.getItem(PREVIEW_MODE_KEY);
+        if (savedPreviewMode && !state.editor.setPreviewMode(savedPreviewMode)) {
+            localStorage.removeItem(PREVIEW_MODE_KEY);
+        }
+        if (state.previewModeSelect) {
+            state.previewModeSelect.value = state.editor.previewMode;
+        }
+
+        restoreShortcuts(state.editor);
+        renderShortcutList();
+
         // Restore auto-saved document if present (locks grid so resize cannot crop it).
         if (tryRestoreAutoSave(state.editor)) {
             logger.info('Restored auto-saved diagram');
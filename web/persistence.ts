@@ -7,22 +7,35 @@ import { logger } from './logger.js';
 import type { AsciiEditor } from './types.js';
 import type { ToastFn } from './clipboard.js';
 
-/** Save current document JSON to localStorage. */
+/** Save current document JSON to localStorage (cells compressed to stay small). */
 export function autoSave(editor: AsciiEditor): void {
     try {
-        const json = editor.serializeDocument();
+        const json = editor.serializeDocument('rle+deflate');
         localStorage.setItem(AUTOSAVE_KEY, json);
     } catch (err) {
         logger.warn('Auto-save failed:', err);
     }
 }
 
+/** Non-fatal load warning returned by `loadDocument`. */
+export interface LoadWarning {
+    code: string;
+    message: string;
+}
+
+/** Message of a `loadDocument` error (a JS `Error` with a `code` property). */
+export function describeLoadError(err: unknown): string {
+    return err instanceof Error ? err.message : String(err);
+}
+
 /** Load autosaved document if present. Returns true if restored. */
 export function tryRestoreAutoSave(editor: AsciiEditor): boolean {
     try {
         const json = localStorage.getItem(AUTOSAVE_KEY);
         if (!json) return false;
-        return editor.loadDocument(json);
+        const warnings = editor.loadDocument(json) as LoadWarning[];
+        for (const w of warnings) logger.warn(`Auto-restore: ${w.message}`);
+        return true;
     } catch (err) {
         logger.warn('Auto-restore failed:', err);
         return false;
@@ -62,11 +75,18 @@ export function openDocumentPicker(
         const reader = new FileReader();
         reader.onload = () => {
             const text = typeof reader.result === 'string' ? reader.result : '';
-            if (editor.loadDocument(text)) {
-                showToast(`Loaded ${file.name}`);
+            try {
+                const warnings = editor.loadDocument(text) as LoadWarning[];
+                for (const w of warnings) logger.warn(`${file.name}: ${w.message}`);
+                showToast(
+                    warnings.length > 0
+                        ? `Loaded ${file.name} with ${warnings.length} warning(s): ${warnings[0].message}`
+                        : `Loaded ${file.name}`,
+                );
                 onLoaded();
-            } else {
-                showToast('Invalid diagram file', true);
+            } catch (err) {
+                logger.warn('Load failed:', err);
+                showToast(`Could not open ${file.name}: ${describeLoadError(err)}`, true);
             }
         };
         reader.onerror = () => {
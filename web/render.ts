@@ -8,8 +8,16 @@ import {
     MIN_COLS,
     MIN_ROWS,
     USE_PIXEL_BUFFER,
+    USE_VIEWPORT_BUFFER,
     GLYPH_WIDTH,
     GLYPH_HEIGHT,
+    STYLE_BOLD,
+    STYLE_ITALIC,
+    STYLE_UNDERLINE,
+    STYLE_HIGHLIGHT,
+    MINIMAP_MAX_WIDTH,
+    MINIMAP_MAX_HEIGHT,
+    MINIMAP_INTERVAL_MS,
 } from './constants.js';
 import { logger } from './logger.js';
 import { debounce } from './utils.js';
@@ -81,6 +89,12 @@ export function resizeCanvas(): void {
     state.ctx.scale(dpr, dpr);
 
     measureFont(state.editor);
+    state.editor?.setCanvasSize(rect.width, rect.height);
+    state.editor?.setDeviceScale(dpr);
+    if (USE_VIEWPORT_BUFFER && !state.editor?.setViewportSize(state.canvas.width, state.canvas.height)) {
+        // Too large for a viewport buffer: fall back to the whole-canvas one
+        state.editor?.setViewportSize(0, 0);
+    }
 
     if (state.editor) {
         if (!state.gridSizeLocked) {
@@ -110,14 +124,28 @@ export function requestRender(): void {
 // Bind to state
 state.requestRender = requestRender;
 
-export function render(): void {
+export function render(time?: number): void {
     if (!state.editor || !state.canvas || !state.ctx) return;
     state.animationFrameId = null;
 
     if (state.wasmMemory) {
-        const bufferWidth = state.editor.width * 8;
-        const bufferHeight = state.editor.height * 20;
+        // Caret blink, marching ants and hover outline are drawn by the renderer.
+        state.editor.setFrameTime(time ?? performance.now());
+
+        // The buffer is rendered at device scale × zoom, so it is drawn without resampling.
+        const bufferWidth = state.editor.bufferWidth;
+        const bufferHeight = state.editor.bufferHeight;
+
+        if (
+            state.offscreenCanvas &&
+            (state.offscreenCanvas.width !== bufferWidth ||
+                state.offscreenCanvas.height !== bufferHeight)
+        ) {
+            state.offscreenCanvas = null;
+            state.offscreenCtx = null;
+        }
 
+        const freshCanvas = !state.offscreenCanvas;
         if (!state.offscreenCanvas) {
             state.offscreenCanvas = document.createElement('canvas');
             state.offscreenCanvas.width = bufferWidth;
@@ -133,28 +161,50 @@ export function render(): void {
             const data = new Uint8ClampedArray(state.wasmMemory.buffer, ptr, len);
             const imageData = new ImageData(data, bufferWidth, bufferHeight);
 
-            state.offscreenCtx?.putImageData(imageData, 0, 0);
+            // Copy only the areas that changed; a new offscreen canvas needs everything.
+            const regions = state.editor.getRenderedRegions();
+            if (freshCanvas) {
+                state.offscreenCtx?.putImageData(imageData, 0, 0);
+            } else {
+                for (let i = 0; i + 3 < regions.length; i += 4) {
+                    state.offscreenCtx?.putImageData(
+                        imageData,
+                        0,
+                        0,
+                        regions[i],
+                        regions[i + 1],
+                        regions[i + 2],
+                        regions[i + 3]
+                    );
+                }
+            }
             state.editor.clearDirtyState();
         }
 
         const dpr = window.devicePixelRatio || 1;
         state.ctx.save();
         state.ctx.setTransform(1, 0, 0, 1, 0, 0);
-        state.ctx.scale(dpr, dpr);
-
-        state.ctx.fillStyle = getComputedThemeColor('--bg', '#1e1e1e');
-        state.ctx.fillRect(0, 0, state.canvas.width / dpr, state.canvas.height / dpr);
-
-        state.ctx.imageSmoothingEnabled = false;
 
-        const pan = state.editor.pan as number[] | Float64Array;
-        state.ctx.drawImage(
-            state.offscreenCanvas,
-            pan[0],
-            pan[1],
-            bufferWidth * state.editor.zoom,
-            bufferHeight * state.editor.zoom
-        );
+        if (state.editor.viewportMode) {
+            // The buffer already is the visible canvas, pan and zoom included.
+            state.ctx.drawImage(state.offscreenCanvas, 0, 0);
+        } else {
+            state.ctx.scale(dpr, dpr);
+
+            state.ctx.fillStyle = getComputedThemeColor('--bg', '#1e1e1e');
+            state.ctx.fillRect(0, 0, state.canvas.width / dpr, state.canvas.height / dpr);
+
+            state.ctx.imageSmoothingEnabled = false;
+
+            const pan = state.editor.pan as number[] | Float64Array;
+            state.ctx.drawImage(
+                state.offscreenCanvas,
+                pan[0],
+                pan[1],
+                state.editor.width * state.editor.glyphWidth * state.editor.zoom,
+                state.editor.height * state.editor.glyphHeight * state.editor.zoom
+            );
+        }
         state.ctx.restore();
     } else {
         const commands = state.editor.getDirtyRenderCommands();
@@ -164,6 +214,7 @@ export function render(): void {
     }
 
     updateIndicator();
+    renderMinimap(time ?? performance.now());
 
     if (state.zoomLevelEl) {
         state.zoomLevelEl.textContent = `${Math.round(state.editor.zoom * 100)}%`;
@@ -175,7 +226,7 @@ export function render(): void {
         state.dirtyRendersEl.textContent = state.editor.dirtyRenderCount.toString();
     }
 
-    if (state.editor.needsRedraw) {
+    if (state.editor.needsRedraw || (state.wasmMemory && state.editor.animating)) {
         requestRender();
     }
 }
@@ -195,13 +246,11 @@ export function executeRenderCommand(cmd: RenderCommand): void {
             break;
 
         case 'DrawChar':
-            state.ctx.fillStyle = getComputedThemeColor('--fg', '#d4d4d4');
-            state.ctx.fillText(cmd.char as string, cmd.x as number, cmd.y as number);
+            drawStyledChar(cmd, getComputedThemeColor('--fg', '#d4d4d4'));
             break;
 
         case 'DrawPreviewChar':
-            state.ctx.fillStyle = 'rgba(86, 156, 214, 0.7)';
-            state.ctx.fillText(cmd.char as string, cmd.x as number, cmd.y as number);
+            drawStyledChar(cmd, getComputedThemeColor('--preview', 'rgba(86, 156, 214, 0.7)'));
             break;
 
         case 'DrawRect':
@@ -235,8 +284,66 @@ export function executeRenderCommand(cmd: RenderCommand): void {
     }
 }
 
+/** Refresh the minimap, at most every `MINIMAP_INTERVAL_MS`. */
+function renderMinimap(now: number): void {
+    const canvas = state.minimapCanvas;
+    if (!state.editor || !state.wasmMemory || !canvas) return;
+    if (now - state.lastMinimapRender < MINIMAP_INTERVAL_MS) return;
+    state.lastMinimapRender = now;
+
+    state.editor.renderMinimap(MINIMAP_MAX_WIDTH, MINIMAP_MAX_HEIGHT);
+    const width = state.editor.minimapWidth;
+    const height = state.editor.minimapHeight;
+    if (width === 0 || height === 0) return;
+    if (canvas.width !== width || canvas.height !== height) {
+        canvas.width = width;
+        canvas.height = height;
+    }
+    const ptr = state.editor.getMinimapPtr();
+    const len = state.editor.getMinimapLen();
+    const data = new Uint8ClampedArray(state.wasmMemory.buffer, ptr, len);
+    canvas.getContext('2d')?.putImageData(new ImageData(data, width, height), 0, 0);
+}
+
+/** Draw a character with its cell style: highlight behind, bold/italic font, underline. */
+function drawStyledChar(cmd: RenderCommand, color: string): void {
+    const ctx = state.ctx;
+    if (!ctx) return;
+    const style = cmd.style as number || 0;
+    const x = cmd.x as number;
+    const y = cmd.y as number;
+    const scale = cmd.scale as number || 1;
+    const cellW = state.charWidth * scale;
+    const cellH = state.lineHeight * scale;
+
+    if (style & STYLE_HIGHLIGHT) {
+        ctx.fillStyle = getComputedThemeColor('--highlight', '#4d4215');
+        ctx.fillRect(x, y, cellW, cellH);
+    }
+
+    const font = ctx.font;
+    if (style & (STYLE_BOLD | STYLE_ITALIC)) {
+        const weight = style & STYLE_BOLD ? 'bold ' : '';
+        const slant = style & STYLE_ITALIC ? 'italic ' : '';
+        ctx.font = `${slant}${weight}${font}`;
+    }
+    ctx.fillStyle = color;
+    ctx.fillText(cmd.char as string, x, y);
+    ctx.font = font;
+
+    if (style & STYLE_UNDERLINE) {
+        const thickness = Math.max(1, Math.round(cellH / 20));
+        ctx.fillRect(x, y + cellH - 2 * thickness, cellW, thickness);
+    }
+}
+
 export function updateCursorIndicator(gridX: number, gridY: number): void {
     if (!state.editor || !state.cursorIndicator) return;
+    if (state.wasmMemory) {
+        // The pixel buffer draws the hover outline itself.
+        state.cursorIndicator.classList.add('hidden');
+        return;
+    }
     const zoom = state.editor.zoom;
     const pan = state.editor.pan;
 
@@ -266,6 +373,12 @@ export function updateCursorIndicator(gridX: number, gridY: number): void {
 
 export function updateIndicator(): void {
     if (!state.editor || !state.cursorIndicator) return;
+    if (state.wasmMemory) {
+        // The pixel buffer draws the caret itself.
+        state.cursorIndicator.classList.remove('caret');
+        state.cursorIndicator.classList.add('hidden');
+        return;
+    }
 
     let textPos: number[] | Int32Array | null = null;
     if (state.editor.tool.toLowerCase() === 'text' && typeof state.editor.textCursorPosition === 'function') {
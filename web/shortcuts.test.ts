@@ -0,0 +1,46 @@
+import { describe, it, expect, vi } from 'vitest';
+import { chordFromEvent, matchShortcut } from './shortcuts.js';
+import type { AsciiEditor } from './types.js';
+
+describe('chordFromEvent', () => {
+    it('names modifiers in a fixed order', () => {
+        const e = new KeyboardEvent('keydown', { key: 'z', code: 'KeyZ', ctrlKey: true, shiftKey: true });
+        expect(chordFromEvent(e)).toBe('Ctrl+Shift+Z');
+    });
+
+    it('uses the physical key with Alt and leaves Shift out of symbols', () => {
+        expect(chordFromEvent(new KeyboardEvent('keydown', { key: '®', code: 'KeyR', altKey: true }))).toBe('Alt+R');
+        expect(chordFromEvent(new KeyboardEvent('keydown', { key: '?', code: 'Slash', shiftKey: true }))).toBe('?');
+        expect(chordFromEvent(new KeyboardEvent('keydown', { key: ' ', code: 'Space' }))).toBe('Space');
+    });
+
+    it('waits while only modifiers are down', () => {
+        expect(chordFromEvent(new KeyboardEvent('keydown', { key: 'Control', ctrlKey: true }))).toBeNull();
+    });
+});
+
+describe('matchShortcut', () => {
+    it('falls back to the typed character when Alt+key is unbound', () => {
+        const shortcutAction = vi.fn((key: string) => (key === 'R' ? 'tool_rectangle' : undefined));
+        const editor = { shortcutAction } as unknown as AsciiEditor;
+
+        const bound = new KeyboardEvent('keydown', { key: '®', code: 'KeyR', altKey: true });
+        expect(matchShortcut(editor, bound)).toEqual({ key: 'R', action: 'tool_rectangle' });
+
+        const typed = new KeyboardEvent('keydown', { key: 'é', code: 'KeyE', altKey: true });
+        expect(matchShortcut(editor, typed)).toEqual({ key: 'é', action: undefined });
+    });
+
+    it('tries the digit under Shift before the layout symbol', () => {
+        const shortcutAction = vi.fn((key: string, _ctrl: boolean, shift: boolean) =>
+            key === '0' && shift ? 'zoom_fit' : undefined
+        );
+        const editor = { shortcutAction } as unknown as AsciiEditor;
+
+        const german = new KeyboardEvent('keydown', { key: '=', code: 'Digit0', shiftKey: true });
+        expect(matchShortcut(editor, german)).toEqual({ key: '0', action: 'zoom_fit' });
+
+        const bang = new KeyboardEvent('keydown', { key: '!', code: 'Digit1', shiftKey: true });
+        expect(matchShortcut(editor, bang)).toEqual({ key: '!', action: undefined });
+    });
+});
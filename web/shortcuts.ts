@@ -0,0 +1,144 @@
+/**
+ * Key bindings - matching key events to editor shortcuts, rebinding them from the
+ * sidebar list, and keeping the user's bindings in localStorage.
+ */
+
+import { state } from './state.js';
+import { SHORTCUTS_KEY } from './constants.js';
+import { logger } from './logger.js';
+import { showToast } from './ui.js';
+import type { AsciiEditor } from './types.js';
+
+interface ShortcutInfo {
+    action: string;
+    description: string;
+    chords: string[];
+}
+
+const MODIFIER_KEYS = ['Control', 'Shift', 'Alt', 'Meta', 'AltGraph'];
+
+/**
+ * The key a shortcut sees for this event. With Option/Alt held, macOS reports the
+ * composed character (Alt+R gives "®"), and Shift+digit gives a symbol that depends
+ * on the layout, so the physical key is tried first.
+ */
+export function matchShortcut(editor: AsciiEditor, e: KeyboardEvent): { key: string; action: string | undefined } {
+    const { ctrlKey, shiftKey, altKey, metaKey } = e;
+    const physical = /^(?:Key|Digit)(.)$/.exec(e.code)?.[1];
+    const shiftedDigit = shiftKey && e.code.startsWith('Digit');
+    if (((altKey && !ctrlKey) || shiftedDigit) && physical) {
+        const action = editor.shortcutAction(physical, ctrlKey, shiftKey, altKey, metaKey);
+        if (action) return { key: physical, action };
+    }
+    return { key: e.key, action: editor.shortcutAction(e.key, ctrlKey, shiftKey, altKey, metaKey) };
+}
+
+/** The chord for a key event, e.g. `Ctrl+Shift+Z`; null while only modifiers are down. */
+export function chordFromEvent(e: KeyboardEvent): string | null {
+    if (MODIFIER_KEYS.includes(e.key)) return null;
+    const physical = /^(?:Key|Digit)(.)$/.exec(e.code)?.[1];
+    let key = e.altKey && physical ? physical : e.key;
+    if (key === ' ') key = 'Space';
+    // Symbols already reflect Shift ("?" rather than Shift+/)
+    const symbol = key.length === 1 && key.toLowerCase() === key.toUpperCase();
+    const parts: string[] = [];
+    if (e.ctrlKey) parts.push('Ctrl');
+    if (e.shiftKey && !symbol) parts.push('Shift');
+    if (e.altKey) parts.push('Alt');
+    if (e.metaKey) parts.push('Meta');
+    parts.push(key.length === 1 ? key.toUpperCase() : key);
+    return parts.join('+');
+}
+
+/** Apply the bindings saved in localStorage, dropping them if they no longer load. */
+export function restoreShortcuts(editor: AsciiEditor): void {
+    const saved = localStorage.getItem(SHORTCUTS_KEY);
+    if (!saved) return;
+    try {
+        editor.loadShortcutBindings(saved);
+    } catch (error) {
+        logger.warn('Discarding saved shortcuts:', error);
+        localStorage.removeItem(SHORTCUTS_KEY);
+    }
+}
+
+function saveShortcuts(editor: AsciiEditor): void {
+    const bindings = editor.shortcutBindings;
+    if (bindings === '{}') {
+        localStorage.removeItem(SHORTCUTS_KEY);
+    } else {
+        localStorage.setItem(SHORTCUTS_KEY, bindings);
+    }
+}
+
+/** Fill the sidebar shortcut list; clicking a key records a new chord for it. */
+export function renderShortcutList(): void {
+    const list = document.getElementById('shortcut-bindings');
+    if (!list || !state.editor) return;
+    const shortcuts = state.editor.getShortcuts() as ShortcutInfo[];
+    list.replaceChildren(...shortcuts.map((info) => shortcutItem(info, shortcuts)));
+}
+
+function shortcutItem(info: ShortcutInfo, all: ShortcutInfo[]): HTMLElement {
+    const item = document.createElement('div');
+    item.className = 'shortcut-item';
+    const button = document.createElement('button');
+    button.type = 'button';
+    button.className = 'shortcut-bind';
+    const keys = info.chords.join(' / ') || 'Unbound';
+    button.title = `Click, then press the new keys for ${info.description}`;
+    button.setAttribute('aria-label', `${info.description}: ${keys}. Rebind`);
+    const kbd = document.createElement('kbd');
+    kbd.textContent = keys;
+    button.append(kbd);
+    button.addEventListener('click', () => {
+        kbd.textContent = 'Press keys…';
+        recordChord((chord) => bindChord(info, chord, all));
+    });
+    const label = document.createElement('span');
+    label.textContent = info.description;
+    item.append(button, label);
+    return item;
+}
+
+/** Capture the next chord; Escape cancels. */
+function recordChord(done: (chord: string | null) => void): void {
+    const onKey = (e: KeyboardEvent): void => {
+        const chord = chordFromEvent(e);
+        if (chord === null) return;
+        e.preventDefault();
+        e.stopPropagation();
+        window.removeEventListener('keydown', onKey, true);
+        done(chord === 'Escape' ? null : chord);
+    };
+    window.addEventListener('keydown', onKey, true);
+}
+
+function bindChord(info: ShortcutInfo, chord: string | null, all: ShortcutInfo[]): void {
+    const editor = state.editor;
+    if (editor && chord) {
+        const conflict = editor.shortcutConflict(info.action, chord);
+        if (conflict) {
+            const owner = all.find((s) => s.action === conflict)?.description ?? conflict;
+            showToast(`${chord} is already used by ${owner}`, true);
+        } else {
+            try {
+                editor.bindShortcut(info.action, chord);
+                saveShortcuts(editor);
+                showToast(`${info.description}: ${chord}`);
+            } catch (error) {
+                showToast(error instanceof Error ? error.message : String(error), true);
+            }
+        }
+    }
+    renderShortcutList();
+}
+
+/** Go back to the default bindings. */
+export function resetShortcuts(): void {
+    if (!state.editor) return;
+    state.editor.resetShortcuts();
+    saveShortcuts(state.editor);
+    renderShortcutList();
+    showToast('Shortcuts reset');
+}
@@ -30,6 +30,8 @@ export interface AppState {
     loadingOverlay: HTMLElement | null;
     canvasContainer: HTMLElement | null;
     cursorIndicator: HTMLElement | null;
+    minimapCanvas: HTMLCanvasElement | null;
+    lastMinimapRender: number;
     gridSizeEl: HTMLElement | null;
     cursorPosEl: HTMLElement | null;
     zoomLevelEl: HTMLElement | null;
@@ -46,6 +48,7 @@ export interface AppState {
     clearBtn: HTMLButtonElement | null;
     helpBtn: HTMLButtonElement | null;
     borderStyleSelect: HTMLSelectElement | null;
+    previewModeSelect: HTMLSelectElement | null;
     toolButtons: NodeListOf<Element> | null;
     zoomFitBtn: HTMLButtonElement | null;
     zoomResetBtn: HTMLButtonElement | null;
@@ -84,6 +87,8 @@ export const state: AppState = {
     loadingOverlay: null,
     canvasContainer: null,
     cursorIndicator: null,
+    minimapCanvas: null,
+    lastMinimapRender: 0,
     gridSizeEl: null,
     cursorPosEl: null,
     zoomLevelEl: null,
@@ -100,6 +105,7 @@ export const state: AppState = {
     clearBtn: null,
     helpBtn: null,
     borderStyleSelect: null,
+    previewModeSelect: null,
     toolButtons: null,
     zoomFitBtn: null,
     zoomResetBtn: null,